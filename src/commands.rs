@@ -1,9 +1,10 @@
 //! Commands available to use on the HD44780 device.
 
 use crate::instructions::*;
+use crate::state::*;
+use core::fmt;
 
-/// Commands every driver must support
-/// /// Commands that only require a write capable bus
+/// Commands every driver must support. They only require a write capable bus.
 pub trait Driver {
     type Error;
 
@@ -26,7 +27,21 @@ pub trait Driver {
         display_shift: AccompaniesDisplayShift,
     ) -> Result<(), Self::Error>;
 
-    // todo! individual entry mode settings
+    /// Current entry mode settings.
+    fn current_entry_mode(&self) -> EntryMode;
+
+    /// Sets the cursor move direction, keeping the display shift setting.
+    fn set_cursor_direction(&mut self, direction: IncrementDecrement) -> Result<(), Self::Error> {
+        let entry_mode = self.current_entry_mode();
+        self.set_entry_mode(direction, entry_mode.display_shift)
+    }
+
+    /// Shifts the display with each character written so the cursor appears to stay still,
+    /// keeping the cursor direction setting.
+    fn set_autoscroll(&mut self, autoscroll: bool) -> Result<(), Self::Error> {
+        let entry_mode = self.current_entry_mode();
+        self.set_entry_mode(entry_mode.cursor_direction, autoscroll.into())
+    }
 
     /// Sets entire display (D) on/off, cursor on/off (C), and blinking of cursor position character
     /// (B).
@@ -37,7 +52,32 @@ pub trait Driver {
         cursor_blink: Blink,
     ) -> Result<(), Self::Error>;
 
-    // todo! individual display control settings
+    /// Current display control settings.
+    fn current_display_control(&self) -> DisplayControl;
+
+    /// Turns the entire display on or off, keeping the cursor and blink settings. DDRAM contents
+    /// remain unchanged.
+    fn display_on(&mut self, on: bool) -> Result<(), Self::Error> {
+        let display_control = self.current_display_control();
+        self.set_display_control(on.into(), display_control.cursor, display_control.blink)
+    }
+
+    /// Shows or hides the cursor, keeping the display and blink settings.
+    fn show_cursor(&mut self, show: bool) -> Result<(), Self::Error> {
+        let display_control = self.current_display_control();
+        self.set_display_control(display_control.display, show.into(), display_control.blink)
+    }
+
+    /// Turns blinking of the cursor position character on or off, keeping the display and cursor
+    /// settings.
+    fn blink(&mut self, blink: bool) -> Result<(), Self::Error> {
+        let display_control = self.current_display_control();
+        self.set_display_control(
+            display_control.display,
+            display_control.cursor,
+            blink.into(),
+        )
+    }
 
     // todo! could provide mutation methods for cursor/display shift like entry mode etc.
 
@@ -47,7 +87,8 @@ pub trait Driver {
     /// Move the display left or right once.
     fn shift_display(&mut self, direction: ShiftDirection) -> Result<(), Self::Error>;
 
-    /// Sets interface data length (DL), number of display lines (N), and character font (F).
+    /// Sets interface data length (DL), number of display lines (N), and character font (F). If the
+    /// cursor is at an address that does not exist in the new line mode it is moved to address 0.
     fn function_set(
         &mut self,
         data_length: DataLength,
@@ -55,23 +96,48 @@ pub trait Driver {
         font: CharacterFont,
     ) -> Result<(), Self::Error>;
 
-    // todo! function set methods
+    /// Current function set settings.
+    fn current_function_set(&self) -> FunctionSet;
+
+    /// Sets the number of display lines, keeping the data length and font settings.
+    fn set_num_lines(&mut self, num_lines: NumberOfDisplayLines) -> Result<(), Self::Error> {
+        let function_set = self.current_function_set();
+        self.function_set(function_set.data_length, num_lines, function_set.char_font)
+    }
+
+    /// Sets the character font, keeping the data length and number of display lines settings.
+    fn set_font(&mut self, font: CharacterFont) -> Result<(), Self::Error> {
+        let function_set = self.current_function_set();
+        self.function_set(function_set.data_length, function_set.num_lines, font)
+    }
 
     /// Sets CGRAM address. CGRAM data is sent and received after this setting.
     fn set_cgram_address(&mut self, address: u8) -> Result<(), Self::Error>;
 
     /// Sets DDRAM address, moving the cursor to the specified position. The next character written
     /// will appear at this position. DDRAM data is sent and received after this setting. Line 2
-    /// begins at address 40.
+    /// begins at address 0x40. Returns an error if the address does not exist in the current
+    /// display line mode.
     ///
     /// ```rust, ignore
-    /// lcd.position(5);        // Line 1, column 5
-    /// lcd.write_char('X');    // 'X' at line 1, column 5
-    /// lcd.position(45);       // Line 2, column 5
-    /// lcd.write_char('Y');    // 'Y' at line 2, column 5, right below 'X'
+    /// lcd.set_position(5);        // Line 1, column 5
+    /// lcd.write_char('X');        // 'X' at line 1, column 5
+    /// lcd.set_position(0x45);     // Line 2, column 5
+    /// lcd.write_char('Y');        // 'Y' at line 2, column 5, right below 'X'
     /// ```
     fn set_position(&mut self, address: u8) -> Result<(), Self::Error>;
 
+    /// Moves the cursor to the character at `row`, `col` (both zero-based) of the display geometry.
+    /// Returns an error if the position is off the panel.
+    ///
+    /// ```rust, ignore
+    /// lcd.set_cursor(0, 5);       // Row 1, column 5
+    /// lcd.write_char('X');
+    /// lcd.set_cursor(3, 5);       // Row 4, column 5 (address 0x59 on a 20x4 display)
+    /// lcd.write_char('Y');
+    /// ```
+    fn set_cursor(&mut self, row: u8, col: u8) -> Result<(), Self::Error>;
+
     /// Writes a byte of data into DDRAM or CGRAM. Type is selected by setting either DDRAM or CGRAM
     /// address. Note: character will be truncated to fit into u8.
     fn write_char(&mut self, data: char) -> Result<(), Self::Error>;
@@ -86,7 +152,80 @@ pub trait Driver {
     /// Writes a series of bytes to the display.
     fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Self::Error>;
 
-    // todo! cgram stuff
+    /// Current DDRAM address, i.e. the position the next character will be written to.
+    fn cursor_address(&self) -> u8;
+
+    /// Defines the glyph of custom character `slot` (0-7) for the 5x8 font. Each row is the lower
+    /// 5 bits of a byte, top row first, with the last row shared with the cursor. The character is
+    /// then displayed by writing byte `slot`. The cursor position is restored afterwards.
+    ///
+    /// ```rust, ignore
+    /// let bell = [0x04, 0x0E, 0x0E, 0x0E, 0x1F, 0x00, 0x04, 0x00];
+    /// lcd.define_char(0, bell);
+    /// lcd.write_byte(0);
+    /// ```
+    fn define_char(&mut self, slot: u8, rows: [u8; 8]) -> Result<(), Self::Error> {
+        let address = self.cursor_address();
+        // Slots past the end of CGRAM are rejected by set_cgram_address
+        self.set_cgram_address(slot.saturating_mul(8))?;
+        self.write_bytes(&rows)?;
+        self.set_position(address)
+    }
+
+    /// Defines the glyph of custom character `slot` (0-3) for the 5x10 font. Each row is the lower
+    /// 5 bits of a byte, top row first, with the last row shared with the cursor. The cursor
+    /// position is restored afterwards.
+    fn define_char_5x10(&mut self, slot: u8, rows: [u8; 11]) -> Result<(), Self::Error> {
+        let address = self.cursor_address();
+        // Each 5x10 character occupies 16 bytes of CGRAM
+        self.set_cgram_address(slot.saturating_mul(16))?;
+        self.write_bytes(&rows)?;
+        self.set_position(address)
+    }
+
+    /// Borrow the driver as a `core::fmt::Write` implementor so `write!` can be used.
+    ///
+    /// ```rust, ignore
+    /// let mut writer = lcd.writer();
+    /// write!(writer, "{:>5.1}C", temp)?;
+    /// ```
+    fn writer(&mut self) -> FmtWriter<'_, Self>
+    where
+        Self: Sized,
+    {
+        FmtWriter::new(self)
+    }
+}
+
+/// Adapter implementing `core::fmt::Write` for any driver. When writing fails, the driver error is
+/// kept so it can be retrieved after `write!` returns `fmt::Error`.
+pub struct FmtWriter<'a, T: Driver> {
+    driver: &'a mut T,
+    error: Option<T::Error>,
+}
+
+impl<'a, T: Driver> FmtWriter<'a, T> {
+    /// Wrap a driver.
+    pub fn new(driver: &'a mut T) -> Self {
+        FmtWriter {
+            driver,
+            error: None,
+        }
+    }
+
+    /// Take the driver error that caused the last `fmt::Error`, if any.
+    pub fn take_error(&mut self) -> Option<T::Error> {
+        self.error.take()
+    }
+}
+
+impl<T: Driver> fmt::Write for FmtWriter<'_, T> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        Driver::write_str(self.driver, s).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
 }
 
 /// Commands that require a read-write bus
@@ -99,3 +238,171 @@ pub trait ReadableDriver {
     /// Reads data from DDRAM or CGRAM. Type is selected by setting either DDRAM or CGRAM address.
     fn read_data(&mut self) -> Result<u8, Self::Error>;
 }
+
+/// Async commands that only require a write capable bus. Mirrors [`Driver`], with each command
+/// awaiting the bus transfers and the execution time of the device.
+///
+/// ```rust, ignore
+/// lcd.set_cursor(1, 0).await?;
+/// lcd.write_str("Hello").await?;
+/// ```
+#[cfg(feature = "async")]
+#[allow(async_fn_in_trait)]
+pub trait AsyncDriver {
+    type Error;
+
+    /// Clears entire display and sets DDRAM address 0 in address counter.
+    async fn clear_display(&mut self) -> Result<(), Self::Error>;
+
+    /// Sets DDRAM address 0 in address counter. Also returns display from being shifted to original
+    /// position. DDRAM contents remain unchanged.
+    async fn return_home(&mut self) -> Result<(), Self::Error>;
+
+    /// Sets cursor move direction and specifies display shift. These operations are performed
+    /// during data write and read.
+    async fn set_entry_mode(
+        &mut self,
+        direction: IncrementDecrement,
+        display_shift: AccompaniesDisplayShift,
+    ) -> Result<(), Self::Error>;
+
+    /// Current entry mode settings.
+    fn current_entry_mode(&self) -> EntryMode;
+
+    /// Sets the cursor move direction, keeping the display shift setting.
+    async fn set_cursor_direction(
+        &mut self,
+        direction: IncrementDecrement,
+    ) -> Result<(), Self::Error> {
+        let entry_mode = self.current_entry_mode();
+        self.set_entry_mode(direction, entry_mode.display_shift)
+            .await
+    }
+
+    /// Shifts the display with each character written so the cursor appears to stay still,
+    /// keeping the cursor direction setting.
+    async fn set_autoscroll(&mut self, autoscroll: bool) -> Result<(), Self::Error> {
+        let entry_mode = self.current_entry_mode();
+        self.set_entry_mode(entry_mode.cursor_direction, autoscroll.into())
+            .await
+    }
+
+    /// Sets entire display (D) on/off, cursor on/off (C), and blinking of cursor position character
+    /// (B).
+    async fn set_display_control(
+        &mut self,
+        display_on: ShowDisplay,
+        cursor_displayed: ShowCursor,
+        cursor_blink: Blink,
+    ) -> Result<(), Self::Error>;
+
+    /// Current display control settings.
+    fn current_display_control(&self) -> DisplayControl;
+
+    /// Turns the entire display on or off, keeping the cursor and blink settings. DDRAM contents
+    /// remain unchanged.
+    async fn display_on(&mut self, on: bool) -> Result<(), Self::Error> {
+        let display_control = self.current_display_control();
+        self.set_display_control(on.into(), display_control.cursor, display_control.blink)
+            .await
+    }
+
+    /// Shows or hides the cursor, keeping the display and blink settings.
+    async fn show_cursor(&mut self, show: bool) -> Result<(), Self::Error> {
+        let display_control = self.current_display_control();
+        self.set_display_control(display_control.display, show.into(), display_control.blink)
+            .await
+    }
+
+    /// Turns blinking of the cursor position character on or off, keeping the display and cursor
+    /// settings.
+    async fn blink(&mut self, blink: bool) -> Result<(), Self::Error> {
+        let display_control = self.current_display_control();
+        self.set_display_control(
+            display_control.display,
+            display_control.cursor,
+            blink.into(),
+        )
+        .await
+    }
+
+    /// Move the cursor left or right once.
+    async fn shift_cursor(&mut self, direction: ShiftDirection) -> Result<(), Self::Error>;
+
+    /// Move the display left or right once.
+    async fn shift_display(&mut self, direction: ShiftDirection) -> Result<(), Self::Error>;
+
+    /// Sets interface data length (DL), number of display lines (N), and character font (F). If the
+    /// cursor is at an address that does not exist in the new line mode it is moved to address 0.
+    async fn function_set(
+        &mut self,
+        data_length: DataLength,
+        num_lines: NumberOfDisplayLines,
+        font: CharacterFont,
+    ) -> Result<(), Self::Error>;
+
+    /// Current function set settings.
+    fn current_function_set(&self) -> FunctionSet;
+
+    /// Sets the number of display lines, keeping the data length and font settings.
+    async fn set_num_lines(&mut self, num_lines: NumberOfDisplayLines) -> Result<(), Self::Error> {
+        let function_set = self.current_function_set();
+        self.function_set(function_set.data_length, num_lines, function_set.char_font)
+            .await
+    }
+
+    /// Sets the character font, keeping the data length and number of display lines settings.
+    async fn set_font(&mut self, font: CharacterFont) -> Result<(), Self::Error> {
+        let function_set = self.current_function_set();
+        self.function_set(function_set.data_length, function_set.num_lines, font)
+            .await
+    }
+
+    /// Sets CGRAM address. CGRAM data is sent and received after this setting.
+    async fn set_cgram_address(&mut self, address: u8) -> Result<(), Self::Error>;
+
+    /// Sets DDRAM address, moving the cursor to the specified position. Returns an error if the
+    /// address does not exist in the current display line mode.
+    async fn set_position(&mut self, address: u8) -> Result<(), Self::Error>;
+
+    /// Moves the cursor to the character at `row`, `col` (both zero-based) of the display geometry.
+    /// Returns an error if the position is off the panel.
+    async fn set_cursor(&mut self, row: u8, col: u8) -> Result<(), Self::Error>;
+
+    /// Writes a byte of data into DDRAM or CGRAM. Type is selected by setting either DDRAM or CGRAM
+    /// address. Note: character will be truncated to fit into u8.
+    async fn write_char(&mut self, data: char) -> Result<(), Self::Error>;
+
+    /// Writes a string of data to the display. Note: each character in the string is written as the
+    /// corresponding byte and will be truncated if does not fit into u8.
+    async fn write_str(&mut self, str: &str) -> Result<(), Self::Error>;
+
+    /// Write a byte of data to the display.
+    async fn write_byte(&mut self, byte: u8) -> Result<(), Self::Error>;
+
+    /// Writes a series of bytes to the display.
+    async fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), Self::Error>;
+
+    /// Current DDRAM address, i.e. the position the next character will be written to.
+    fn cursor_address(&self) -> u8;
+
+    /// Defines the glyph of custom character `slot` (0-7) for the 5x8 font. The cursor position is
+    /// restored afterwards.
+    async fn define_char(&mut self, slot: u8, rows: [u8; 8]) -> Result<(), Self::Error> {
+        let address = self.cursor_address();
+        // Slots past the end of CGRAM are rejected by set_cgram_address
+        self.set_cgram_address(slot.saturating_mul(8)).await?;
+        self.write_bytes(&rows).await?;
+        self.set_position(address).await
+    }
+
+    /// Defines the glyph of custom character `slot` (0-3) for the 5x10 font. The cursor position
+    /// is restored afterwards.
+    async fn define_char_5x10(&mut self, slot: u8, rows: [u8; 11]) -> Result<(), Self::Error> {
+        let address = self.cursor_address();
+        // Each 5x10 character occupies 16 bytes of CGRAM
+        self.set_cgram_address(slot.saturating_mul(16)).await?;
+        self.write_bytes(&rows).await?;
+        self.set_position(address).await
+    }
+}
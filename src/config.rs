@@ -0,0 +1,125 @@
+//! Configuration applied to the device during initialization.
+
+use crate::geometry::Geometry;
+use crate::instructions::*;
+use crate::state::*;
+use crate::timing::Timing;
+
+/// Initial device configuration, used by every driver constructor regardless of bus type. Built by
+/// chaining setters onto the default configuration.
+///
+/// ```rust, ignore
+/// let config = Hd44780Config::new()
+///     .geometry(Geometry::lcd_20x4())
+///     .display_control(ShowDisplay::On, ShowCursor::Off, Blink::Off)
+///     .timing(Timing::three_volt());
+/// let lcd = WriteOnlyHD44780::new_bus4(en, rs, d4, d5, d6, d7, delay, config)?;
+/// ```
+#[derive(Copy, Clone)]
+pub struct Hd44780Config {
+    pub(crate) function_set: FunctionSet,
+    pub(crate) entry_mode: EntryMode,
+    pub(crate) display_control: DisplayControl,
+    pub(crate) clear_on_init: bool,
+    pub(crate) timing: Timing,
+    pub(crate) geometry: Geometry,
+}
+
+impl Hd44780Config {
+    /// Default configuration: 16x2 display, 8-bit interface, 5x8 font, cursor incrementing without
+    /// display shift, display on with the cursor hidden, and the display cleared on init.
+    pub fn new() -> Self {
+        Hd44780Config {
+            function_set: FunctionSet {
+                data_length: DataLength::EightBits,
+                num_lines: NumberOfDisplayLines::TwoLines,
+                char_font: CharacterFont::FiveByEight,
+            },
+            entry_mode: EntryMode {
+                cursor_direction: IncrementDecrement::Increment,
+                display_shift: AccompaniesDisplayShift::NoShift,
+            },
+            display_control: DisplayControl {
+                display: ShowDisplay::On,
+                cursor: ShowCursor::Off,
+                blink: Blink::Off,
+            },
+            clear_on_init: true,
+            timing: Timing::default(),
+            geometry: Geometry::lcd_16x2(),
+        }
+    }
+
+    /// Interface data length. Constructors for buses that only support one width override this to
+    /// match the bus.
+    pub fn data_length(mut self, data_length: DataLength) -> Self {
+        self.function_set.data_length = data_length;
+        self
+    }
+
+    /// Number of display lines.
+    pub fn num_lines(mut self, num_lines: NumberOfDisplayLines) -> Self {
+        self.function_set.num_lines = num_lines;
+        self
+    }
+
+    /// Character font.
+    pub fn font(mut self, font: CharacterFont) -> Self {
+        self.function_set.char_font = font;
+        self
+    }
+
+    /// Cursor move direction and display shift applied after initialization.
+    pub fn entry_mode(
+        mut self,
+        direction: IncrementDecrement,
+        display_shift: AccompaniesDisplayShift,
+    ) -> Self {
+        self.entry_mode = EntryMode {
+            cursor_direction: direction,
+            display_shift,
+        };
+        self
+    }
+
+    /// Display, cursor and blink settings applied after initialization.
+    pub fn display_control(
+        mut self,
+        display_on: ShowDisplay,
+        cursor_displayed: ShowCursor,
+        cursor_blink: Blink,
+    ) -> Self {
+        self.display_control = DisplayControl {
+            display: display_on,
+            cursor: cursor_displayed,
+            blink: cursor_blink,
+        };
+        self
+    }
+
+    /// Whether to clear the display during initialization.
+    pub fn clear_on_init(mut self, clear: bool) -> Self {
+        self.clear_on_init = clear;
+        self
+    }
+
+    /// Size and row layout of the display. Also sets the number of display lines required by the
+    /// geometry.
+    pub fn geometry(mut self, geometry: Geometry) -> Self {
+        self.geometry = geometry;
+        self.function_set.num_lines = geometry.num_lines();
+        self
+    }
+
+    /// Timing profile used by the bus and driver.
+    pub fn timing(mut self, timing: Timing) -> Self {
+        self.timing = timing;
+        self
+    }
+}
+
+impl Default for Hd44780Config {
+    fn default() -> Self {
+        Hd44780Config::new()
+    }
+}
@@ -0,0 +1,264 @@
+//! KS0073 controller (Samsung), found on 20x4 and 12x4 modules such as the EA DIP204.
+//!
+//! Setting the RE bit of function set selects an extended instruction set with the extended
+//! function set (font width, inverting cursor and four-line mode), per line scroll enable and the
+//! scroll quantity. In four-line mode rows start at 0x00, 0x20, 0x40 and 0x60: configure the driver
+//! with `Geometry::four_line` so cursor positioning and address tracking follow this layout.
+//! Change the number of lines with `set_num_lines` or `function_set` of the profile, which also
+//! switch four-line mode (NW) of the extended function set.
+//!
+//! The font of the KS0073 is fixed at 5x8 and RE takes the place of the font bit (F) of the
+//! HD44780, so the font must be left at `CharacterFont::FiveByEight`.
+
+use crate::commands::Driver;
+use crate::data_bus::DataBus;
+use crate::hal::Delay;
+use crate::instructions::*;
+use crate::state::ExtendedFunctionSet;
+use crate::{DataBusError, WriteOnlyHD44780};
+use core::ops::{Deref, DerefMut};
+
+/// Extended instruction set select bit of function set.
+const FUNCTION_SET_RE: u8 = 1 << 2;
+
+/// Dot scroll enable bit of function set (RE = 0).
+const FUNCTION_SET_DH: u8 = 1 << 1;
+
+/// Largest horizontal scroll quantity, in dots.
+pub const MAX_SCROLL_QUANTITY: u8 = 48;
+
+/// KS0073 controller. Derefs to the driver, so all standard commands remain available.
+///
+/// ```rust, ignore
+/// let config = Hd44780Config::new().geometry(Geometry::lcd_20x4_four_line());
+/// let lcd = WriteOnlyHD44780::new_bus4(en, rs, d4, d5, d6, d7, delay, config)?;
+/// let mut lcd = Ks0073::new(lcd)?;
+/// lcd.set_cursor(3, 0)?;
+/// lcd.write_str("Row 4")?;
+/// ```
+pub struct Ks0073<B, D>
+where
+    B: DataBus,
+    D: Delay<B::Hal>,
+{
+    lcd: WriteOnlyHD44780<B, D>,
+    extended_function_set: ExtendedFunctionSet,
+}
+
+impl<B, D, E> Ks0073<B, D>
+where
+    B: DataBus<Error = DataBusError<E>>,
+    D: Delay<B::Hal>,
+{
+    /// Take an initialized driver and send the extended function set. Four-line mode is enabled
+    /// when the driver is configured for four lines.
+    pub fn new(lcd: WriteOnlyHD44780<B, D>) -> Result<Ks0073<B, D>, DataBusError<E>> {
+        let extended_function_set = ExtendedFunctionSet {
+            four_lines: matches!(lcd.function_set.num_lines, NumberOfDisplayLines::FourLines),
+            ..Default::default()
+        };
+        let mut ks0073 = Ks0073 {
+            lcd,
+            extended_function_set,
+        };
+        ks0073.write_extended(&[
+            ExtendedOpcodes::ExtendedFunctionSet as u8 | extended_function_set.as_byte()
+        ])?;
+        Ok(ks0073)
+    }
+
+    /// Destroy the controller profile and return the driver.
+    pub fn release(self) -> WriteOnlyHD44780<B, D> {
+        self.lcd
+    }
+
+    /// Current extended function set settings.
+    pub fn current_extended_function_set(&self) -> ExtendedFunctionSet {
+        self.extended_function_set
+    }
+
+    /// Set the character width and turn the black and white inverting cursor on or off.
+    pub fn set_extended_function_set(
+        &mut self,
+        font_width: FontWidth,
+        inverting_cursor: bool,
+    ) -> Result<(), DataBusError<E>> {
+        let extended_function_set = ExtendedFunctionSet {
+            font_width,
+            inverting_cursor,
+            ..self.extended_function_set
+        };
+        self.write_extended(&[
+            ExtendedOpcodes::ExtendedFunctionSet as u8 | extended_function_set.as_byte()
+        ])?;
+        self.extended_function_set = extended_function_set;
+        Ok(())
+    }
+
+    /// Function set through the driver, followed by the extended function set when entering or
+    /// leaving four-line mode.
+    pub fn function_set(
+        &mut self,
+        data_length: DataLength,
+        num_lines: NumberOfDisplayLines,
+        font: CharacterFont,
+    ) -> Result<(), DataBusError<E>> {
+        self.lcd.function_set(data_length, num_lines, font)?;
+        let four_lines = matches!(num_lines, NumberOfDisplayLines::FourLines);
+        if four_lines != self.extended_function_set.four_lines {
+            let extended_function_set = ExtendedFunctionSet {
+                four_lines,
+                ..self.extended_function_set
+            };
+            self.write_extended(&[
+                ExtendedOpcodes::ExtendedFunctionSet as u8 | extended_function_set.as_byte()
+            ])?;
+            self.extended_function_set = extended_function_set;
+        }
+        Ok(())
+    }
+
+    /// Set the number of display lines, entering or leaving four-line mode as needed.
+    pub fn set_num_lines(
+        &mut self,
+        num_lines: NumberOfDisplayLines,
+    ) -> Result<(), DataBusError<E>> {
+        let function_set = self.lcd.current_function_set();
+        self.function_set(function_set.data_length, num_lines, function_set.char_font)
+    }
+
+    /// Select the rows that scroll, one bit per row with row 1 in the lowest bit. Rows that are not
+    /// enabled stay in place on a display shift or scroll.
+    pub fn set_scroll_enable(&mut self, rows: u8) -> Result<(), DataBusError<E>> {
+        self.write_extended(&[ExtendedOpcodes::ScrollEnable as u8 | rows & 0x0F])
+    }
+
+    /// Scroll the enabled rows horizontally by `dots` (0-48, larger values are limited to 48), or
+    /// return to shifting them by whole characters with display shift with `None`. The dot scroll
+    /// bit (DH) is kept when function set is sent through the driver.
+    pub fn set_scroll_quantity(&mut self, dots: Option<u8>) -> Result<(), DataBusError<E>> {
+        let dh = match dots {
+            Some(dots) => {
+                self.write_extended(&[
+                    ExtendedOpcodes::ScrollQuantity as u8 | dots.min(MAX_SCROLL_QUANTITY)
+                ])?;
+                FUNCTION_SET_DH
+            }
+            None => 0,
+        };
+        self.lcd.set_function_set_extension(dh)
+    }
+
+    /// Write instructions from the extended instruction set, returning to the fundamental set
+    /// afterwards.
+    fn write_extended(&mut self, instructions: &[u8]) -> Result<(), DataBusError<E>> {
+        self.enter_extended()?;
+        let result = instructions
+            .iter()
+            .try_for_each(|instruction| self.lcd.write_instruction(*instruction));
+        self.leave_extended()?;
+        result
+    }
+
+    /// Function set with RE set. The bits below RE (CGRAM blink, low power) are left clear.
+    fn enter_extended(&mut self) -> Result<(), DataBusError<E>> {
+        let function_set = self.lcd.function_set.as_byte() & !FUNCTION_SET_RE;
+        self.lcd
+            .write_instruction(Opcodes::FunctionSet as u8 | function_set | FUNCTION_SET_RE)
+    }
+
+    /// Function set with RE clear, as last set through the driver and with dot scroll kept.
+    fn leave_extended(&mut self) -> Result<(), DataBusError<E>> {
+        let function_set = self.lcd.function_set_operands() & !FUNCTION_SET_RE;
+        self.lcd
+            .write_instruction(Opcodes::FunctionSet as u8 | function_set)
+    }
+}
+
+impl<B, D> Deref for Ks0073<B, D>
+where
+    B: DataBus,
+    D: Delay<B::Hal>,
+{
+    type Target = WriteOnlyHD44780<B, D>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.lcd
+    }
+}
+
+impl<B, D> DerefMut for Ks0073<B, D>
+where
+    B: DataBus,
+    D: Delay<B::Hal>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.lcd
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Hd44780Config;
+    use crate::geometry::Geometry;
+    use crate::mock::{Delay, Pin, Port};
+    use crate::WriteOnlyBus4;
+
+    extern crate std;
+    use std::vec::Vec;
+
+    type Bus4 = WriteOnlyBus4<Pin, Pin, Pin, Pin, Pin, Pin>;
+
+    /// Four-line driver on a 4-bit bus with E on bit 0, RS on bit 1 and D4-D7 on bits 2-5.
+    fn four_line_lcd(port: &Port) -> WriteOnlyHD44780<Bus4, Delay> {
+        WriteOnlyHD44780::new_bus4(
+            port.pin(0),
+            port.pin(1),
+            port.pin(2),
+            port.pin(3),
+            port.pin(4),
+            port.pin(5),
+            Delay::default(),
+            Hd44780Config::new().geometry(Geometry::lcd_20x4_four_line()),
+        )
+        .unwrap()
+    }
+
+    /// Instructions latched from nibble `from` on.
+    fn instructions(port: &Port, from: usize) -> Vec<u32> {
+        port.latched(0)[from..]
+            .chunks(2)
+            .map(|n| (n[0] >> 2 & 0x0F) << 4 | n[1] >> 2 & 0x0F)
+            .collect()
+    }
+
+    #[test]
+    fn four_line_mode_is_enabled_in_the_extended_function_set() {
+        let port = Port::default();
+        let lcd = four_line_lcd(&port);
+        let from = port.latched(0).len();
+
+        let lcd = Ks0073::new(lcd).unwrap();
+
+        assert_eq!(instructions(&port, from), [0x2C, 0x09, 0x28]);
+        assert!(lcd.current_extended_function_set().four_lines);
+    }
+
+    #[test]
+    fn dot_scroll_is_kept_by_function_set_through_the_driver() {
+        let port = Port::default();
+        let mut lcd = Ks0073::new(four_line_lcd(&port)).unwrap();
+        let from = port.latched(0).len();
+
+        lcd.set_scroll_quantity(Some(60)).unwrap();
+        lcd.set_num_lines(NumberOfDisplayLines::TwoLines).unwrap();
+        lcd.set_scroll_quantity(None).unwrap();
+
+        assert_eq!(
+            instructions(&port, from),
+            [0x2C, 0xB0, 0x28, 0x2A, 0x2A, 0x2C, 0x08, 0x2A, 0x28]
+        );
+        assert!(!lcd.current_extended_function_set().four_lines);
+    }
+}
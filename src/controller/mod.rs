@@ -0,0 +1,9 @@
+//! HD44780-compatible controllers with additional instructions. Each profile wraps a driver,
+//! derefs to it for the standard commands and adds the extended commands of the controller.
+
+pub mod ks0073;
+pub mod pt6314;
+pub mod ssd1803a;
+pub mod st7032;
+pub mod us2066;
+pub mod ws0010;
@@ -0,0 +1,148 @@
+//! PT6314 VFD controller (Princeton Technology) and Noritake CU-series VFD modules.
+//!
+//! The two lowest bits of function set, unused on the HD44780, select the display brightness. Use
+//! `Timing::vfd` for the driver, as the controller is held in reset until the high voltage converter
+//! has started.
+
+use crate::data_bus::DataBus;
+use crate::hal::Delay;
+use crate::instructions::*;
+use crate::{DataBusError, WriteOnlyHD44780};
+use core::ops::{Deref, DerefMut};
+
+/// PT6314 controller. Derefs to the driver, so all standard commands remain available.
+///
+/// ```rust, ignore
+/// let config = Hd44780Config::new().timing(Timing::vfd());
+/// let lcd = WriteOnlyHD44780::new_bus4(en, rs, d4, d5, d6, d7, delay, config)?;
+/// let mut vfd = Pt6314::new(lcd, Brightness::Half)?;
+/// vfd.set_brightness(Brightness::Full)?;
+/// ```
+pub struct Pt6314<B, D>
+where
+    B: DataBus,
+    D: Delay<B::Hal>,
+{
+    lcd: WriteOnlyHD44780<B, D>,
+    brightness: Brightness,
+}
+
+impl<B, D, E> Pt6314<B, D>
+where
+    B: DataBus<Error = DataBusError<E>>,
+    D: Delay<B::Hal>,
+{
+    /// Take an initialized driver and set the brightness.
+    pub fn new(
+        lcd: WriteOnlyHD44780<B, D>,
+        brightness: Brightness,
+    ) -> Result<Pt6314<B, D>, DataBusError<E>> {
+        let mut pt6314 = Pt6314 { lcd, brightness };
+        pt6314.set_brightness(brightness)?;
+        Ok(pt6314)
+    }
+
+    /// Destroy the controller profile and return the driver.
+    pub fn release(self) -> WriteOnlyHD44780<B, D> {
+        self.lcd
+    }
+
+    /// Set the brightness, keeping the other function set bits as last set through the driver. The
+    /// brightness bits (BR1, BR0) are kept when function set is sent through the driver.
+    pub fn set_brightness(&mut self, brightness: Brightness) -> Result<(), DataBusError<E>> {
+        self.lcd.set_function_set_extension(brightness as u8)?;
+        self.brightness = brightness;
+        Ok(())
+    }
+
+    /// Current brightness.
+    pub fn brightness(&self) -> Brightness {
+        self.brightness
+    }
+}
+
+impl<B, D> Deref for Pt6314<B, D>
+where
+    B: DataBus,
+    D: Delay<B::Hal>,
+{
+    type Target = WriteOnlyHD44780<B, D>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.lcd
+    }
+}
+
+impl<B, D> DerefMut for Pt6314<B, D>
+where
+    B: DataBus,
+    D: Delay<B::Hal>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.lcd
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::Driver;
+    use crate::config::Hd44780Config;
+    use crate::mock::{Delay, Pin, Port};
+    use crate::timing::Timing;
+    use crate::WriteOnlyBus4;
+
+    extern crate std;
+    use std::vec::Vec;
+
+    type Bus4 = WriteOnlyBus4<Pin, Pin, Pin, Pin, Pin, Pin>;
+
+    /// VFD on a 4-bit bus with E on bit 0, RS on bit 1 and D4-D7 on bits 2-5.
+    fn pt6314(port: &Port) -> Pt6314<Bus4, Delay> {
+        let lcd = WriteOnlyHD44780::new_bus4(
+            port.pin(0),
+            port.pin(1),
+            port.pin(2),
+            port.pin(3),
+            port.pin(4),
+            port.pin(5),
+            Delay::default(),
+            Hd44780Config::new().timing(Timing::vfd()),
+        )
+        .unwrap();
+        Pt6314::new(lcd, Brightness::Half).unwrap()
+    }
+
+    /// Instructions latched from nibble `from` on.
+    fn instructions(port: &Port, from: usize) -> Vec<u32> {
+        port.latched(0)[from..]
+            .chunks(2)
+            .map(|n| (n[0] >> 2 & 0x0F) << 4 | n[1] >> 2 & 0x0F)
+            .collect()
+    }
+
+    #[test]
+    fn brightness_is_sent_with_function_set() {
+        let port = Port::default();
+        let mut vfd = pt6314(&port);
+        let from = port.latched(0).len();
+
+        vfd.set_brightness(Brightness::Quarter).unwrap();
+
+        assert_eq!(instructions(&port, from - 2), [0x2A, 0x2B]);
+        assert!(matches!(vfd.brightness(), Brightness::Quarter));
+    }
+
+    #[test]
+    fn brightness_is_kept_by_function_set_through_the_driver() {
+        let port = Port::default();
+        let mut vfd = pt6314(&port);
+        let from = port.latched(0).len();
+
+        vfd.set_num_lines(NumberOfDisplayLines::OneLine).unwrap();
+        vfd.set_font(CharacterFont::FiveByTen).unwrap();
+
+        assert_eq!(instructions(&port, from), [0x22, 0x26]);
+        assert!(matches!(vfd.brightness(), Brightness::Half));
+    }
+}
@@ -0,0 +1,336 @@
+//! SSD1803A controller (Solomon Systech), found on 20x4 and 12x4 COG modules.
+//!
+//! Like the US2066, the RE bit of function set selects an extended instruction set with the
+//! extended function set (font width, inverting cursor and four-line mode) and double height.
+//! The IS bit selects a further table with the bias and per line scroll enable. In four-line mode
+//! rows start at 0x00, 0x20, 0x40 and 0x60: configure the driver with `Geometry::four_line` so
+//! cursor positioning and address tracking follow this layout. Change the number of lines with
+//! `set_num_lines` or `function_set` of the profile, which also switch four-line mode (NW) of the
+//! extended function set.
+
+use crate::commands::Driver;
+use crate::data_bus::DataBus;
+use crate::hal::Delay;
+use crate::instructions::*;
+use crate::state::ExtendedFunctionSet;
+use crate::{DataBusError, WriteOnlyHD44780};
+use core::ops::{Deref, DerefMut};
+
+/// Extended instruction set select bit of function set.
+const FUNCTION_SET_RE: u8 = 1 << 1;
+
+/// Double height bit of function set (RE = 0), in place of the font bit of the HD44780.
+const FUNCTION_SET_DH: u8 = 1 << 2;
+
+/// Double height/bias instruction (RE = 1, IS = 0).
+const DOUBLE_HEIGHT_BIAS_OPCODE: u8 = 0x10;
+
+/// Internal oscillator instruction (RE = 0, IS = 1), which also holds the lower bias bit.
+const OSCILLATOR_OPCODE: u8 = 0x10;
+
+/// Internal oscillator frequency adjustment (F2-F0) sent with the bias.
+const OSCILLATOR_FREQUENCY: u8 = 0x03;
+
+/// SSD1803A controller. Derefs to the driver, so all standard commands remain available.
+///
+/// ```rust, ignore
+/// let config = Hd44780Config::new().geometry(Geometry::lcd_20x4_four_line());
+/// let lcd = WriteOnlyHD44780::new_bus8(en, rs, d0, d1, d2, d3, d4, d5, d6, d7, delay, config)?;
+/// let mut lcd = Ssd1803a::new(lcd, BiasRatio::OneSixth)?;
+/// lcd.set_double_height(Some(DoubleHeight::Top))?;
+/// ```
+pub struct Ssd1803a<B, D>
+where
+    B: DataBus,
+    D: Delay<B::Hal>,
+{
+    lcd: WriteOnlyHD44780<B, D>,
+    extended_function_set: ExtendedFunctionSet,
+    double_height: Option<DoubleHeight>,
+    bias: BiasRatio,
+}
+
+impl<B, D, E> Ssd1803a<B, D>
+where
+    B: DataBus<Error = DataBusError<E>>,
+    D: Delay<B::Hal>,
+{
+    /// Take an initialized driver and send the extended function set and the bias. Four-line mode
+    /// is enabled when the driver is configured for four lines.
+    pub fn new(
+        lcd: WriteOnlyHD44780<B, D>,
+        bias: BiasRatio,
+    ) -> Result<Ssd1803a<B, D>, DataBusError<E>> {
+        let extended_function_set = ExtendedFunctionSet {
+            four_lines: matches!(lcd.function_set.num_lines, NumberOfDisplayLines::FourLines),
+            ..Default::default()
+        };
+        let mut ssd1803a = Ssd1803a {
+            lcd,
+            extended_function_set,
+            double_height: None,
+            bias,
+        };
+        ssd1803a.write_extended(&[
+            ExtendedOpcodes::ExtendedFunctionSet as u8 | extended_function_set.as_byte(),
+            double_height_bias_byte(None, bias),
+        ])?;
+        ssd1803a.write_table(&[oscillator_byte(bias)])?;
+        Ok(ssd1803a)
+    }
+
+    /// Destroy the controller profile and return the driver.
+    pub fn release(self) -> WriteOnlyHD44780<B, D> {
+        self.lcd
+    }
+
+    /// Current extended function set settings.
+    pub fn current_extended_function_set(&self) -> ExtendedFunctionSet {
+        self.extended_function_set
+    }
+
+    /// Set the character width and turn the black and white inverting cursor on or off.
+    pub fn set_extended_function_set(
+        &mut self,
+        font_width: FontWidth,
+        inverting_cursor: bool,
+    ) -> Result<(), DataBusError<E>> {
+        let extended_function_set = ExtendedFunctionSet {
+            font_width,
+            inverting_cursor,
+            ..self.extended_function_set
+        };
+        self.write_extended(&[
+            ExtendedOpcodes::ExtendedFunctionSet as u8 | extended_function_set.as_byte()
+        ])?;
+        self.extended_function_set = extended_function_set;
+        Ok(())
+    }
+
+    /// Function set through the driver, followed by the extended function set when entering or
+    /// leaving four-line mode.
+    pub fn function_set(
+        &mut self,
+        data_length: DataLength,
+        num_lines: NumberOfDisplayLines,
+        font: CharacterFont,
+    ) -> Result<(), DataBusError<E>> {
+        self.lcd.function_set(data_length, num_lines, font)?;
+        let four_lines = matches!(num_lines, NumberOfDisplayLines::FourLines);
+        if four_lines != self.extended_function_set.four_lines {
+            let extended_function_set = ExtendedFunctionSet {
+                four_lines,
+                ..self.extended_function_set
+            };
+            self.write_extended(&[
+                ExtendedOpcodes::ExtendedFunctionSet as u8 | extended_function_set.as_byte()
+            ])?;
+            self.extended_function_set = extended_function_set;
+        }
+        Ok(())
+    }
+
+    /// Set the number of display lines, entering or leaving four-line mode as needed.
+    pub fn set_num_lines(
+        &mut self,
+        num_lines: NumberOfDisplayLines,
+    ) -> Result<(), DataBusError<E>> {
+        let function_set = self.lcd.current_function_set();
+        self.function_set(function_set.data_length, num_lines, function_set.char_font)
+    }
+
+    /// Merge rows into double height lines, or return to normal height with `None`. The double
+    /// height bit (DH) takes the place of the font bit (F) of the HD44780 in function set and is
+    /// kept when function set is sent through the driver, so the font should be left at 5x8.
+    pub fn set_double_height(&mut self, mode: Option<DoubleHeight>) -> Result<(), DataBusError<E>> {
+        self.write_extended(&[double_height_bias_byte(mode, self.bias)])?;
+        let dh = if mode.is_some() { FUNCTION_SET_DH } else { 0 };
+        self.lcd.set_function_set_extension(dh)?;
+        self.double_height = mode;
+        Ok(())
+    }
+
+    /// Current double height mode, `None` at normal height.
+    pub fn double_height(&self) -> Option<DoubleHeight> {
+        self.double_height
+    }
+
+    /// Set the bias of the LCD voltage.
+    pub fn set_bias(&mut self, bias: BiasRatio) -> Result<(), DataBusError<E>> {
+        self.write_extended(&[double_height_bias_byte(self.double_height, bias)])?;
+        self.write_table(&[oscillator_byte(bias)])?;
+        self.bias = bias;
+        Ok(())
+    }
+
+    /// Current bias of the LCD voltage.
+    pub fn bias(&self) -> BiasRatio {
+        self.bias
+    }
+
+    /// Select the rows that move on a display shift, one bit per row with row 1 in the lowest bit.
+    /// Rows that are not enabled stay in place.
+    pub fn set_scroll_enable(&mut self, rows: u8) -> Result<(), DataBusError<E>> {
+        // IS is kept while RE is set, selecting scroll enable in place of double height/bias
+        self.select_table(InstructionTable::Extended)?;
+        self.write_extended(&[ExtendedOpcodes::ScrollEnable as u8 | rows & 0x0F])
+    }
+
+    /// Write instructions from the extended instruction set, returning to the fundamental set
+    /// afterwards.
+    fn write_extended(&mut self, instructions: &[u8]) -> Result<(), DataBusError<E>> {
+        self.enter_extended()?;
+        let result = instructions
+            .iter()
+            .try_for_each(|instruction| self.lcd.write_instruction(*instruction));
+        self.select_table(InstructionTable::Normal)?;
+        result
+    }
+
+    /// Write instructions from the table selected by IS, returning to the normal table afterwards.
+    fn write_table(&mut self, instructions: &[u8]) -> Result<(), DataBusError<E>> {
+        self.select_table(InstructionTable::Extended)?;
+        let result = instructions
+            .iter()
+            .try_for_each(|instruction| self.lcd.write_instruction(*instruction));
+        self.select_table(InstructionTable::Normal)?;
+        result
+    }
+
+    /// Function set with RE set. The bits around RE (CGRAM blink, reverse display) are left clear,
+    /// double height only applies with RE clear.
+    fn enter_extended(&mut self) -> Result<(), DataBusError<E>> {
+        let function_set = self.lcd.function_set_operands() & !FUNCTION_SET_DH;
+        self.lcd
+            .write_instruction(Opcodes::FunctionSet as u8 | function_set | FUNCTION_SET_RE)
+    }
+
+    /// Function set with RE clear and IS selecting `table`, as last set through the driver and with
+    /// double height kept.
+    fn select_table(&mut self, table: InstructionTable) -> Result<(), DataBusError<E>> {
+        let function_set = self.lcd.function_set_operands() | table as u8;
+        self.lcd
+            .write_instruction(Opcodes::FunctionSet as u8 | function_set)
+    }
+}
+
+/// Double height/bias instruction, with the upper bias bit (BS1).
+fn double_height_bias_byte(mode: Option<DoubleHeight>, bias: BiasRatio) -> u8 {
+    let ud = mode.map_or(0, |mode| (mode as u8) << 2);
+    let bs1 = (bias as u8 >> 1) << 1;
+    DOUBLE_HEIGHT_BIAS_OPCODE | ud | bs1
+}
+
+/// Internal oscillator instruction, with the lower bias bit (BS0).
+fn oscillator_byte(bias: BiasRatio) -> u8 {
+    let bs0 = (bias as u8 & 1) << 3;
+    OSCILLATOR_OPCODE | bs0 | OSCILLATOR_FREQUENCY
+}
+
+impl<B, D> Deref for Ssd1803a<B, D>
+where
+    B: DataBus,
+    D: Delay<B::Hal>,
+{
+    type Target = WriteOnlyHD44780<B, D>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.lcd
+    }
+}
+
+impl<B, D> DerefMut for Ssd1803a<B, D>
+where
+    B: DataBus,
+    D: Delay<B::Hal>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.lcd
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Hd44780Config;
+    use crate::geometry::Geometry;
+    use crate::mock::{Delay, Pin, Port};
+    use crate::WriteOnlyBus4;
+
+    extern crate std;
+    use std::vec::Vec;
+
+    type Bus4 = WriteOnlyBus4<Pin, Pin, Pin, Pin, Pin, Pin>;
+
+    /// Four-line driver on a 4-bit bus with E on bit 0, RS on bit 1 and D4-D7 on bits 2-5.
+    fn four_line_lcd(port: &Port) -> WriteOnlyHD44780<Bus4, Delay> {
+        WriteOnlyHD44780::new_bus4(
+            port.pin(0),
+            port.pin(1),
+            port.pin(2),
+            port.pin(3),
+            port.pin(4),
+            port.pin(5),
+            Delay::default(),
+            Hd44780Config::new().geometry(Geometry::lcd_20x4_four_line()),
+        )
+        .unwrap()
+    }
+
+    /// Instructions latched from nibble `from` on.
+    fn instructions(port: &Port, from: usize) -> Vec<u32> {
+        port.latched(0)[from..]
+            .chunks(2)
+            .map(|n| (n[0] >> 2 & 0x0F) << 4 | n[1] >> 2 & 0x0F)
+            .collect()
+    }
+
+    #[test]
+    fn four_line_mode_and_bias_are_set_up() {
+        let port = Port::default();
+        let lcd = four_line_lcd(&port);
+        let from = port.latched(0).len();
+
+        let lcd = Ssd1803a::new(lcd, BiasRatio::OneSixth).unwrap();
+
+        assert_eq!(
+            instructions(&port, from),
+            [0x2A, 0x09, 0x12, 0x28, 0x29, 0x1B, 0x28]
+        );
+        assert!(lcd.current_extended_function_set().four_lines);
+    }
+
+    #[test]
+    fn double_height_is_selected_in_both_instruction_sets() {
+        let port = Port::default();
+        let mut lcd = Ssd1803a::new(four_line_lcd(&port), BiasRatio::OneSixth).unwrap();
+        let from = port.latched(0).len();
+
+        lcd.set_double_height(Some(DoubleHeight::Bottom)).unwrap();
+        lcd.set_scroll_enable(0b0101).unwrap();
+        lcd.set_double_height(None).unwrap();
+
+        assert_eq!(
+            instructions(&port, from),
+            [0x2A, 0x1E, 0x28, 0x2C, 0x2D, 0x2A, 0x15, 0x2C, 0x2A, 0x12, 0x2C, 0x28]
+        );
+        assert!(matches!(
+            lcd.current_function_set().char_font,
+            CharacterFont::FiveByEight
+        ));
+        assert!(lcd.double_height().is_none());
+    }
+
+    #[test]
+    fn leaving_four_line_mode_updates_the_extended_function_set() {
+        let port = Port::default();
+        let mut lcd = Ssd1803a::new(four_line_lcd(&port), BiasRatio::OneSixth).unwrap();
+        lcd.set_double_height(Some(DoubleHeight::Top)).unwrap();
+        let from = port.latched(0).len();
+
+        lcd.set_num_lines(NumberOfDisplayLines::TwoLines).unwrap();
+
+        assert_eq!(instructions(&port, from), [0x2C, 0x2A, 0x08, 0x2C]);
+        assert!(!lcd.current_extended_function_set().four_lines);
+    }
+}
@@ -0,0 +1,371 @@
+//! ST7032 and ST7036 controllers (instruction table 1 on the ST7036), found on many COG modules.
+//!
+//! Setting the IS bit of function set switches to an extended instruction table with the internal
+//! oscillator, bias, contrast, voltage booster, voltage follower and icon RAM. Contrast is zero
+//! after reset, so the panel stays blank until the extended settings have been sent.
+
+use crate::commands::Driver;
+use crate::data_bus::{DataBus, TransactionType};
+use crate::hal::Delay;
+use crate::instructions::*;
+use crate::{DataBusError, WriteOnlyHD44780};
+use core::ops::{Deref, DerefMut};
+
+// Opcodes of the extended instruction table (IS = 1)
+const OSCILLATOR_OPCODE: u8 = 0x10;
+const ICON_ADDRESS_OPCODE: u8 = 0x40;
+const POWER_ICON_CONTRAST_OPCODE: u8 = 0x50;
+const FOLLOWER_CONTROL_OPCODE: u8 = 0x60;
+const CONTRAST_OPCODE: u8 = 0x70;
+
+/// Largest contrast value (6 bits).
+pub const MAX_CONTRAST: u8 = 0x3F;
+
+/// Number of icon RAM addresses, each holding five segments.
+pub const ICON_RAM_SIZE: u8 = 16;
+
+/// Wait for the follower output to stabilize after it is turned on, in milliseconds.
+const FOLLOWER_STABILIZE_MS: u16 = 200;
+
+/// Settings of the extended instruction table.
+#[derive(Copy, Clone, Debug)]
+pub struct St7032Config {
+    /// Contrast, 0-63. Larger values are limited to 63.
+    pub contrast: u8,
+
+    /// Voltage booster, required when running from 3.3 V.
+    pub booster: bool,
+
+    /// Voltage follower, required to drive the panel.
+    pub follower: bool,
+
+    /// Voltage follower amplified ratio (Rab2-Rab0), 0-7.
+    pub follower_ratio: u8,
+
+    /// Bias of the LCD voltage.
+    pub bias: Bias,
+
+    /// Internal oscillator frequency adjustment (F2-F0), 0-7.
+    pub oscillator: u8,
+
+    /// Show the icons set in icon RAM.
+    pub icons: bool,
+}
+
+impl St7032Config {
+    /// Typical settings for a module running from 5 V. Contrast usually needs tuning per panel.
+    pub const fn five_volt() -> Self {
+        St7032Config {
+            contrast: 0x20,
+            booster: false,
+            follower: true,
+            follower_ratio: 0x02,
+            bias: Bias::OneFifth,
+            oscillator: 0x04,
+            icons: false,
+        }
+    }
+
+    /// Typical settings for a module running from 3.3 V, with the booster on. Contrast usually needs
+    /// tuning per panel.
+    pub const fn three_volt() -> Self {
+        St7032Config {
+            contrast: 0x28,
+            booster: true,
+            follower: true,
+            follower_ratio: 0x04,
+            bias: Bias::OneFifth,
+            oscillator: 0x04,
+            icons: false,
+        }
+    }
+
+    /// Internal oscillator frequency instruction.
+    fn oscillator_byte(&self) -> u8 {
+        let bs = (self.bias as u8) << 3;
+        OSCILLATOR_OPCODE | bs | self.oscillator & 0x07
+    }
+
+    /// Contrast set instruction, lower 4 bits of the contrast.
+    fn contrast_byte(&self) -> u8 {
+        CONTRAST_OPCODE | self.contrast & 0x0F
+    }
+
+    /// Power/icon control/contrast set instruction, upper 2 bits of the contrast.
+    fn power_icon_contrast_byte(&self) -> u8 {
+        let ion = if self.icons { 1 << 3 } else { 0 };
+        let bon = if self.booster { 1 << 2 } else { 0 };
+        POWER_ICON_CONTRAST_OPCODE | ion | bon | self.contrast.min(MAX_CONTRAST) >> 4
+    }
+
+    /// Follower control instruction.
+    fn follower_byte(&self) -> u8 {
+        let fon = if self.follower { 1 << 3 } else { 0 };
+        FOLLOWER_CONTROL_OPCODE | fon | self.follower_ratio & 0x07
+    }
+}
+
+impl Default for St7032Config {
+    fn default() -> Self {
+        St7032Config::five_volt()
+    }
+}
+
+/// ST7032 controller. Derefs to the driver, so all standard commands remain available.
+///
+/// ```rust, ignore
+/// let lcd = WriteOnlyHD44780::new_serial_i2c(i2c, ST7032_I2C_ADDRESS, I2cSpeed::Standard, delay, config)?;
+/// let mut lcd = St7032::new(lcd, St7032Config::three_volt())?;
+/// lcd.set_contrast(0x30)?;
+/// lcd.write_str("Hello")?;
+/// ```
+pub struct St7032<B, D>
+where
+    B: DataBus,
+    D: Delay<B::Hal>,
+{
+    lcd: WriteOnlyHD44780<B, D>,
+    config: St7032Config,
+}
+
+impl<B, D, E> St7032<B, D>
+where
+    B: DataBus<Error = DataBusError<E>>,
+    D: Delay<B::Hal>,
+{
+    /// Take an initialized driver and send the extended settings, then wait for the voltage
+    /// follower to stabilize.
+    pub fn new(
+        lcd: WriteOnlyHD44780<B, D>,
+        config: St7032Config,
+    ) -> Result<St7032<B, D>, DataBusError<E>> {
+        let config = St7032Config {
+            contrast: config.contrast.min(MAX_CONTRAST),
+            ..config
+        };
+        let mut st7032 = St7032 { lcd, config };
+        st7032.write_extended(&[
+            config.oscillator_byte(),
+            config.contrast_byte(),
+            config.power_icon_contrast_byte(),
+            config.follower_byte(),
+        ])?;
+        if config.follower {
+            st7032.lcd.delay.delay_ms(FOLLOWER_STABILIZE_MS);
+        }
+        Ok(st7032)
+    }
+
+    /// Destroy the controller profile and return the driver.
+    pub fn release(self) -> WriteOnlyHD44780<B, D> {
+        self.lcd
+    }
+
+    /// Current extended settings.
+    pub fn current_config(&self) -> St7032Config {
+        self.config
+    }
+
+    /// Set the contrast, 0-63. Larger values are limited to 63.
+    pub fn set_contrast(&mut self, contrast: u8) -> Result<(), DataBusError<E>> {
+        let config = St7032Config {
+            contrast: contrast.min(MAX_CONTRAST),
+            ..self.config
+        };
+        self.write_extended(&[config.contrast_byte(), config.power_icon_contrast_byte()])?;
+        self.config = config;
+        Ok(())
+    }
+
+    /// Turn the voltage booster on or off.
+    pub fn set_booster(&mut self, on: bool) -> Result<(), DataBusError<E>> {
+        let config = St7032Config {
+            booster: on,
+            ..self.config
+        };
+        self.write_extended(&[config.power_icon_contrast_byte()])?;
+        self.config = config;
+        Ok(())
+    }
+
+    /// Turn the voltage follower on or off and set its amplified ratio, 0-7. Waits for the
+    /// follower to stabilize when turned on.
+    pub fn set_follower(&mut self, on: bool, ratio: u8) -> Result<(), DataBusError<E>> {
+        let config = St7032Config {
+            follower: on,
+            follower_ratio: ratio & 0x07,
+            ..self.config
+        };
+        self.write_extended(&[config.follower_byte()])?;
+        self.config = config;
+        if on {
+            self.lcd.delay.delay_ms(FOLLOWER_STABILIZE_MS);
+        }
+        Ok(())
+    }
+
+    /// Set the bias and internal oscillator frequency adjustment, 0-7.
+    pub fn set_oscillator(&mut self, bias: Bias, frequency: u8) -> Result<(), DataBusError<E>> {
+        let config = St7032Config {
+            bias,
+            oscillator: frequency & 0x07,
+            ..self.config
+        };
+        self.write_extended(&[config.oscillator_byte()])?;
+        self.config = config;
+        Ok(())
+    }
+
+    /// Show or hide the icons set in icon RAM.
+    pub fn show_icons(&mut self, show: bool) -> Result<(), DataBusError<E>> {
+        let config = St7032Config {
+            icons: show,
+            ..self.config
+        };
+        self.write_extended(&[config.power_icon_contrast_byte()])?;
+        self.config = config;
+        Ok(())
+    }
+
+    /// Write the segments (lower 5 bits of each byte) of consecutive icon RAM addresses, starting at
+    /// `address` (0-15). The cursor position is restored afterwards.
+    pub fn write_icons(&mut self, address: u8, segments: &[u8]) -> Result<(), DataBusError<E>> {
+        if address as usize + segments.len() > ICON_RAM_SIZE as usize {
+            return Err(DataBusError::AddressOutOfRange);
+        }
+        let cursor = self.lcd.cursor_address();
+        self.write_extended(&[ICON_ADDRESS_OPCODE | address])?;
+
+        // Data is written to icon RAM until a DDRAM address is set again
+        let lcd = &mut self.lcd;
+        lcd.bus
+            .write_bytes(segments, TransactionType::Data, &mut lcd.delay)?;
+        lcd.delay.delay_us(lcd.bus.timing().execution_us);
+        lcd.set_position(cursor)
+    }
+
+    /// Write instructions from the extended table, returning to the normal table afterwards. The
+    /// driver only tracks the normal table, as every function set it sends clears IS, so the
+    /// normal table is selected again even if an instruction fails.
+    fn write_extended(&mut self, instructions: &[u8]) -> Result<(), DataBusError<E>> {
+        let opcode = Opcodes::FunctionSet as u8;
+        let function_set = self.lcd.function_set;
+        self.lcd.write_instruction(
+            opcode | function_set.as_byte_with_table(InstructionTable::Extended),
+        )?;
+        let result = instructions
+            .iter()
+            .try_for_each(|instruction| self.lcd.write_instruction(*instruction));
+        self.lcd.write_instruction(
+            opcode | function_set.as_byte_with_table(InstructionTable::Normal),
+        )?;
+        result
+    }
+}
+
+impl<B, D> Deref for St7032<B, D>
+where
+    B: DataBus,
+    D: Delay<B::Hal>,
+{
+    type Target = WriteOnlyHD44780<B, D>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.lcd
+    }
+}
+
+impl<B, D> DerefMut for St7032<B, D>
+where
+    B: DataBus,
+    D: Delay<B::Hal>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.lcd
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Hd44780Config;
+    use crate::data_bus::serial::{I2cSpeed, SerialI2cBus, ST7032_I2C_ADDRESS};
+    use crate::mock::{Delay, I2c};
+
+    fn st7032(i2c: &I2c) -> St7032<SerialI2cBus<I2c>, Delay> {
+        let lcd = WriteOnlyHD44780::new_serial_i2c(
+            i2c.clone(),
+            ST7032_I2C_ADDRESS,
+            I2cSpeed::Standard,
+            Delay::default(),
+            Hd44780Config::new(),
+        )
+        .unwrap();
+        St7032::new(lcd, St7032Config::three_volt()).unwrap()
+    }
+
+    #[test]
+    fn extended_settings_are_sent_from_the_extended_table() {
+        let i2c = I2c::default();
+        let lcd = st7032(&i2c);
+        let sent = i2c.serial_bytes();
+        let extended = sent.iter().position(|b| *b == (0x00, 0x39)).unwrap();
+
+        // IS set, oscillator (1/5 bias, F=4), contrast low bits, booster and contrast high bits,
+        // follower on (ratio 4), IS cleared
+        assert_eq!(
+            sent[extended..],
+            [
+                (0x00, 0x39),
+                (0x00, 0x14),
+                (0x00, 0x78),
+                (0x00, 0x56),
+                (0x00, 0x6C),
+                (0x00, 0x38),
+            ]
+        );
+        assert_eq!(lcd.delay.log.last(), Some(&200_000));
+    }
+
+    #[test]
+    fn extended_table_follows_function_set_through_the_driver() {
+        let i2c = I2c::default();
+        let mut lcd = st7032(&i2c);
+
+        lcd.set_num_lines(NumberOfDisplayLines::OneLine).unwrap();
+        let sent = i2c.serial_bytes().len();
+        lcd.set_contrast(0x3F).unwrap();
+
+        assert_eq!(
+            i2c.serial_bytes()[sent..],
+            [(0x00, 0x31), (0x00, 0x7F), (0x00, 0x57), (0x00, 0x30)]
+        );
+        assert_eq!(lcd.current_config().contrast, MAX_CONTRAST);
+    }
+
+    #[test]
+    fn icons_are_written_and_cursor_restored() {
+        let i2c = I2c::default();
+        let mut lcd = st7032(&i2c);
+        lcd.set_position(0x05).unwrap();
+        let sent = i2c.serial_bytes().len();
+
+        lcd.write_icons(2, &[0x1F, 0x01]).unwrap();
+
+        assert_eq!(
+            i2c.serial_bytes()[sent..],
+            [
+                (0x00, 0x39),
+                (0x00, 0x42),
+                (0x00, 0x38),
+                (0x40, 0x1F),
+                (0x40, 0x01),
+                (0x00, 0x85),
+            ]
+        );
+        assert!(matches!(
+            lcd.write_icons(15, &[0, 0]),
+            Err(DataBusError::AddressOutOfRange)
+        ));
+    }
+}
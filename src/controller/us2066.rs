@@ -0,0 +1,444 @@
+//! US2066 and SSD1311 character OLED controllers (Newhaven, Winstar and others).
+//!
+//! Setting the RE bit of function set selects an extended instruction set, from which the SD bit
+//! enters the OLED characterization command set (contrast, fade out, clock and driving voltages).
+//! The controllers work on the parallel buses and over I2C, where they use the same control bytes
+//! as the ST7032 (see `SerialI2cBus`).
+//!
+//! Change the number of lines with `set_num_lines` or `function_set` of the profile, which also
+//! switch four-line mode (NW) of the extended function set.
+
+use crate::commands::Driver;
+use crate::data_bus::{DataBus, TransactionType};
+use crate::hal::Delay;
+use crate::instructions::*;
+use crate::state::ExtendedFunctionSet;
+use crate::{DataBusError, WriteOnlyHD44780};
+use core::ops::{Deref, DerefMut};
+
+/// I2C address with SA0 low. Pulling SA0 high selects 0x3D.
+pub const US2066_I2C_ADDRESS: u8 = 0x3C;
+
+/// Extended instruction set select bit of function set.
+const FUNCTION_SET_RE: u8 = 1 << 1;
+
+/// Double height bit of function set (RE = 0), in place of the font bit of the HD44780.
+const FUNCTION_SET_DH: u8 = 1 << 2;
+
+// Extended instruction set (RE = 1)
+const COM_SEG_DIRECTION_OPCODE: u8 = 0x04;
+const POWER_DOWN_OPCODE: u8 = 0x02;
+const DOUBLE_HEIGHT_OPCODE: u8 = 0x10;
+const FUNCTION_SELECTION_A_OPCODE: u8 = 0x71;
+const FUNCTION_SELECTION_B_OPCODE: u8 = 0x72;
+const OLED_COMMAND_SET_ENABLE: u8 = 0x79;
+const OLED_COMMAND_SET_DISABLE: u8 = 0x78;
+
+// OLED characterization commands (RE = 1, SD = 1), each followed by its value
+const SET_CONTRAST: u8 = 0x81;
+const SET_CLOCK: u8 = 0xD5;
+const SET_PHASE_LENGTH: u8 = 0xD9;
+const SET_SEG_CONFIGURATION: u8 = 0xDA;
+const SET_VCOMH_DESELECT: u8 = 0xDB;
+const FUNCTION_SELECTION_C: u8 = 0xDC;
+const SET_FADE_BLINK: u8 = 0x23;
+
+/// Function selection A value enabling the internal VDD regulator.
+const INTERNAL_REGULATOR: u8 = 0x5C;
+
+/// Wait after the power-up sequence before the display is used, in milliseconds.
+const POWER_UP_WAIT_MS: u16 = 100;
+
+/// Settings sent during the power-up sequence.
+#[derive(Copy, Clone, Debug)]
+pub struct Us2066Config {
+    /// Internal VDD regulator, required with 5 V logic and off for low voltage I/O.
+    pub internal_regulator: bool,
+
+    /// Character ROM.
+    pub rom: CharacterRom,
+
+    /// Contrast, 0-255.
+    pub contrast: u8,
+}
+
+impl Us2066Config {
+    /// Settings for a module with 5 V logic.
+    pub const fn five_volt() -> Self {
+        Us2066Config {
+            internal_regulator: true,
+            rom: CharacterRom::A,
+            contrast: 0x7F,
+        }
+    }
+
+    /// Settings for a module with 3.3 V logic.
+    pub const fn three_volt() -> Self {
+        Us2066Config {
+            internal_regulator: false,
+            rom: CharacterRom::A,
+            contrast: 0x7F,
+        }
+    }
+}
+
+impl Default for Us2066Config {
+    fn default() -> Self {
+        Us2066Config::five_volt()
+    }
+}
+
+/// US2066 controller. Derefs to the driver, so all standard commands remain available.
+///
+/// ```rust, ignore
+/// let lcd = WriteOnlyHD44780::new_serial_i2c(i2c, US2066_I2C_ADDRESS, I2cSpeed::Standard, delay, config)?;
+/// let mut lcd = Us2066::new(lcd, Us2066Config::three_volt())?;
+/// lcd.set_fade(FadeMode::Blink, 4)?;
+/// ```
+pub struct Us2066<B, D>
+where
+    B: DataBus,
+    D: Delay<B::Hal>,
+{
+    lcd: WriteOnlyHD44780<B, D>,
+    config: Us2066Config,
+    extended_function_set: ExtendedFunctionSet,
+    double_height: Option<DoubleHeight>,
+}
+
+impl<B, D, E> Us2066<B, D>
+where
+    B: DataBus<Error = DataBusError<E>>,
+    D: Delay<B::Hal>,
+{
+    /// Take an initialized driver and perform the power-up sequence with the display off, then
+    /// clear and restore the display. Four-line mode is enabled when the driver is configured for
+    /// four lines (see `Geometry::four_line`).
+    pub fn new(
+        lcd: WriteOnlyHD44780<B, D>,
+        config: Us2066Config,
+    ) -> Result<Us2066<B, D>, DataBusError<E>> {
+        let extended_function_set = ExtendedFunctionSet {
+            four_lines: matches!(lcd.function_set.num_lines, NumberOfDisplayLines::FourLines),
+            ..Default::default()
+        };
+        let mut us2066 = Us2066 {
+            lcd,
+            config,
+            extended_function_set,
+            double_height: None,
+        };
+
+        let regulator = if config.internal_regulator {
+            INTERNAL_REGULATOR
+        } else {
+            0x00
+        };
+        us2066.write_function_selection(FUNCTION_SELECTION_A_OPCODE, regulator)?;
+        // Display off, keeping the driver settings to restore afterwards
+        us2066
+            .lcd
+            .write_instruction(Opcodes::DisplayControl as u8)?;
+
+        us2066.write_oled_commands(&[SET_CLOCK, 0x70])?;
+        us2066.write_extended(&[
+            ExtendedOpcodes::ExtendedFunctionSet as u8 | extended_function_set.as_byte(),
+            // COM0 -> COM31, SEG99 -> SEG0
+            COM_SEG_DIRECTION_OPCODE | 1 << 1,
+        ])?;
+        us2066.write_function_selection(FUNCTION_SELECTION_B_OPCODE, (config.rom as u8) << 2)?;
+        us2066.write_oled_commands(&[
+            SET_SEG_CONFIGURATION,
+            0x10,
+            FUNCTION_SELECTION_C,
+            0x00,
+            SET_CONTRAST,
+            config.contrast,
+            SET_PHASE_LENGTH,
+            0xF1,
+            SET_VCOMH_DESELECT,
+            0x40,
+        ])?;
+
+        us2066.lcd.clear_display()?;
+        us2066.restore_display_control()?;
+        us2066.lcd.delay.delay_ms(POWER_UP_WAIT_MS);
+        Ok(us2066)
+    }
+
+    /// Destroy the controller profile and return the driver.
+    pub fn release(self) -> WriteOnlyHD44780<B, D> {
+        self.lcd
+    }
+
+    /// Current power-up settings, updated by the commands that change them.
+    pub fn current_config(&self) -> Us2066Config {
+        self.config
+    }
+
+    /// Set the contrast, 0-255.
+    pub fn set_contrast(&mut self, contrast: u8) -> Result<(), DataBusError<E>> {
+        self.write_oled_commands(&[SET_CONTRAST, contrast])?;
+        self.config.contrast = contrast;
+        Ok(())
+    }
+
+    /// Fade out or blink the whole display, stepping every `interval` (0-15) x 8 + 8 frames.
+    pub fn set_fade(&mut self, mode: FadeMode, interval: u8) -> Result<(), DataBusError<E>> {
+        let value = (mode as u8) << 4 | interval & 0x0F;
+        self.write_oled_commands(&[SET_FADE_BLINK, value])
+    }
+
+    /// Select the character ROM.
+    pub fn set_rom(&mut self, rom: CharacterRom) -> Result<(), DataBusError<E>> {
+        self.write_function_selection(FUNCTION_SELECTION_B_OPCODE, (rom as u8) << 2)?;
+        self.config.rom = rom;
+        Ok(())
+    }
+
+    /// Function set through the driver, followed by the extended function set when entering or
+    /// leaving four-line mode.
+    pub fn function_set(
+        &mut self,
+        data_length: DataLength,
+        num_lines: NumberOfDisplayLines,
+        font: CharacterFont,
+    ) -> Result<(), DataBusError<E>> {
+        self.lcd.function_set(data_length, num_lines, font)?;
+        let four_lines = matches!(num_lines, NumberOfDisplayLines::FourLines);
+        if four_lines != self.extended_function_set.four_lines {
+            let extended_function_set = ExtendedFunctionSet {
+                four_lines,
+                ..self.extended_function_set
+            };
+            self.write_extended(&[
+                ExtendedOpcodes::ExtendedFunctionSet as u8 | extended_function_set.as_byte()
+            ])?;
+            self.extended_function_set = extended_function_set;
+        }
+        Ok(())
+    }
+
+    /// Set the number of display lines, entering or leaving four-line mode as needed.
+    pub fn set_num_lines(
+        &mut self,
+        num_lines: NumberOfDisplayLines,
+    ) -> Result<(), DataBusError<E>> {
+        let function_set = self.lcd.current_function_set();
+        self.function_set(function_set.data_length, num_lines, function_set.char_font)
+    }
+
+    /// Merge rows into double height lines, or return to normal height with `None`. The double
+    /// height bit (DH) takes the place of the font bit (F) of the HD44780 in function set and is
+    /// kept when function set is sent through the driver, so the font should be left at 5x8.
+    pub fn set_double_height(&mut self, mode: Option<DoubleHeight>) -> Result<(), DataBusError<E>> {
+        let (rows, dh) = match mode {
+            Some(mode) => ((mode as u8) << 2, FUNCTION_SET_DH),
+            None => (0, 0),
+        };
+        self.write_extended(&[DOUBLE_HEIGHT_OPCODE | rows])?;
+        self.lcd.set_function_set_extension(dh)?;
+        self.double_height = mode;
+        Ok(())
+    }
+
+    /// Current double height mode, `None` at normal height.
+    pub fn double_height(&self) -> Option<DoubleHeight> {
+        self.double_height
+    }
+
+    /// Enter or leave sleep mode. The display is turned off and the controller powered down while
+    /// asleep; DDRAM contents and settings are kept.
+    pub fn sleep(&mut self, sleep: bool) -> Result<(), DataBusError<E>> {
+        if sleep {
+            // Display off, keeping the driver settings to restore on wake up
+            self.lcd.write_instruction(Opcodes::DisplayControl as u8)?;
+            self.write_extended(&[POWER_DOWN_OPCODE | 1])
+        } else {
+            self.write_extended(&[POWER_DOWN_OPCODE])?;
+            self.restore_display_control()
+        }
+    }
+
+    /// Write instructions from the extended instruction set, returning to the fundamental set
+    /// afterwards.
+    fn write_extended(&mut self, instructions: &[u8]) -> Result<(), DataBusError<E>> {
+        self.enter_extended()?;
+        let result = instructions
+            .iter()
+            .try_for_each(|instruction| self.lcd.write_instruction(*instruction));
+        self.leave_extended()?;
+        result
+    }
+
+    /// Write OLED characterization commands and their values.
+    fn write_oled_commands(&mut self, commands: &[u8]) -> Result<(), DataBusError<E>> {
+        self.enter_extended()?;
+        let result = self
+            .lcd
+            .write_instruction(OLED_COMMAND_SET_ENABLE)
+            .and_then(|_| {
+                commands
+                    .iter()
+                    .try_for_each(|command| self.lcd.write_instruction(*command))
+            });
+        // The OLED command set is left even if a command fails
+        let disabled = self.lcd.write_instruction(OLED_COMMAND_SET_DISABLE);
+        self.leave_extended()?;
+        result.and(disabled)
+    }
+
+    /// Write a function selection instruction, which takes its value as a data byte.
+    fn write_function_selection(&mut self, opcode: u8, value: u8) -> Result<(), DataBusError<E>> {
+        self.enter_extended()?;
+        let lcd = &mut self.lcd;
+        let result = lcd.write_instruction(opcode).and_then(|_| {
+            lcd.bus
+                .write_byte(value, TransactionType::Data, &mut lcd.delay)?;
+            lcd.delay.delay_us(lcd.bus.timing().execution_us);
+            Ok(())
+        });
+        self.leave_extended()?;
+        result
+    }
+
+    /// Function set with RE set. The bits below RE (CGRAM blink, reverse display) are left clear,
+    /// double height only applies with RE clear.
+    fn enter_extended(&mut self) -> Result<(), DataBusError<E>> {
+        let function_set = self.lcd.function_set_operands() & !FUNCTION_SET_DH;
+        self.lcd
+            .write_instruction(Opcodes::FunctionSet as u8 | function_set | FUNCTION_SET_RE)
+    }
+
+    /// Function set with RE clear, as last set through the driver and with double height kept.
+    fn leave_extended(&mut self) -> Result<(), DataBusError<E>> {
+        let function_set = self.lcd.function_set_operands();
+        self.lcd
+            .write_instruction(Opcodes::FunctionSet as u8 | function_set)
+    }
+
+    /// Send the display control settings last set through the driver.
+    fn restore_display_control(&mut self) -> Result<(), DataBusError<E>> {
+        let display_control = self.lcd.display_control.as_byte();
+        self.lcd
+            .write_instruction(Opcodes::DisplayControl as u8 | display_control)
+    }
+}
+
+impl<B, D> Deref for Us2066<B, D>
+where
+    B: DataBus,
+    D: Delay<B::Hal>,
+{
+    type Target = WriteOnlyHD44780<B, D>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.lcd
+    }
+}
+
+impl<B, D> DerefMut for Us2066<B, D>
+where
+    B: DataBus,
+    D: Delay<B::Hal>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.lcd
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Hd44780Config;
+    use crate::data_bus::serial::{I2cSpeed, SerialI2cBus};
+    use crate::mock::{Delay, I2c};
+
+    extern crate std;
+    use std::vec::Vec;
+
+    fn us2066(i2c: &I2c) -> Us2066<SerialI2cBus<I2c>, Delay> {
+        let lcd = WriteOnlyHD44780::new_serial_i2c(
+            i2c.clone(),
+            US2066_I2C_ADDRESS,
+            I2cSpeed::Standard,
+            Delay::default(),
+            Hd44780Config::new(),
+        )
+        .unwrap();
+        Us2066::new(lcd, Us2066Config::five_volt()).unwrap()
+    }
+
+    fn instructions(sent: &[(u8, u8)]) -> Vec<u8> {
+        sent.iter()
+            .filter(|(control, _)| *control == 0x00)
+            .map(|(_, byte)| *byte)
+            .collect()
+    }
+
+    #[test]
+    fn function_selection_value_is_sent_as_data_with_re_set() {
+        let i2c = I2c::default();
+        let lcd = us2066(&i2c);
+        let sent = i2c.serial_bytes();
+        let start = sent.iter().position(|b| *b == (0x00, 0x3A)).unwrap();
+
+        assert_eq!(
+            sent[start..start + 4],
+            [(0x00, 0x3A), (0x00, 0x71), (0x40, 0x5C), (0x00, 0x38)]
+        );
+        assert_eq!(lcd.current_config().contrast, 0x7F);
+    }
+
+    #[test]
+    fn oled_commands_are_wrapped_in_the_command_set() {
+        let i2c = I2c::default();
+        let mut lcd = us2066(&i2c);
+        let sent = i2c.serial_bytes().len();
+
+        lcd.set_contrast(0x40).unwrap();
+
+        assert_eq!(
+            instructions(&i2c.serial_bytes()[sent..]),
+            [0x3A, 0x79, 0x81, 0x40, 0x78, 0x38]
+        );
+        assert_eq!(lcd.current_config().contrast, 0x40);
+    }
+
+    #[test]
+    fn double_height_is_kept_by_function_set_through_the_driver() {
+        let i2c = I2c::default();
+        let mut lcd = us2066(&i2c);
+        let sent = i2c.serial_bytes().len();
+
+        lcd.set_double_height(Some(DoubleHeight::Both)).unwrap();
+        lcd.set_num_lines(NumberOfDisplayLines::OneLine).unwrap();
+        lcd.set_contrast(0x40).unwrap();
+
+        assert_eq!(
+            instructions(&i2c.serial_bytes()[sent..]),
+            [0x3A, 0x18, 0x38, 0x3C, 0x34, 0x32, 0x79, 0x81, 0x40, 0x78, 0x34]
+        );
+        assert!(matches!(
+            lcd.current_function_set().char_font,
+            CharacterFont::FiveByEight
+        ));
+        assert!(matches!(lcd.double_height(), Some(DoubleHeight::Both)));
+    }
+
+    #[test]
+    fn normal_height_is_selected_in_both_instruction_sets() {
+        let i2c = I2c::default();
+        let mut lcd = us2066(&i2c);
+        lcd.set_double_height(Some(DoubleHeight::Top)).unwrap();
+        let sent = i2c.serial_bytes().len();
+
+        lcd.set_double_height(None).unwrap();
+
+        assert_eq!(
+            instructions(&i2c.serial_bytes()[sent..]),
+            [0x3A, 0x10, 0x3C, 0x38]
+        );
+        assert!(lcd.double_height().is_none());
+    }
+}
@@ -0,0 +1,261 @@
+//! WS0010 and RS0010 character OLED controllers (Winstar and others).
+//!
+//! The mode/power instruction, which shares its opcode with cursor/display shift, turns the
+//! internal DC-DC converter on or off and switches between character and graphic mode. In graphic
+//! mode DDRAM holds columns of 8 pixels (least significant bit at the top): set DDRAM address
+//! selects the column and set CGRAM address the 8 pixel high row.
+
+use crate::commands::Driver;
+use crate::data_bus::{DataBus, TransactionType};
+use crate::hal::Delay;
+use crate::instructions::*;
+use crate::{DataBusError, WriteOnlyHD44780};
+use core::ops::{Deref, DerefMut};
+
+/// Width of the graphic mode bitmap in pixels.
+pub const GRAPHIC_WIDTH: u8 = 100;
+
+/// Number of 8 pixel high rows of the graphic mode bitmap.
+pub const GRAPHIC_ROWS: u8 = 2;
+
+/// Mode/power instruction, distinguished from a cursor/display shift by its two lowest bits.
+const MODE_POWER_OPCODE: u8 = 0x13;
+
+/// Graphic/character mode select bit of mode/power.
+const MODE_POWER_GC: u8 = 1 << 3;
+
+/// Internal power bit of mode/power.
+const MODE_POWER_PWR: u8 = 1 << 2;
+
+/// WS0010 controller. Derefs to the driver, so all standard commands remain available in
+/// character mode.
+///
+/// ```rust, ignore
+/// let lcd = WriteOnlyHD44780::new_bus4(en, rs, d4, d5, d6, d7, delay, config)?;
+/// let mut lcd = Ws0010::new(lcd, FontTable::WesternEuropean1)?;
+/// lcd.set_graphic_mode(true)?;
+/// lcd.draw_bitmap(&logo)?;
+/// ```
+pub struct Ws0010<B, D>
+where
+    B: DataBus,
+    D: Delay<B::Hal>,
+{
+    lcd: WriteOnlyHD44780<B, D>,
+    font_table: FontTable,
+    graphic_mode: bool,
+    power: bool,
+}
+
+impl<B, D, E> Ws0010<B, D>
+where
+    B: DataBus<Error = DataBusError<E>>,
+    D: Delay<B::Hal>,
+{
+    /// Take an initialized driver, select the font table and turn the internal power on in
+    /// character mode, then clear the display.
+    pub fn new(
+        lcd: WriteOnlyHD44780<B, D>,
+        font_table: FontTable,
+    ) -> Result<Ws0010<B, D>, DataBusError<E>> {
+        let mut ws0010 = Ws0010 {
+            lcd,
+            font_table,
+            graphic_mode: false,
+            power: true,
+        };
+        ws0010.set_font_table(font_table)?;
+        ws0010.write_mode_power(false, true)?;
+        ws0010.lcd.clear_display()?;
+        Ok(ws0010)
+    }
+
+    /// Destroy the controller profile and return the driver.
+    pub fn release(self) -> WriteOnlyHD44780<B, D> {
+        self.lcd
+    }
+
+    /// Select the character font table. The font table bits (FT1, FT0) are kept when function set
+    /// is sent through the driver.
+    pub fn set_font_table(&mut self, font_table: FontTable) -> Result<(), DataBusError<E>> {
+        self.lcd.set_function_set_extension(font_table as u8)?;
+        self.font_table = font_table;
+        Ok(())
+    }
+
+    /// Current font table.
+    pub fn font_table(&self) -> FontTable {
+        self.font_table
+    }
+
+    /// Turn the internal power (DC-DC converter) on or off. DDRAM contents are kept while off.
+    pub fn set_power(&mut self, on: bool) -> Result<(), DataBusError<E>> {
+        self.write_mode_power(self.graphic_mode, on)?;
+        self.power = on;
+        Ok(())
+    }
+
+    /// Switch between graphic and character mode. The cursor position is restored when returning
+    /// to character mode.
+    pub fn set_graphic_mode(&mut self, graphic: bool) -> Result<(), DataBusError<E>> {
+        self.write_mode_power(graphic, self.power)?;
+        self.graphic_mode = graphic;
+        if !graphic {
+            let cursor = self.lcd.cursor_address();
+            self.lcd.set_position(cursor)?;
+        }
+        Ok(())
+    }
+
+    /// Whether graphic mode is selected.
+    pub fn is_graphic_mode(&self) -> bool {
+        self.graphic_mode
+    }
+
+    /// Write pixel columns to `row` (0-1) starting at column `x` (0-99). Each byte is a column of
+    /// 8 pixels, least significant bit at the top. Only valid in graphic mode.
+    pub fn draw_columns(&mut self, row: u8, x: u8, columns: &[u8]) -> Result<(), DataBusError<E>> {
+        if row >= GRAPHIC_ROWS || x as usize + columns.len() > GRAPHIC_WIDTH as usize {
+            return Err(DataBusError::AddressOutOfRange);
+        }
+        self.lcd
+            .write_instruction(Opcodes::SetCgramAddress as u8 | row)?;
+        self.lcd
+            .write_instruction(Opcodes::SetDdramAddress as u8 | x)?;
+
+        let lcd = &mut self.lcd;
+        lcd.bus
+            .write_bytes(columns, TransactionType::Data, &mut lcd.delay)?;
+        lcd.delay.delay_us(lcd.bus.timing().execution_us);
+        Ok(())
+    }
+
+    /// Write the whole 100x16 bitmap: the 100 columns of the top row followed by those of the
+    /// bottom row. Only valid in graphic mode.
+    pub fn draw_bitmap(
+        &mut self,
+        bitmap: &[u8; GRAPHIC_WIDTH as usize * GRAPHIC_ROWS as usize],
+    ) -> Result<(), DataBusError<E>> {
+        for (row, columns) in bitmap.chunks(GRAPHIC_WIDTH as usize).enumerate() {
+            self.draw_columns(row as u8, 0, columns)?;
+        }
+        Ok(())
+    }
+
+    /// Send the mode/power instruction.
+    fn write_mode_power(&mut self, graphic: bool, power: bool) -> Result<(), DataBusError<E>> {
+        let gc = if graphic { MODE_POWER_GC } else { 0 };
+        let pwr = if power { MODE_POWER_PWR } else { 0 };
+        self.lcd.write_instruction(MODE_POWER_OPCODE | gc | pwr)
+    }
+}
+
+impl<B, D> Deref for Ws0010<B, D>
+where
+    B: DataBus,
+    D: Delay<B::Hal>,
+{
+    type Target = WriteOnlyHD44780<B, D>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.lcd
+    }
+}
+
+impl<B, D> DerefMut for Ws0010<B, D>
+where
+    B: DataBus,
+    D: Delay<B::Hal>,
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.lcd
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Hd44780Config;
+    use crate::mock::{Delay, Pin, Port};
+    use crate::WriteOnlyBus4;
+
+    extern crate std;
+    use std::vec::Vec;
+
+    type Bus4 = WriteOnlyBus4<Pin, Pin, Pin, Pin, Pin, Pin>;
+
+    /// WS0010 on a 4-bit bus with E on bit 0, RS on bit 1 and D4-D7 on bits 2-5.
+    fn ws0010(port: &Port) -> Ws0010<Bus4, Delay> {
+        let lcd = WriteOnlyHD44780::new_bus4(
+            port.pin(0),
+            port.pin(1),
+            port.pin(2),
+            port.pin(3),
+            port.pin(4),
+            port.pin(5),
+            Delay::default(),
+            Hd44780Config::new(),
+        )
+        .unwrap();
+        Ws0010::new(lcd, FontTable::WesternEuropean1).unwrap()
+    }
+
+    /// Bytes latched from nibble `from` on, as (RS, byte).
+    fn bytes(port: &Port, from: usize) -> Vec<(u32, u32)> {
+        port.latched(0)[from..]
+            .chunks(2)
+            .map(|n| (n[0] >> 1 & 1, (n[0] >> 2 & 0x0F) << 4 | n[1] >> 2 & 0x0F))
+            .collect()
+    }
+
+    #[test]
+    fn font_table_and_power_are_set_up_in_character_mode() {
+        let port = Port::default();
+        let _lcd = ws0010(&port);
+        let sent = bytes(&port, 6);
+
+        assert_eq!(sent[sent.len() - 3..], [(0, 0x29), (0, 0x17), (0, 0x01)]);
+    }
+
+    #[test]
+    fn font_table_is_kept_by_function_set_through_the_driver() {
+        let port = Port::default();
+        let mut lcd = ws0010(&port);
+        let from = port.latched(0).len();
+
+        lcd.set_num_lines(NumberOfDisplayLines::OneLine).unwrap();
+        lcd.set_font(CharacterFont::FiveByTen).unwrap();
+
+        assert_eq!(bytes(&port, from), [(0, 0x21), (0, 0x25)]);
+        assert!(matches!(lcd.font_table(), FontTable::WesternEuropean1));
+    }
+
+    #[test]
+    fn columns_are_drawn_in_graphic_mode_and_cursor_restored() {
+        let port = Port::default();
+        let mut lcd = ws0010(&port);
+        lcd.set_position(0x04).unwrap();
+        let from = port.latched(0).len();
+
+        lcd.set_graphic_mode(true).unwrap();
+        lcd.draw_columns(1, 3, &[0xAA, 0x55]).unwrap();
+        lcd.set_graphic_mode(false).unwrap();
+
+        assert_eq!(
+            bytes(&port, from),
+            [
+                (0, 0x1F),
+                (0, 0x41),
+                (0, 0x83),
+                (1, 0xAA),
+                (1, 0x55),
+                (0, 0x17),
+                (0, 0x84),
+            ]
+        );
+        assert!(matches!(
+            lcd.draw_columns(0, 99, &[0, 0]),
+            Err(DataBusError::AddressOutOfRange)
+        ));
+    }
+}
@@ -1 +1,385 @@
-//! 4-bit wide data bit bus
+//! 4-bit wide data bit bus. Only the upper data pins (D4-D7) of the device are connected, each byte
+//! is transferred as two nibbles, high nibble first.
+
+use crate::data_bus::*;
+#[cfg(feature = "async")]
+use crate::hal::Eh1;
+use crate::hal::{Delay, Eh02, OutputPin};
+use crate::timing::Timing;
+use crate::DataBusError;
+#[cfg(feature = "async")]
+use embedded_hal_1 as hal_1;
+#[cfg(feature = "async")]
+use embedded_hal_async::delay::DelayNs;
+use pins::*;
+
+/// 4-bit data bus with write-only data pins. Note: RW pin on device should be tied low and D0-D3
+/// left unconnected.
+pub struct WriteOnlyBus4<
+    EN: OutputPin<H>,
+    RS: OutputPin<H>,
+    D4: OutputPin<H>,
+    D5: OutputPin<H>,
+    D6: OutputPin<H>,
+    D7: OutputPin<H>,
+    H = Eh02,
+> {
+    en: EnablePin<EN, H>,
+    rs: RegisterSelectPin<RS, H>,
+    d4: D4,
+    d5: D5,
+    d6: D6,
+    d7: D7,
+    timing: Timing,
+}
+
+impl<EN, RS, D4, D5, D6, D7, H> WriteOnlyBus4<EN, RS, D4, D5, D6, D7, H>
+where
+    EN: OutputPin<H>,
+    RS: OutputPin<H, Error = EN::Error>,
+    D4: OutputPin<H, Error = EN::Error>,
+    D5: OutputPin<H, Error = EN::Error>,
+    D6: OutputPin<H, Error = EN::Error>,
+    D7: OutputPin<H, Error = EN::Error>,
+{
+    /// Create a new instance of a write-only 4-bit bus from a group of pins.
+    pub fn from_pins(
+        en: EN,
+        rs: RS,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+    ) -> WriteOnlyBus4<EN, RS, D4, D5, D6, D7, H> {
+        WriteOnlyBus4 {
+            en: EnablePin::from_output_pin(en),
+            rs: RegisterSelectPin::from_output_pin(rs),
+            d4,
+            d5,
+            d6,
+            d7,
+            timing: Default::default(),
+        }
+    }
+
+    /// Destroy the bus and return the pins.
+    pub fn release(self) -> (EN, RS, D4, D5, D6, D7) {
+        (
+            self.en.release(),
+            self.rs.release(),
+            self.d4,
+            self.d5,
+            self.d6,
+            self.d7,
+        )
+    }
+
+    /// Set the data pins to the lower nibble of the provided value
+    pub fn set_pins(&mut self, nibble: u8) -> Result<(), DataBusError<EN::Error>> {
+        if nibble & (1 << 0) != 0 {
+            self.d4.set_high().map_err(DataBusError::PinSetError)?;
+        } else {
+            self.d4.set_low().map_err(DataBusError::PinSetError)?;
+        }
+        if nibble & (1 << 1) != 0 {
+            self.d5.set_high().map_err(DataBusError::PinSetError)?;
+        } else {
+            self.d5.set_low().map_err(DataBusError::PinSetError)?;
+        }
+        if nibble & (1 << 2) != 0 {
+            self.d6.set_high().map_err(DataBusError::PinSetError)?;
+        } else {
+            self.d6.set_low().map_err(DataBusError::PinSetError)?;
+        }
+        if nibble & (1 << 3) != 0 {
+            self.d7.set_high().map_err(DataBusError::PinSetError)?;
+        } else {
+            self.d7.set_low().map_err(DataBusError::PinSetError)?;
+        }
+        Ok(())
+    }
+
+    /// Send a full byte as two nibbles, high nibble first. Register must already be selected.
+    fn send_byte<D: Delay<H>>(
+        &mut self,
+        byte: u8,
+        delay: &mut D,
+    ) -> Result<(), DataBusError<EN::Error>> {
+        self.set_pins(byte >> 4)?;
+        self.enable(delay)?;
+        self.set_pins(byte & 0x0F)?;
+        self.enable(delay)
+    }
+
+    /// Enable pulse.
+    fn enable<D: Delay<H>>(&mut self, delay: &mut D) -> Result<(), DataBusError<EN::Error>> {
+        delay.delay_us(self.timing.address_setup_us);
+        self.en.start_transaction()?;
+        delay.delay_us(self.timing.enable_pulse_us);
+        self.en.end_transaction()?;
+        Ok(())
+    }
+}
+
+impl<EN, RS, D4, D5, D6, D7, H> DataBus for WriteOnlyBus4<EN, RS, D4, D5, D6, D7, H>
+where
+    EN: OutputPin<H>,
+    RS: OutputPin<H, Error = EN::Error>,
+    D4: OutputPin<H, Error = EN::Error>,
+    D5: OutputPin<H, Error = EN::Error>,
+    D6: OutputPin<H, Error = EN::Error>,
+    D7: OutputPin<H, Error = EN::Error>,
+{
+    type Error = DataBusError<EN::Error>;
+    type Hal = H;
+
+    fn timing(&self) -> &Timing {
+        &self.timing
+    }
+
+    fn set_timing(&mut self, timing: Timing) {
+        self.timing = timing;
+    }
+
+    fn write_byte<D: Delay<H>>(
+        &mut self,
+        byte: u8,
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        match transaction {
+            TransactionType::Instruction => self.rs.select_instruction_register()?,
+            TransactionType::Data => self.rs.select_data_register()?,
+        };
+        self.send_byte(byte, delay)
+    }
+
+    fn write_bytes<D: Delay<H>>(
+        &mut self,
+        bytes: &[u8],
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        match transaction {
+            TransactionType::Instruction => self.rs.select_instruction_register()?,
+            TransactionType::Data => self.rs.select_data_register()?,
+        };
+        for (i, byte) in bytes.iter().enumerate() {
+            if i != 0 {
+                delay.delay_us(self.timing.execution_us);
+            }
+            self.send_byte(*byte, delay)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<EN, RS, D4, D5, D6, D7, H> FourBitBus for WriteOnlyBus4<EN, RS, D4, D5, D6, D7, H>
+where
+    EN: OutputPin<H>,
+    RS: OutputPin<H, Error = EN::Error>,
+    D4: OutputPin<H, Error = EN::Error>,
+    D5: OutputPin<H, Error = EN::Error>,
+    D6: OutputPin<H, Error = EN::Error>,
+    D7: OutputPin<H, Error = EN::Error>,
+{
+    fn write_nibble<D: Delay<H>>(
+        &mut self,
+        nibble: u8,
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        match transaction {
+            TransactionType::Instruction => self.rs.select_instruction_register()?,
+            TransactionType::Data => self.rs.select_data_register()?,
+        };
+        self.set_pins(nibble)?;
+        self.enable(delay)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<EN, RS, D4, D5, D6, D7> WriteOnlyBus4<EN, RS, D4, D5, D6, D7, Eh1>
+where
+    EN: hal_1::digital::OutputPin,
+    RS: hal_1::digital::OutputPin<Error = EN::Error>,
+    D4: hal_1::digital::OutputPin<Error = EN::Error>,
+    D5: hal_1::digital::OutputPin<Error = EN::Error>,
+    D6: hal_1::digital::OutputPin<Error = EN::Error>,
+    D7: hal_1::digital::OutputPin<Error = EN::Error>,
+{
+    /// Send a full byte as two nibbles, high nibble first, awaiting the enable pulse delays.
+    async fn send_byte_async<D: DelayNs>(
+        &mut self,
+        byte: u8,
+        delay: &mut D,
+    ) -> Result<(), DataBusError<EN::Error>> {
+        self.set_pins(byte >> 4)?;
+        self.enable_async(delay).await?;
+        self.set_pins(byte & 0x0F)?;
+        self.enable_async(delay).await
+    }
+
+    /// Enable pulse, awaiting the setup and pulse width delays.
+    async fn enable_async<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<(), DataBusError<EN::Error>> {
+        delay.delay_us(self.timing.address_setup_us.into()).await;
+        self.en.start_transaction()?;
+        delay.delay_us(self.timing.enable_pulse_us.into()).await;
+        self.en.end_transaction()
+    }
+}
+
+#[cfg(feature = "async")]
+impl<EN, RS, D4, D5, D6, D7> AsyncDataBus for WriteOnlyBus4<EN, RS, D4, D5, D6, D7, Eh1>
+where
+    EN: hal_1::digital::OutputPin,
+    RS: hal_1::digital::OutputPin<Error = EN::Error>,
+    D4: hal_1::digital::OutputPin<Error = EN::Error>,
+    D5: hal_1::digital::OutputPin<Error = EN::Error>,
+    D6: hal_1::digital::OutputPin<Error = EN::Error>,
+    D7: hal_1::digital::OutputPin<Error = EN::Error>,
+{
+    type Error = DataBusError<EN::Error>;
+
+    fn timing(&self) -> &Timing {
+        &self.timing
+    }
+
+    fn set_timing(&mut self, timing: Timing) {
+        self.timing = timing;
+    }
+
+    async fn write_byte<D: DelayNs>(
+        &mut self,
+        byte: u8,
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        match transaction {
+            TransactionType::Instruction => self.rs.select_instruction_register()?,
+            TransactionType::Data => self.rs.select_data_register()?,
+        };
+        self.send_byte_async(byte, delay).await
+    }
+
+    async fn write_bytes<D: DelayNs>(
+        &mut self,
+        bytes: &[u8],
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        match transaction {
+            TransactionType::Instruction => self.rs.select_instruction_register()?,
+            TransactionType::Data => self.rs.select_data_register()?,
+        };
+        for (i, byte) in bytes.iter().enumerate() {
+            if i != 0 {
+                delay.delay_us(self.timing.execution_us.into()).await;
+            }
+            self.send_byte_async(*byte, delay).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<EN, RS, D4, D5, D6, D7> AsyncFourBitBus for WriteOnlyBus4<EN, RS, D4, D5, D6, D7, Eh1>
+where
+    EN: hal_1::digital::OutputPin,
+    RS: hal_1::digital::OutputPin<Error = EN::Error>,
+    D4: hal_1::digital::OutputPin<Error = EN::Error>,
+    D5: hal_1::digital::OutputPin<Error = EN::Error>,
+    D6: hal_1::digital::OutputPin<Error = EN::Error>,
+    D7: hal_1::digital::OutputPin<Error = EN::Error>,
+{
+    async fn write_nibble<D: DelayNs>(
+        &mut self,
+        nibble: u8,
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        match transaction {
+            TransactionType::Instruction => self.rs.select_instruction_register()?,
+            TransactionType::Data => self.rs.select_data_register()?,
+        };
+        self.set_pins(nibble)?;
+        self.enable_async(delay).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{Delay, Pin, Port};
+
+    extern crate std;
+    use std::vec::Vec;
+
+    /// (RS, nibble) latched at each falling edge of E, with E on bit 0, RS on bit 1 and D4-D7 on
+    /// bits 2-5.
+    fn nibbles(port: &Port) -> Vec<(u32, u32)> {
+        port.latched(0)
+            .iter()
+            .map(|levels| (levels >> 1 & 1, levels >> 2 & 0x0F))
+            .collect()
+    }
+
+    fn bus(port: &Port) -> WriteOnlyBus4<Pin, Pin, Pin, Pin, Pin, Pin> {
+        WriteOnlyBus4::from_pins(
+            port.pin(0),
+            port.pin(1),
+            port.pin(2),
+            port.pin(3),
+            port.pin(4),
+            port.pin(5),
+        )
+    }
+
+    #[test]
+    fn bytes_are_sent_high_nibble_first() {
+        let port = Port::default();
+        let mut bus = bus(&port);
+        let mut delay = Delay::default();
+
+        bus.write_byte(0xA5, TransactionType::Data, &mut delay)
+            .unwrap();
+        bus.write_bytes(&[0x12, 0x3C], TransactionType::Instruction, &mut delay)
+            .unwrap();
+
+        assert_eq!(
+            nibbles(&port),
+            [(1, 0xA), (1, 0x5), (0, 0x1), (0, 0x2), (0, 0x3), (0, 0xC)]
+        );
+    }
+
+    #[test]
+    fn nibble_is_sent_alone() {
+        let port = Port::default();
+        let mut bus = bus(&port);
+
+        bus.write_nibble(0x13, TransactionType::Instruction, &mut Delay::default())
+            .unwrap();
+
+        assert_eq!(nibbles(&port), [(0, 0x3)]);
+        assert_eq!(port.levels() & 1, 0);
+    }
+
+    #[test]
+    fn transfers_follow_the_timing_profile() {
+        let port = Port::default();
+        let mut bus = bus(&port);
+        bus.set_timing(Timing::slow());
+        let mut delay = Delay::default();
+
+        bus.write_bytes(&[0x41, 0x42], TransactionType::Data, &mut delay)
+            .unwrap();
+
+        // Address setup and enable pulse for each nibble, execution time between the bytes
+        assert_eq!(delay.log, [1, 2, 1, 2, 120, 1, 2, 1, 2]);
+    }
+}
@@ -2,76 +2,363 @@
 //! todo! try pin arrays rather than 8 individual pins
 
 use crate::data_bus::*;
-use embedded_hal as hal;
-// use embedded_hal::blocking::digital::PinState;
-use hal::blocking::delay::{DelayMs, DelayUs};
-// use hal::blocking::digital::OutputPin;
+#[cfg(feature = "async")]
+use crate::hal::Eh1;
+use crate::hal::{Delay, Eh02, OutputPin};
+use crate::timing::Timing;
 use crate::DataBusError;
-use hal::digital::v2::OutputPin;
+#[cfg(feature = "async")]
+use embedded_hal_1 as hal_1;
+#[cfg(feature = "async")]
+use embedded_hal_async::delay::DelayNs;
 use pins::*;
 
-pub struct ReadWriteBus8 {
-    // has io pins
-    pin: u8,
+/// Direction the data pins are currently configured for.
+#[derive(Copy, Clone, PartialEq)]
+enum Direction {
+    Output,
+    Input,
 }
 
-impl ReadWriteBus8 {
-    fn from_pins() {}
-
-    fn release() {}
+/// 8-bit data bus with bidirectional data pins, allowing the busy flag, address counter and RAM
+/// contents to be read back from the device. Pin direction is only changed when switching between
+/// reads and writes.
+pub struct ReadWriteBus8<
+    EN: OutputPin<H>,
+    RS: OutputPin<H>,
+    RW: OutputPin<H>,
+    D0: IoPin,
+    D1: IoPin,
+    D2: IoPin,
+    D3: IoPin,
+    D4: IoPin,
+    D5: IoPin,
+    D6: IoPin,
+    D7: IoPin,
+    H = Eh02,
+> {
+    en: EnablePin<EN, H>,
+    rs: RegisterSelectPin<RS, H>,
+    rw: ReadWritePin<RW, H>,
+    d0: D0,
+    d1: D1,
+    d2: D2,
+    d3: D3,
+    d4: D4,
+    d5: D5,
+    d6: D6,
+    d7: D7,
+    direction: Option<Direction>,
+    timing: Timing,
 }
 
-impl ReadableDataBus for ReadWriteBus8 {
-    type Error = DataBusError;
+impl<EN, RS, RW, D0, D1, D2, D3, D4, D5, D6, D7, H>
+    ReadWriteBus8<EN, RS, RW, D0, D1, D2, D3, D4, D5, D6, D7, H>
+where
+    EN: OutputPin<H>,
+    RS: OutputPin<H, Error = EN::Error>,
+    RW: OutputPin<H, Error = EN::Error>,
+    D0: IoPin<Error = EN::Error>,
+    D1: IoPin<Error = EN::Error>,
+    D2: IoPin<Error = EN::Error>,
+    D3: IoPin<Error = EN::Error>,
+    D4: IoPin<Error = EN::Error>,
+    D5: IoPin<Error = EN::Error>,
+    D6: IoPin<Error = EN::Error>,
+    D7: IoPin<Error = EN::Error>,
+{
+    /// Create a new instance of a read-write 8-bit bus from a group of pins. Data pins are
+    /// configured on the first transfer.
+    pub fn from_pins(
+        en: EN,
+        rs: RS,
+        rw: RW,
+        d0: D0,
+        d1: D1,
+        d2: D2,
+        d3: D3,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+    ) -> ReadWriteBus8<EN, RS, RW, D0, D1, D2, D3, D4, D5, D6, D7, H> {
+        ReadWriteBus8 {
+            en: EnablePin::from_output_pin(en),
+            rs: RegisterSelectPin::from_output_pin(rs),
+            rw: ReadWritePin::from_output_pin(rw),
+            d0,
+            d1,
+            d2,
+            d3,
+            d4,
+            d5,
+            d6,
+            d7,
+            // Unknown until the first transfer configures the data pins
+            direction: None,
+            timing: Default::default(),
+        }
+    }
 
-    fn read_busy_flag<D: DelayMs<u16> + DelayUs<u16>>(
-        &mut self,
-        delay: &mut D,
-    ) -> Result<bool, Self::Error> {
-        todo!()
+    /// Destroy the bus and return the pins.
+    pub fn release(self) -> (EN, RS, RW, D0, D1, D2, D3, D4, D5, D6, D7) {
+        (
+            self.en.release(),
+            self.rs.release(),
+            self.rw.release(),
+            self.d0,
+            self.d1,
+            self.d2,
+            self.d3,
+            self.d4,
+            self.d5,
+            self.d6,
+            self.d7,
+        )
+    }
+
+    /// Configure the data pins as outputs and select write mode.
+    fn set_output(&mut self) -> Result<(), DataBusError<EN::Error>> {
+        if self.direction == Some(Direction::Output) {
+            return Ok(());
+        }
+        // Device must stop driving the data lines before the pins are driven
+        self.rw.set_write()?;
+        self.d0.set_as_output().map_err(DataBusError::PinSetError)?;
+        self.d1.set_as_output().map_err(DataBusError::PinSetError)?;
+        self.d2.set_as_output().map_err(DataBusError::PinSetError)?;
+        self.d3.set_as_output().map_err(DataBusError::PinSetError)?;
+        self.d4.set_as_output().map_err(DataBusError::PinSetError)?;
+        self.d5.set_as_output().map_err(DataBusError::PinSetError)?;
+        self.d6.set_as_output().map_err(DataBusError::PinSetError)?;
+        self.d7.set_as_output().map_err(DataBusError::PinSetError)?;
+        self.direction = Some(Direction::Output);
+        Ok(())
+    }
+
+    /// Configure the data pins as inputs and select read mode.
+    fn set_input(&mut self) -> Result<(), DataBusError<EN::Error>> {
+        if self.direction == Some(Direction::Input) {
+            return Ok(());
+        }
+        // Release the data lines before the device starts driving them
+        self.d0.set_as_input().map_err(DataBusError::PinReadError)?;
+        self.d1.set_as_input().map_err(DataBusError::PinReadError)?;
+        self.d2.set_as_input().map_err(DataBusError::PinReadError)?;
+        self.d3.set_as_input().map_err(DataBusError::PinReadError)?;
+        self.d4.set_as_input().map_err(DataBusError::PinReadError)?;
+        self.d5.set_as_input().map_err(DataBusError::PinReadError)?;
+        self.d6.set_as_input().map_err(DataBusError::PinReadError)?;
+        self.d7.set_as_input().map_err(DataBusError::PinReadError)?;
+        self.rw.set_read()?;
+        self.direction = Some(Direction::Input);
+        Ok(())
+    }
+
+    /// Set the data pins to the provided value
+    pub fn set_pins(&mut self, data: u8) -> Result<(), DataBusError<EN::Error>> {
+        if data & (1 << 0) != 0 {
+            self.d0.set_high().map_err(DataBusError::PinSetError)?;
+        } else {
+            self.d0.set_low().map_err(DataBusError::PinSetError)?;
+        }
+        if data & (1 << 1) != 0 {
+            self.d1.set_high().map_err(DataBusError::PinSetError)?;
+        } else {
+            self.d1.set_low().map_err(DataBusError::PinSetError)?;
+        }
+        if data & (1 << 2) != 0 {
+            self.d2.set_high().map_err(DataBusError::PinSetError)?;
+        } else {
+            self.d2.set_low().map_err(DataBusError::PinSetError)?;
+        }
+        if data & (1 << 3) != 0 {
+            self.d3.set_high().map_err(DataBusError::PinSetError)?;
+        } else {
+            self.d3.set_low().map_err(DataBusError::PinSetError)?;
+        }
+        if data & (1 << 4) != 0 {
+            self.d4.set_high().map_err(DataBusError::PinSetError)?;
+        } else {
+            self.d4.set_low().map_err(DataBusError::PinSetError)?;
+        }
+        if data & (1 << 5) != 0 {
+            self.d5.set_high().map_err(DataBusError::PinSetError)?;
+        } else {
+            self.d5.set_low().map_err(DataBusError::PinSetError)?;
+        }
+        if data & (1 << 6) != 0 {
+            self.d6.set_high().map_err(DataBusError::PinSetError)?;
+        } else {
+            self.d6.set_low().map_err(DataBusError::PinSetError)?;
+        }
+        if data & (1 << 7) != 0 {
+            self.d7.set_high().map_err(DataBusError::PinSetError)?;
+        } else {
+            self.d7.set_low().map_err(DataBusError::PinSetError)?;
+        }
+        Ok(())
     }
 
-    fn read_address_counter<D: DelayMs<u16> + DelayUs<u16>>(
+    /// Read the value currently driven onto the data pins by the device.
+    pub fn get_pins(&self) -> Result<u8, DataBusError<EN::Error>> {
+        let mut data = 0;
+        if self.d0.is_high().map_err(DataBusError::PinReadError)? {
+            data |= 1 << 0;
+        }
+        if self.d1.is_high().map_err(DataBusError::PinReadError)? {
+            data |= 1 << 1;
+        }
+        if self.d2.is_high().map_err(DataBusError::PinReadError)? {
+            data |= 1 << 2;
+        }
+        if self.d3.is_high().map_err(DataBusError::PinReadError)? {
+            data |= 1 << 3;
+        }
+        if self.d4.is_high().map_err(DataBusError::PinReadError)? {
+            data |= 1 << 4;
+        }
+        if self.d5.is_high().map_err(DataBusError::PinReadError)? {
+            data |= 1 << 5;
+        }
+        if self.d6.is_high().map_err(DataBusError::PinReadError)? {
+            data |= 1 << 6;
+        }
+        if self.d7.is_high().map_err(DataBusError::PinReadError)? {
+            data |= 1 << 7;
+        }
+        Ok(data)
+    }
+
+    /// Enable pulse.
+    fn enable<D: Delay<H>>(&mut self, delay: &mut D) -> Result<(), DataBusError<EN::Error>> {
+        delay.delay_us(self.timing.address_setup_us);
+        self.en.start_transaction()?;
+        delay.delay_us(self.timing.enable_pulse_us);
+        self.en.end_transaction()?;
+        Ok(())
+    }
+
+    /// Read a byte from the instruction register ([busy flag:address counter]) or data register.
+    /// Data is sampled while enable is held high.
+    fn read_byte<D: Delay<H>>(
         &mut self,
+        transaction: TransactionType,
         delay: &mut D,
-    ) -> Result<u8, Self::Error> {
-        todo!()
+    ) -> Result<u8, DataBusError<EN::Error>> {
+        self.set_input()?;
+        match transaction {
+            TransactionType::Instruction => self.rs.select_instruction_register()?,
+            TransactionType::Data => self.rs.select_data_register()?,
+        };
+        delay.delay_us(self.timing.address_setup_us);
+        self.en.start_transaction()?;
+        delay.delay_us(self.timing.enable_pulse_us);
+        let data = self.get_pins();
+        self.en.end_transaction()?;
+        data
+    }
+}
+
+impl<EN, RS, RW, D0, D1, D2, D3, D4, D5, D6, D7, H> ReadableDataBus
+    for ReadWriteBus8<EN, RS, RW, D0, D1, D2, D3, D4, D5, D6, D7, H>
+where
+    EN: OutputPin<H>,
+    RS: OutputPin<H, Error = EN::Error>,
+    RW: OutputPin<H, Error = EN::Error>,
+    D0: IoPin<Error = EN::Error>,
+    D1: IoPin<Error = EN::Error>,
+    D2: IoPin<Error = EN::Error>,
+    D3: IoPin<Error = EN::Error>,
+    D4: IoPin<Error = EN::Error>,
+    D5: IoPin<Error = EN::Error>,
+    D6: IoPin<Error = EN::Error>,
+    D7: IoPin<Error = EN::Error>,
+{
+    fn read_busy_flag<D: Delay<H>>(&mut self, delay: &mut D) -> Result<bool, Self::Error> {
+        let (busy, _) = self.read_busy_flag_and_address_counter(delay)?;
+        Ok(busy)
     }
 
-    fn read_busy_flag_and_address_counter<D: DelayMs<u16> + DelayUs<u16>>(
+    fn read_address_counter<D: Delay<H>>(&mut self, delay: &mut D) -> Result<u8, Self::Error> {
+        let (_, address) = self.read_busy_flag_and_address_counter(delay)?;
+        Ok(address)
+    }
+
+    fn read_busy_flag_and_address_counter<D: Delay<H>>(
         &mut self,
         delay: &mut D,
     ) -> Result<(bool, u8), Self::Error> {
-        todo!()
+        let data = self.read_byte(TransactionType::Instruction, delay)?;
+        Ok((data & (1 << 7) != 0, data & !(1 << 7)))
     }
 
-    fn read_data<D: DelayMs<u16> + DelayUs<u16>>(
-        &mut self,
-        delay: &mut D,
-    ) -> Result<u8, Self::Error> {
-        todo!()
+    fn read_data<D: Delay<H>>(&mut self, delay: &mut D) -> Result<u8, Self::Error> {
+        self.read_byte(TransactionType::Data, delay)
     }
 }
 
-impl DataBus for ReadWriteBus8 {
-    type Error = ();
+impl<EN, RS, RW, D0, D1, D2, D3, D4, D5, D6, D7, H> DataBus
+    for ReadWriteBus8<EN, RS, RW, D0, D1, D2, D3, D4, D5, D6, D7, H>
+where
+    EN: OutputPin<H>,
+    RS: OutputPin<H, Error = EN::Error>,
+    RW: OutputPin<H, Error = EN::Error>,
+    D0: IoPin<Error = EN::Error>,
+    D1: IoPin<Error = EN::Error>,
+    D2: IoPin<Error = EN::Error>,
+    D3: IoPin<Error = EN::Error>,
+    D4: IoPin<Error = EN::Error>,
+    D5: IoPin<Error = EN::Error>,
+    D6: IoPin<Error = EN::Error>,
+    D7: IoPin<Error = EN::Error>,
+{
+    type Error = DataBusError<EN::Error>;
+    type Hal = H;
+
+    fn timing(&self) -> &Timing {
+        &self.timing
+    }
 
-    fn write_byte<D: DelayMs<u16> + DelayUs<u16>>(
+    fn set_timing(&mut self, timing: Timing) {
+        self.timing = timing;
+    }
+
+    fn write_byte<D: Delay<H>>(
         &mut self,
-        data: u8,
+        byte: u8,
         transaction: TransactionType,
         delay: &mut D,
     ) -> Result<(), Self::Error> {
-        todo!()
+        self.set_output()?;
+        match transaction {
+            TransactionType::Instruction => self.rs.select_instruction_register()?,
+            TransactionType::Data => self.rs.select_data_register()?,
+        };
+        self.set_pins(byte)?;
+        self.enable(delay)
     }
 
-    fn write_bytes<D: DelayMs<u16> + DelayUs<u16>>(
+    fn write_bytes<D: Delay<H>>(
         &mut self,
         bytes: &[u8],
         transaction: TransactionType,
         delay: &mut D,
     ) -> Result<(), Self::Error> {
-        todo!()
+        self.set_output()?;
+        match transaction {
+            TransactionType::Instruction => self.rs.select_instruction_register()?,
+            TransactionType::Data => self.rs.select_data_register()?,
+        };
+        for (i, byte) in bytes.iter().enumerate() {
+            if i != 0 {
+                delay.delay_us(self.timing.execution_us);
+            }
+            self.set_pins(*byte)?;
+            self.enable(delay)?;
+        }
+
+        Ok(())
     }
 }
 
@@ -79,19 +366,20 @@ impl DataBus for ReadWriteBus8 {
 
 /// 8-bit data bus with write-only data pins. Note: RW pin on device should be tied low.
 pub struct WriteOnlyBus8<
-    EN: OutputPin,
-    RS: OutputPin,
-    D0: OutputPin,
-    D1: OutputPin,
-    D2: OutputPin,
-    D3: OutputPin,
-    D4: OutputPin,
-    D5: OutputPin,
-    D6: OutputPin,
-    D7: OutputPin,
+    EN: OutputPin<H>,
+    RS: OutputPin<H>,
+    D0: OutputPin<H>,
+    D1: OutputPin<H>,
+    D2: OutputPin<H>,
+    D3: OutputPin<H>,
+    D4: OutputPin<H>,
+    D5: OutputPin<H>,
+    D6: OutputPin<H>,
+    D7: OutputPin<H>,
+    H = Eh02,
 > {
-    en: EnablePin<EN>,
-    rs: RegisterSelectPin<RS>,
+    en: EnablePin<EN, H>,
+    rs: RegisterSelectPin<RS, H>,
     d0: D0,
     d1: D1,
     d2: D2,
@@ -100,20 +388,22 @@ pub struct WriteOnlyBus8<
     d5: D5,
     d6: D6,
     d7: D7,
+    timing: Timing,
 }
 
-impl<
-        EN: OutputPin,
-        RS: OutputPin,
-        D0: OutputPin,
-        D1: OutputPin,
-        D2: OutputPin,
-        D3: OutputPin,
-        D4: OutputPin,
-        D5: OutputPin,
-        D6: OutputPin,
-        D7: OutputPin,
-    > WriteOnlyBus8<EN, RS, D0, D1, D2, D3, D4, D5, D6, D7>
+impl<EN, RS, D0, D1, D2, D3, D4, D5, D6, D7, H>
+    WriteOnlyBus8<EN, RS, D0, D1, D2, D3, D4, D5, D6, D7, H>
+where
+    EN: OutputPin<H>,
+    RS: OutputPin<H, Error = EN::Error>,
+    D0: OutputPin<H, Error = EN::Error>,
+    D1: OutputPin<H, Error = EN::Error>,
+    D2: OutputPin<H, Error = EN::Error>,
+    D3: OutputPin<H, Error = EN::Error>,
+    D4: OutputPin<H, Error = EN::Error>,
+    D5: OutputPin<H, Error = EN::Error>,
+    D6: OutputPin<H, Error = EN::Error>,
+    D7: OutputPin<H, Error = EN::Error>,
 {
     /// Create a new instance of a write-only 8-bit bus from a group of pins.
     pub fn from_pins(
@@ -127,7 +417,7 @@ impl<
         d5: D5,
         d6: D6,
         d7: D7,
-    ) -> WriteOnlyBus8<EN, RS, D0, D1, D2, D3, D4, D5, D6, D7> {
+    ) -> WriteOnlyBus8<EN, RS, D0, D1, D2, D3, D4, D5, D6, D7, H> {
         WriteOnlyBus8 {
             en: EnablePin::from_output_pin(en),
             rs: RegisterSelectPin::from_output_pin(rs),
@@ -139,6 +429,7 @@ impl<
             d5,
             d6,
             d7,
+            timing: Default::default(),
         }
     }
 
@@ -159,104 +450,69 @@ impl<
     }
 
     /// Set the data pins to the provided value
-    pub fn set_pins(&mut self, data: u8) -> Result<(), DataBusError> {
-        if data & (1 << 0) != 0 {
-            self.d0.set_high().map_err(|_| DataBusError::PinSetError)?;
-        } else {
-            self.d0.set_low().map_err(|_| DataBusError::PinSetError)?;
-        }
-        if data & (1 << 1) != 0 {
-            self.d1.set_high().map_err(|_| DataBusError::PinSetError)?;
-        } else {
-            self.d1.set_low().map_err(|_| DataBusError::PinSetError)?;
-        }
-        if data & (1 << 2) != 0 {
-            self.d2.set_high().map_err(|_| DataBusError::PinSetError)?;
-        } else {
-            self.d2.set_low().map_err(|_| DataBusError::PinSetError)?;
-        }
-        if data & (1 << 3) != 0 {
-            self.d3.set_high().map_err(|_| DataBusError::PinSetError)?;
-        } else {
-            self.d3.set_low().map_err(|_| DataBusError::PinSetError)?;
-        }
-        if data & (1 << 4) != 0 {
-            self.d4.set_high().map_err(|_| DataBusError::PinSetError)?;
-        } else {
-            self.d4.set_low().map_err(|_| DataBusError::PinSetError)?;
-        }
-        if data & (1 << 5) != 0 {
-            self.d5.set_high().map_err(|_| DataBusError::PinSetError)?;
-        } else {
-            self.d5.set_low().map_err(|_| DataBusError::PinSetError)?;
-        }
-        if data & (1 << 6) != 0 {
-            self.d6.set_high().map_err(|_| DataBusError::PinSetError)?;
-        } else {
-            self.d6.set_low().map_err(|_| DataBusError::PinSetError)?;
-        }
-        if data & (1 << 7) != 0 {
-            self.d7.set_high().map_err(|_| DataBusError::PinSetError)?;
-        } else {
-            self.d7.set_low().map_err(|_| DataBusError::PinSetError)?;
-        }
-        Ok(())
-        // todo! embedded-hal-alpha option
-        // self.d0
-        //     .set_state(PinState::from(data & (1 << 0) != 0))
-        //     .map_err(|_| DataBusError::PinSetError)?;
-        // self.d1
-        //     .set_state(PinState::from(data & (1 << 1) != 0))
-        //     .map_err(|_| DataBusError::PinSetError)?;
-        // self.d2
-        //     .set_state(PinState::from(data & (1 << 2) != 0))
-        //     .map_err(|_| DataBusError::PinSetError)?;
-        // self.d3
-        //     .set_state(PinState::from(data & (1 << 3) != 0))
-        //     .map_err(|_| DataBusError::PinSetError)?;
-        // self.d4
-        //     .set_state(PinState::from(data & (1 << 4) != 0))
-        //     .map_err(|_| DataBusError::PinSetError)?;
-        // self.d5
-        //     .set_state(PinState::from(data & (1 << 5) != 0))
-        //     .map_err(|_| DataBusError::PinSetError)?;
-        // self.d6
-        //     .set_state(PinState::from(data & (1 << 6) != 0))
-        //     .map_err(|_| DataBusError::PinSetError)?;
-        // self.d7
-        //     .set_state(PinState::from(data & (1 << 7) != 0))
-        //     .map_err(|_| DataBusError::PinSetError)
+    pub fn set_pins(&mut self, data: u8) -> Result<(), DataBusError<EN::Error>> {
+        self.d0
+            .set_state(data & (1 << 0) != 0)
+            .map_err(DataBusError::PinSetError)?;
+        self.d1
+            .set_state(data & (1 << 1) != 0)
+            .map_err(DataBusError::PinSetError)?;
+        self.d2
+            .set_state(data & (1 << 2) != 0)
+            .map_err(DataBusError::PinSetError)?;
+        self.d3
+            .set_state(data & (1 << 3) != 0)
+            .map_err(DataBusError::PinSetError)?;
+        self.d4
+            .set_state(data & (1 << 4) != 0)
+            .map_err(DataBusError::PinSetError)?;
+        self.d5
+            .set_state(data & (1 << 5) != 0)
+            .map_err(DataBusError::PinSetError)?;
+        self.d6
+            .set_state(data & (1 << 6) != 0)
+            .map_err(DataBusError::PinSetError)?;
+        self.d7
+            .set_state(data & (1 << 7) != 0)
+            .map_err(DataBusError::PinSetError)
     }
 
     /// Enable pulse.
-    fn enable<D: DelayMs<u16> + DelayUs<u16>>(
-        &mut self,
-        delay: &mut D,
-    ) -> Result<(), DataBusError> {
+    fn enable<D: Delay<H>>(&mut self, delay: &mut D) -> Result<(), DataBusError<EN::Error>> {
+        delay.delay_us(self.timing.address_setup_us);
         self.en.start_transaction()?;
-        delay.delay_ms(2); //.map_err(|_| DataBusError::DelayError)?;
+        delay.delay_us(self.timing.enable_pulse_us);
         self.en.end_transaction()?;
         Ok(())
     }
 }
 
-impl<EN, RS, D0, D1, D2, D3, D4, D5, D6, D7> DataBus
-    for WriteOnlyBus8<EN, RS, D0, D1, D2, D3, D4, D5, D6, D7>
+impl<EN, RS, D0, D1, D2, D3, D4, D5, D6, D7, H> DataBus
+    for WriteOnlyBus8<EN, RS, D0, D1, D2, D3, D4, D5, D6, D7, H>
 where
-    EN: OutputPin,
-    RS: OutputPin,
-    D0: OutputPin,
-    D1: OutputPin,
-    D2: OutputPin,
-    D3: OutputPin,
-    D4: OutputPin,
-    D5: OutputPin,
-    D6: OutputPin,
-    D7: OutputPin,
+    EN: OutputPin<H>,
+    RS: OutputPin<H, Error = EN::Error>,
+    D0: OutputPin<H, Error = EN::Error>,
+    D1: OutputPin<H, Error = EN::Error>,
+    D2: OutputPin<H, Error = EN::Error>,
+    D3: OutputPin<H, Error = EN::Error>,
+    D4: OutputPin<H, Error = EN::Error>,
+    D5: OutputPin<H, Error = EN::Error>,
+    D6: OutputPin<H, Error = EN::Error>,
+    D7: OutputPin<H, Error = EN::Error>,
 {
-    type Error = DataBusError;
+    type Error = DataBusError<EN::Error>;
+    type Hal = H;
+
+    fn timing(&self) -> &Timing {
+        &self.timing
+    }
 
-    fn write_byte<D: DelayMs<u16> + DelayUs<u16>>(
+    fn set_timing(&mut self, timing: Timing) {
+        self.timing = timing;
+    }
+
+    fn write_byte<D: Delay<H>>(
         &mut self,
         byte: u8,
         transaction: TransactionType,
@@ -267,11 +523,11 @@ where
             TransactionType::Data => self.rs.select_data_register()?,
         };
         self.set_pins(byte)?;
-        self.enable(delay).map_err(|_| DataBusError::DelayError)?;
+        self.enable(delay)?;
         Ok(())
     }
 
-    fn write_bytes<D: DelayMs<u16> + DelayUs<u16>>(
+    fn write_bytes<D: Delay<H>>(
         &mut self,
         bytes: &[u8],
         transaction: TransactionType,
@@ -281,11 +537,172 @@ where
             TransactionType::Instruction => self.rs.select_instruction_register()?,
             TransactionType::Data => self.rs.select_data_register()?,
         };
-        for byte in bytes {
+        for (i, byte) in bytes.iter().enumerate() {
+            if i != 0 {
+                delay.delay_us(self.timing.execution_us);
+            }
             self.set_pins(*byte)?;
-            self.enable(delay).map_err(|_| DataBusError::DelayError)?;
+            self.enable(delay)?;
         }
 
         Ok(())
     }
 }
+
+#[cfg(feature = "async")]
+impl<EN, RS, D0, D1, D2, D3, D4, D5, D6, D7>
+    WriteOnlyBus8<EN, RS, D0, D1, D2, D3, D4, D5, D6, D7, Eh1>
+where
+    EN: hal_1::digital::OutputPin,
+    RS: hal_1::digital::OutputPin<Error = EN::Error>,
+    D0: hal_1::digital::OutputPin<Error = EN::Error>,
+    D1: hal_1::digital::OutputPin<Error = EN::Error>,
+    D2: hal_1::digital::OutputPin<Error = EN::Error>,
+    D3: hal_1::digital::OutputPin<Error = EN::Error>,
+    D4: hal_1::digital::OutputPin<Error = EN::Error>,
+    D5: hal_1::digital::OutputPin<Error = EN::Error>,
+    D6: hal_1::digital::OutputPin<Error = EN::Error>,
+    D7: hal_1::digital::OutputPin<Error = EN::Error>,
+{
+    /// Enable pulse, awaiting the setup and pulse width delays.
+    async fn enable_async<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<(), DataBusError<EN::Error>> {
+        delay.delay_us(self.timing.address_setup_us.into()).await;
+        self.en.start_transaction()?;
+        delay.delay_us(self.timing.enable_pulse_us.into()).await;
+        self.en.end_transaction()
+    }
+}
+
+#[cfg(feature = "async")]
+impl<EN, RS, D0, D1, D2, D3, D4, D5, D6, D7> AsyncDataBus
+    for WriteOnlyBus8<EN, RS, D0, D1, D2, D3, D4, D5, D6, D7, Eh1>
+where
+    EN: hal_1::digital::OutputPin,
+    RS: hal_1::digital::OutputPin<Error = EN::Error>,
+    D0: hal_1::digital::OutputPin<Error = EN::Error>,
+    D1: hal_1::digital::OutputPin<Error = EN::Error>,
+    D2: hal_1::digital::OutputPin<Error = EN::Error>,
+    D3: hal_1::digital::OutputPin<Error = EN::Error>,
+    D4: hal_1::digital::OutputPin<Error = EN::Error>,
+    D5: hal_1::digital::OutputPin<Error = EN::Error>,
+    D6: hal_1::digital::OutputPin<Error = EN::Error>,
+    D7: hal_1::digital::OutputPin<Error = EN::Error>,
+{
+    type Error = DataBusError<EN::Error>;
+
+    fn timing(&self) -> &Timing {
+        &self.timing
+    }
+
+    fn set_timing(&mut self, timing: Timing) {
+        self.timing = timing;
+    }
+
+    async fn write_byte<D: DelayNs>(
+        &mut self,
+        byte: u8,
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        match transaction {
+            TransactionType::Instruction => self.rs.select_instruction_register()?,
+            TransactionType::Data => self.rs.select_data_register()?,
+        };
+        self.set_pins(byte)?;
+        self.enable_async(delay).await
+    }
+
+    async fn write_bytes<D: DelayNs>(
+        &mut self,
+        bytes: &[u8],
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        match transaction {
+            TransactionType::Instruction => self.rs.select_instruction_register()?,
+            TransactionType::Data => self.rs.select_data_register()?,
+        };
+        for (i, byte) in bytes.iter().enumerate() {
+            if i != 0 {
+                delay.delay_us(self.timing.execution_us.into()).await;
+            }
+            self.set_pins(*byte)?;
+            self.enable_async(delay).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{BidirectionalPin, Delay, Pin, Port};
+
+    type Bus = ReadWriteBus8<
+        Pin,
+        Pin,
+        Pin,
+        BidirectionalPin,
+        BidirectionalPin,
+        BidirectionalPin,
+        BidirectionalPin,
+        BidirectionalPin,
+        BidirectionalPin,
+        BidirectionalPin,
+        BidirectionalPin,
+    >;
+
+    /// Bus with E on bit 0, RS on bit 1, RW on bit 2 and D0-D7 on bits 3-10.
+    fn bus(port: &Port) -> Bus {
+        ReadWriteBus8::from_pins(
+            port.pin(0),
+            port.pin(1),
+            port.pin(2),
+            port.io_pin(3),
+            port.io_pin(4),
+            port.io_pin(5),
+            port.io_pin(6),
+            port.io_pin(7),
+            port.io_pin(8),
+            port.io_pin(9),
+            port.io_pin(10),
+        )
+    }
+
+    #[test]
+    fn busy_flag_and_address_are_read_with_data_pins_released() {
+        let port = Port::default();
+        let mut bus = bus(&port);
+        port.drive(0xA5 << 3);
+
+        let (busy, address) = bus
+            .read_busy_flag_and_address_counter(&mut Delay::default())
+            .unwrap();
+
+        assert!(busy);
+        assert_eq!(address, 0x25);
+        assert_eq!(port.inputs(), 0xFF << 3);
+        // RW high and RS low while E is pulsed
+        assert_eq!(port.latched(0)[0] & 0b110, 0b100);
+    }
+
+    #[test]
+    fn write_after_read_drives_data_pins_again() {
+        let port = Port::default();
+        let mut bus = bus(&port);
+        port.drive(0x12 << 3);
+        assert_eq!(bus.read_data(&mut Delay::default()).unwrap(), 0x12);
+
+        bus.write_byte(0xC3, TransactionType::Data, &mut Delay::default())
+            .unwrap();
+
+        assert_eq!(port.inputs(), 0);
+        let levels = *port.latched(0).last().unwrap();
+        assert_eq!(levels & 0b110, 0b010);
+        assert_eq!(levels >> 3 & 0xFF, 0xC3);
+    }
+}
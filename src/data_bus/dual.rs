@@ -0,0 +1,238 @@
+//! 4-bit wide data bus shared by two controllers, as on 40x4 displays: the data and register select
+//! pins are connected to both, each controller has its own enable pin and only latches transfers
+//! while it is enabled.
+
+use crate::data_bus::*;
+use crate::hal::{Delay, Eh02, OutputPin};
+use crate::timing::Timing;
+use crate::DataBusError;
+use pins::*;
+
+/// 4-bit data bus with write-only data pins and an enable pin per controller. Note: RW pin on
+/// device should be tied low and D0-D3 left unconnected.
+pub struct WriteOnlyDualBus4<
+    EN1: OutputPin<H>,
+    EN2: OutputPin<H>,
+    RS: OutputPin<H>,
+    D4: OutputPin<H>,
+    D5: OutputPin<H>,
+    D6: OutputPin<H>,
+    D7: OutputPin<H>,
+    H = Eh02,
+> {
+    en1: EnablePin<EN1, H>,
+    en2: EnablePin<EN2, H>,
+    rs: RegisterSelectPin<RS, H>,
+    d4: D4,
+    d5: D5,
+    d6: D6,
+    d7: D7,
+    selected: EnableSelect,
+    timing: Timing,
+}
+
+impl<EN1, EN2, RS, D4, D5, D6, D7, H> WriteOnlyDualBus4<EN1, EN2, RS, D4, D5, D6, D7, H>
+where
+    EN1: OutputPin<H>,
+    EN2: OutputPin<H, Error = EN1::Error>,
+    RS: OutputPin<H, Error = EN1::Error>,
+    D4: OutputPin<H, Error = EN1::Error>,
+    D5: OutputPin<H, Error = EN1::Error>,
+    D6: OutputPin<H, Error = EN1::Error>,
+    D7: OutputPin<H, Error = EN1::Error>,
+{
+    /// Create a new instance of a write-only dual controller 4-bit bus from a group of pins. Both
+    /// controllers are selected.
+    pub fn from_pins(
+        en1: EN1,
+        en2: EN2,
+        rs: RS,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+    ) -> WriteOnlyDualBus4<EN1, EN2, RS, D4, D5, D6, D7, H> {
+        WriteOnlyDualBus4 {
+            en1: EnablePin::from_output_pin(en1),
+            en2: EnablePin::from_output_pin(en2),
+            rs: RegisterSelectPin::from_output_pin(rs),
+            d4,
+            d5,
+            d6,
+            d7,
+            selected: EnableSelect::Both,
+            timing: Default::default(),
+        }
+    }
+
+    /// Destroy the bus and return the pins.
+    pub fn release(self) -> (EN1, EN2, RS, D4, D5, D6, D7) {
+        (
+            self.en1.release(),
+            self.en2.release(),
+            self.rs.release(),
+            self.d4,
+            self.d5,
+            self.d6,
+            self.d7,
+        )
+    }
+
+    /// Set the data pins to the lower nibble of the provided value
+    pub fn set_pins(&mut self, nibble: u8) -> Result<(), DataBusError<EN1::Error>> {
+        if nibble & (1 << 0) != 0 {
+            self.d4.set_high().map_err(DataBusError::PinSetError)?;
+        } else {
+            self.d4.set_low().map_err(DataBusError::PinSetError)?;
+        }
+        if nibble & (1 << 1) != 0 {
+            self.d5.set_high().map_err(DataBusError::PinSetError)?;
+        } else {
+            self.d5.set_low().map_err(DataBusError::PinSetError)?;
+        }
+        if nibble & (1 << 2) != 0 {
+            self.d6.set_high().map_err(DataBusError::PinSetError)?;
+        } else {
+            self.d6.set_low().map_err(DataBusError::PinSetError)?;
+        }
+        if nibble & (1 << 3) != 0 {
+            self.d7.set_high().map_err(DataBusError::PinSetError)?;
+        } else {
+            self.d7.set_low().map_err(DataBusError::PinSetError)?;
+        }
+        Ok(())
+    }
+
+    /// Send a full byte as two nibbles, high nibble first. Register must already be selected.
+    fn send_byte<D: Delay<H>>(
+        &mut self,
+        byte: u8,
+        delay: &mut D,
+    ) -> Result<(), DataBusError<EN1::Error>> {
+        self.set_pins(byte >> 4)?;
+        self.enable(delay)?;
+        self.set_pins(byte & 0x0F)?;
+        self.enable(delay)
+    }
+
+    /// Enable pulse on the selected controllers, simultaneously when both are selected.
+    fn enable<D: Delay<H>>(&mut self, delay: &mut D) -> Result<(), DataBusError<EN1::Error>> {
+        let (first, second) = match self.selected {
+            EnableSelect::First => (true, false),
+            EnableSelect::Second => (false, true),
+            EnableSelect::Both => (true, true),
+        };
+        delay.delay_us(self.timing.address_setup_us);
+        if first {
+            self.en1.start_transaction()?;
+        }
+        if second {
+            self.en2.start_transaction()?;
+        }
+        delay.delay_us(self.timing.enable_pulse_us);
+        if first {
+            self.en1.end_transaction()?;
+        }
+        if second {
+            self.en2.end_transaction()?;
+        }
+        Ok(())
+    }
+}
+
+impl<EN1, EN2, RS, D4, D5, D6, D7, H> DataBus for WriteOnlyDualBus4<EN1, EN2, RS, D4, D5, D6, D7, H>
+where
+    EN1: OutputPin<H>,
+    EN2: OutputPin<H, Error = EN1::Error>,
+    RS: OutputPin<H, Error = EN1::Error>,
+    D4: OutputPin<H, Error = EN1::Error>,
+    D5: OutputPin<H, Error = EN1::Error>,
+    D6: OutputPin<H, Error = EN1::Error>,
+    D7: OutputPin<H, Error = EN1::Error>,
+{
+    type Error = DataBusError<EN1::Error>;
+    type Hal = H;
+
+    fn timing(&self) -> &Timing {
+        &self.timing
+    }
+
+    fn set_timing(&mut self, timing: Timing) {
+        self.timing = timing;
+    }
+
+    fn write_byte<D: Delay<H>>(
+        &mut self,
+        byte: u8,
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        match transaction {
+            TransactionType::Instruction => self.rs.select_instruction_register()?,
+            TransactionType::Data => self.rs.select_data_register()?,
+        };
+        self.send_byte(byte, delay)
+    }
+
+    fn write_bytes<D: Delay<H>>(
+        &mut self,
+        bytes: &[u8],
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        match transaction {
+            TransactionType::Instruction => self.rs.select_instruction_register()?,
+            TransactionType::Data => self.rs.select_data_register()?,
+        };
+        for (i, byte) in bytes.iter().enumerate() {
+            if i != 0 {
+                delay.delay_us(self.timing.execution_us);
+            }
+            self.send_byte(*byte, delay)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<EN1, EN2, RS, D4, D5, D6, D7, H> FourBitBus
+    for WriteOnlyDualBus4<EN1, EN2, RS, D4, D5, D6, D7, H>
+where
+    EN1: OutputPin<H>,
+    EN2: OutputPin<H, Error = EN1::Error>,
+    RS: OutputPin<H, Error = EN1::Error>,
+    D4: OutputPin<H, Error = EN1::Error>,
+    D5: OutputPin<H, Error = EN1::Error>,
+    D6: OutputPin<H, Error = EN1::Error>,
+    D7: OutputPin<H, Error = EN1::Error>,
+{
+    fn write_nibble<D: Delay<H>>(
+        &mut self,
+        nibble: u8,
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        match transaction {
+            TransactionType::Instruction => self.rs.select_instruction_register()?,
+            TransactionType::Data => self.rs.select_data_register()?,
+        };
+        self.set_pins(nibble)?;
+        self.enable(delay)
+    }
+}
+
+impl<EN1, EN2, RS, D4, D5, D6, D7, H> DualEnableBus
+    for WriteOnlyDualBus4<EN1, EN2, RS, D4, D5, D6, D7, H>
+where
+    EN1: OutputPin<H>,
+    EN2: OutputPin<H, Error = EN1::Error>,
+    RS: OutputPin<H, Error = EN1::Error>,
+    D4: OutputPin<H, Error = EN1::Error>,
+    D5: OutputPin<H, Error = EN1::Error>,
+    D6: OutputPin<H, Error = EN1::Error>,
+    D7: OutputPin<H, Error = EN1::Error>,
+{
+    fn select(&mut self, target: EnableSelect) {
+        self.selected = target;
+    }
+}
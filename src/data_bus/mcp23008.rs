@@ -0,0 +1,275 @@
+//! 4-bit data bus through an MCP23008 (I2C) or MCP23S08 (SPI) I/O expander, as used on the Adafruit
+//! I2C/SPI character LCD backpack. RS, E, the backlight and D4-D7 are driven from the expander
+//! outputs (GP0-GP7); RW must be tied low.
+
+use crate::data_bus::mcp23x::WriteRegister;
+use crate::data_bus::*;
+use crate::hal::Delay;
+use crate::timing::Timing;
+use crate::DataBusError;
+
+/// I/O direction register, a set bit makes the pin an input.
+const IODIR: u8 = 0x00;
+
+/// Configuration register.
+const IOCON: u8 = 0x05;
+
+/// Output latch register.
+const OLAT: u8 = 0x0A;
+
+/// Hardware address enable bit of IOCON (MCP23S08 only).
+const IOCON_HAEN: u8 = 0x08;
+
+/// Assignment of the expander pins (GP0-GP7) to the display pins, as bit numbers 0-7.
+#[derive(Copy, Clone, Debug)]
+pub struct Mcp23008Mapping {
+    rs: u8,
+    en: u8,
+    backlight: u8,
+    data: [u8; 4],
+    backlight_active_high: bool,
+}
+
+impl Mcp23008Mapping {
+    /// Custom mapping. `data` holds the expander pins of D4-D7, in that order. Returns
+    /// `InvalidPinMapping` if a pin is greater than 7 or assigned twice.
+    pub fn new<E>(
+        rs: u8,
+        en: u8,
+        backlight: u8,
+        data: [u8; 4],
+        backlight_active_high: bool,
+    ) -> Result<Self, DataBusError<E>> {
+        let mapping = Mcp23008Mapping {
+            rs,
+            en,
+            backlight,
+            data,
+            backlight_active_high,
+        };
+        check_pin_mapping(&mapping.pins(), &[], 8)?;
+        Ok(mapping)
+    }
+
+    /// Mapping of the Adafruit I2C/SPI backpack: RS=GP1, E=GP2, D4-D7=GP3-GP6, backlight=GP7
+    /// (active high).
+    pub const fn adafruit_backpack() -> Self {
+        Mcp23008Mapping {
+            rs: 1,
+            en: 2,
+            backlight: 7,
+            data: [3, 4, 5, 6],
+            backlight_active_high: true,
+        }
+    }
+
+    /// Every expander pin used by the mapping.
+    fn pins(&self) -> [u8; 7] {
+        let [d4, d5, d6, d7] = self.data;
+        [self.rs, self.en, self.backlight, d4, d5, d6, d7]
+    }
+
+    /// Expander output byte driving the lower nibble of `nibble` onto D4-D7.
+    fn data_bits(&self, nibble: u8) -> u8 {
+        let mut bits = 0;
+        for (i, pin) in self.data.iter().enumerate() {
+            if nibble & (1 << i) != 0 {
+                bits |= 1 << pin;
+            }
+        }
+        bits
+    }
+
+    /// Pins driven by the bus. All others are left as inputs.
+    fn outputs(&self) -> u8 {
+        1 << self.rs | 1 << self.en | 1 << self.backlight | self.data_bits(0x0F)
+    }
+}
+
+impl Default for Mcp23008Mapping {
+    fn default() -> Self {
+        Mcp23008Mapping::adafruit_backpack()
+    }
+}
+
+/// 4-bit data bus through an MCP23008 or MCP23S08 I/O expander.
+pub struct Mcp23008Bus<IF: WriteRegister> {
+    interface: IF,
+    mapping: Mcp23008Mapping,
+    backlight: bool,
+    timing: Timing,
+}
+
+impl<IF: WriteRegister> Mcp23008Bus<IF> {
+    /// Create a new bus with the given pin mapping, configuring the mapped expander pins as outputs
+    /// and turning the backlight on.
+    pub fn new(
+        interface: IF,
+        mapping: Mcp23008Mapping,
+    ) -> Result<Mcp23008Bus<IF>, DataBusError<IF::Error>> {
+        let mut bus = Mcp23008Bus {
+            interface,
+            mapping,
+            backlight: true,
+            timing: Default::default(),
+        };
+        bus.interface
+            .write_register_broadcast(IOCON, IOCON_HAEN)
+            .map_err(DataBusError::BusError)?;
+        bus.write_output(bus.backlight_bits())?;
+        bus.interface
+            .write_register(IODIR, !mapping.outputs())
+            .map_err(DataBusError::BusError)?;
+        Ok(bus)
+    }
+
+    /// Destroy the bus and return the expander interface.
+    pub fn release(self) -> IF {
+        self.interface
+    }
+
+    /// Turn the backlight on or off. The data pins are left low.
+    pub fn set_backlight(&mut self, on: bool) -> Result<(), DataBusError<IF::Error>> {
+        self.backlight = on;
+        self.write_output(self.backlight_bits())
+    }
+
+    /// Expander output bit for the current backlight state.
+    fn backlight_bits(&self) -> u8 {
+        if self.backlight == self.mapping.backlight_active_high {
+            1 << self.mapping.backlight
+        } else {
+            0
+        }
+    }
+
+    /// Expander output byte for the control pins, with E low.
+    fn control_bits(&self, transaction: &TransactionType) -> u8 {
+        match transaction {
+            TransactionType::Instruction => self.backlight_bits(),
+            TransactionType::Data => self.backlight_bits() | 1 << self.mapping.rs,
+        }
+    }
+
+    /// Set all expander outputs at once.
+    fn write_output(&mut self, bits: u8) -> Result<(), DataBusError<IF::Error>> {
+        self.interface
+            .write_register(OLAT, bits)
+            .map_err(DataBusError::BusError)
+    }
+
+    /// Send a nibble, latched by an enable pulse formed by register writes. RS and D4-D7 are set
+    /// with E low first, so the address setup time has passed by the time E rises.
+    fn send_nibble(
+        &mut self,
+        nibble: u8,
+        transaction: &TransactionType,
+    ) -> Result<(), DataBusError<IF::Error>> {
+        let bits = self.control_bits(transaction) | self.mapping.data_bits(nibble);
+        self.write_output(bits)?;
+        self.write_output(bits | 1 << self.mapping.en)?;
+        self.write_output(bits)
+    }
+}
+
+impl<IF: WriteRegister> DataBus for Mcp23008Bus<IF> {
+    type Error = DataBusError<IF::Error>;
+    type Hal = IF::Hal;
+
+    fn timing(&self) -> &Timing {
+        &self.timing
+    }
+
+    fn set_timing(&mut self, timing: Timing) {
+        self.timing = timing;
+    }
+
+    fn write_byte<D: Delay<IF::Hal>>(
+        &mut self,
+        byte: u8,
+        transaction: TransactionType,
+        _delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        self.send_nibble(byte >> 4, &transaction)?;
+        self.send_nibble(byte & 0x0F, &transaction)
+    }
+
+    fn write_bytes<D: Delay<IF::Hal>>(
+        &mut self,
+        bytes: &[u8],
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        for (i, byte) in bytes.iter().enumerate() {
+            if i != 0 {
+                delay.delay_us(self.timing.execution_us);
+            }
+            self.send_nibble(byte >> 4, &transaction)?;
+            self.send_nibble(byte & 0x0F, &transaction)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<IF: WriteRegister> FourBitBus for Mcp23008Bus<IF> {
+    fn write_nibble<D: Delay<IF::Hal>>(
+        &mut self,
+        nibble: u8,
+        transaction: TransactionType,
+        _delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        self.send_nibble(nibble, &transaction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_bus::mcp23x::I2cInterface;
+    use crate::mock::{Delay, I2c};
+
+    extern crate std;
+    use std::vec;
+    use std::vec::Vec;
+
+    #[test]
+    fn preset_is_valid() {
+        let mapping = Mcp23008Mapping::adafruit_backpack();
+        assert!(check_pin_mapping::<()>(&mapping.pins(), &[], 8).is_ok());
+    }
+
+    #[test]
+    fn custom_mapping_is_checked() {
+        assert!(Mcp23008Mapping::new::<()>(0, 1, 2, [3, 4, 5, 6], true).is_ok());
+        assert!(matches!(
+            Mcp23008Mapping::new::<()>(0, 1, 2, [3, 4, 5, 0], true),
+            Err(DataBusError::InvalidPinMapping)
+        ));
+    }
+
+    #[test]
+    fn nibbles_are_written_to_the_output_latch() {
+        let i2c = I2c::default();
+        let interface = I2cInterface::new(i2c.clone(), 0x20);
+        let mut bus = Mcp23008Bus::new(interface, Mcp23008Mapping::adafruit_backpack()).unwrap();
+
+        bus.write_nibble(0x5, TransactionType::Data, &mut Delay::default())
+            .unwrap();
+
+        // IOCON.HAEN, backlight on GP7, GP0 left as an input, then RS on GP1, D4 on GP3 and D6 on
+        // GP5 set up before E on GP2 is pulsed
+        let writes: Vec<_> = i2c.writes().into_iter().map(|(_, bytes)| bytes).collect();
+        assert_eq!(
+            writes,
+            [
+                vec![IOCON, 0x08],
+                vec![OLAT, 0x80],
+                vec![IODIR, 0x01],
+                vec![OLAT, 0xAA],
+                vec![OLAT, 0xAE],
+                vec![OLAT, 0xAA],
+            ]
+        );
+    }
+}
@@ -0,0 +1,454 @@
+//! 4-bit data bus through an MCP23017 (I2C) or MCP23S17 (SPI) I/O expander, as used on the Adafruit
+//! RGB LCD shield. The expander pins are numbered 0-15: GPA0-GPA7 are 0-7 and GPB0-GPB7 are 8-15.
+//!
+//! Besides the display, the expander drives an RGB backlight (one pin per colour, switched on or
+//! off) and reads five buttons, both through the same bus.
+
+use crate::data_bus::mcp23x::{ReadRegister, WriteRegister};
+use crate::data_bus::*;
+use crate::hal::Delay;
+use crate::timing::Timing;
+use crate::DataBusError;
+
+// Register addresses with IOCON.BANK = 0 (the default), where each port B register directly
+// follows the port A register.
+
+/// I/O direction register, a set bit makes the pin an input.
+const IODIRA: u8 = 0x00;
+
+/// Configuration register.
+const IOCON: u8 = 0x0A;
+
+/// Pull-up resistor register.
+const GPPUA: u8 = 0x0C;
+
+/// Port register, reads the pin levels.
+const GPIOA: u8 = 0x12;
+
+/// Output latch register.
+const OLATA: u8 = 0x14;
+
+/// Offset of a port B register from its port A counterpart.
+const PORT_B: u8 = 0x01;
+
+/// Hardware address enable bit of IOCON (MCP23S17 only).
+const IOCON_HAEN: u8 = 0x08;
+
+/// Colour of an RGB backlight. Each colour is either fully on or off.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Color {
+    pub red: bool,
+    pub green: bool,
+    pub blue: bool,
+}
+
+impl Color {
+    pub const OFF: Color = Color::new(false, false, false);
+    pub const RED: Color = Color::new(true, false, false);
+    pub const YELLOW: Color = Color::new(true, true, false);
+    pub const GREEN: Color = Color::new(false, true, false);
+    pub const TEAL: Color = Color::new(false, true, true);
+    pub const BLUE: Color = Color::new(false, false, true);
+    pub const VIOLET: Color = Color::new(true, false, true);
+    pub const WHITE: Color = Color::new(true, true, true);
+
+    /// Colour with each of red, green and blue on or off.
+    pub const fn new(red: bool, green: bool, blue: bool) -> Self {
+        Color { red, green, blue }
+    }
+}
+
+/// State of the five buttons, true while pressed.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Buttons {
+    pub select: bool,
+    pub right: bool,
+    pub down: bool,
+    pub up: bool,
+    pub left: bool,
+}
+
+impl Buttons {
+    /// True if any button is pressed.
+    pub fn any(&self) -> bool {
+        self.select || self.right || self.down || self.up || self.left
+    }
+}
+
+/// Assignment of the expander pins to the display pins, backlight and buttons, as pin numbers 0-15.
+#[derive(Copy, Clone, Debug)]
+pub struct Mcp23017Mapping {
+    rs: u8,
+    rw: u8,
+    en: u8,
+    data: [u8; 4],
+    backlight: [u8; 3],
+    backlight_active_high: bool,
+    buttons: [u8; 5],
+}
+
+impl Mcp23017Mapping {
+    /// Custom mapping. `data` holds the expander pins of D4-D7, in that order.
+    ///
+    /// `backlight` holds the red, green and blue backlight pins. Use the same pin three times for a
+    /// single colour backlight. `buttons` holds the select, right, down, up and left button pins.
+    /// Buttons connect the pin to ground and are read with the internal pull-ups enabled.
+    ///
+    /// Returns `InvalidPinMapping` if a pin is greater than 15 or assigned twice, other than a
+    /// backlight pin repeated within `backlight`.
+    pub fn new<E>(
+        rs: u8,
+        rw: u8,
+        en: u8,
+        data: [u8; 4],
+        backlight: [u8; 3],
+        backlight_active_high: bool,
+        buttons: [u8; 5],
+    ) -> Result<Self, DataBusError<E>> {
+        let mapping = Mcp23017Mapping {
+            rs,
+            rw,
+            en,
+            data,
+            backlight,
+            backlight_active_high,
+            buttons,
+        };
+        check_pin_mapping(&mapping.pins(), &backlight, 16)?;
+        Ok(mapping)
+    }
+
+    /// Mapping of the Adafruit RGB LCD shield: RS=GPB7, RW=GPB6, E=GPB5, D4-D7=GPB4-GPB1,
+    /// backlight red=GPA6, green=GPA7, blue=GPB0 (active low), buttons select, right, down, up,
+    /// left=GPA0-GPA4.
+    pub const fn adafruit_rgb_shield() -> Self {
+        Mcp23017Mapping {
+            rs: 15,
+            rw: 14,
+            en: 13,
+            data: [12, 11, 10, 9],
+            backlight: [6, 7, 8],
+            backlight_active_high: false,
+            buttons: [0, 1, 2, 3, 4],
+        }
+    }
+
+    /// Every expander pin used by the mapping other than the backlight, which may share a pin
+    /// between colours.
+    fn pins(&self) -> [u8; 12] {
+        let [d4, d5, d6, d7] = self.data;
+        let [select, right, down, up, left] = self.buttons;
+        [
+            self.rs, self.rw, self.en, d4, d5, d6, d7, select, right, down, up, left,
+        ]
+    }
+
+    /// Expander output bits driving the lower nibble of `nibble` onto D4-D7.
+    fn data_bits(&self, nibble: u8) -> u16 {
+        let mut bits = 0;
+        for (i, pin) in self.data.iter().enumerate() {
+            if nibble & (1 << i) != 0 {
+                bits |= 1 << pin;
+            }
+        }
+        bits
+    }
+
+    /// Expander output bits of the display pins.
+    fn display_bits(&self) -> u16 {
+        1 << self.rs | 1 << self.rw | 1 << self.en | self.data_bits(0x0F)
+    }
+
+    /// Expander output bits lighting the backlight in `color`.
+    fn backlight_bits(&self, color: Color) -> u16 {
+        let mut bits = 0;
+        let colors = [color.red, color.green, color.blue];
+        for (on, pin) in colors.iter().zip(self.backlight.iter()) {
+            if *on == self.backlight_active_high {
+                bits |= 1 << pin;
+            }
+        }
+        bits
+    }
+
+    /// Expander output bits of the backlight pins.
+    fn backlight_mask(&self) -> u16 {
+        self.backlight.iter().fold(0, |bits, pin| bits | 1 << pin)
+    }
+
+    /// Expander input bits of the buttons.
+    fn button_bits(&self) -> u16 {
+        self.buttons.iter().fold(0, |bits, pin| bits | 1 << pin)
+    }
+
+    /// Pins driven by the bus. The buttons and any unassigned pins are left as inputs.
+    fn outputs(&self) -> u16 {
+        self.display_bits() | self.backlight_mask()
+    }
+}
+
+impl Default for Mcp23017Mapping {
+    fn default() -> Self {
+        Mcp23017Mapping::adafruit_rgb_shield()
+    }
+}
+
+/// 4-bit data bus through an MCP23017 or MCP23S17 I/O expander, with RGB backlight and buttons.
+pub struct Mcp23017Bus<IF: WriteRegister> {
+    interface: IF,
+    mapping: Mcp23017Mapping,
+    output: u16,
+    timing: Timing,
+}
+
+impl<IF: WriteRegister> Mcp23017Bus<IF> {
+    /// Create a new bus with the given pin mapping, configuring the display and backlight pins as
+    /// outputs and the buttons as inputs with pull-ups. The backlight is turned on white.
+    pub fn new(
+        interface: IF,
+        mapping: Mcp23017Mapping,
+    ) -> Result<Mcp23017Bus<IF>, DataBusError<IF::Error>> {
+        let mut bus = Mcp23017Bus {
+            interface,
+            mapping,
+            output: mapping.backlight_bits(Color::WHITE),
+            timing: Default::default(),
+        };
+        bus.interface
+            .write_register_broadcast(IOCON, IOCON_HAEN)
+            .map_err(DataBusError::BusError)?;
+        bus.write_pair(OLATA, bus.output)?;
+        bus.write_pair(IODIRA, !mapping.outputs())?;
+        bus.write_pair(GPPUA, mapping.button_bits())?;
+        Ok(bus)
+    }
+
+    /// Destroy the bus and return the expander interface.
+    pub fn release(self) -> IF {
+        self.interface
+    }
+
+    /// Light the backlight in `color`.
+    pub fn set_backlight_color(&mut self, color: Color) -> Result<(), DataBusError<IF::Error>> {
+        let output =
+            self.output & !self.mapping.backlight_mask() | self.mapping.backlight_bits(color);
+        self.write_output(output)
+    }
+
+    /// Write a 16-bit value to the port A and B registers starting at `register`.
+    fn write_pair(&mut self, register: u8, value: u16) -> Result<(), DataBusError<IF::Error>> {
+        self.interface
+            .write_register(register, value as u8)
+            .map_err(DataBusError::BusError)?;
+        self.interface
+            .write_register(register + PORT_B, (value >> 8) as u8)
+            .map_err(DataBusError::BusError)
+    }
+
+    /// Set the expander outputs, only writing the ports that changed.
+    fn write_output(&mut self, output: u16) -> Result<(), DataBusError<IF::Error>> {
+        let changed = output ^ self.output;
+        if changed & 0x00FF != 0 {
+            self.interface
+                .write_register(OLATA, output as u8)
+                .map_err(DataBusError::BusError)?;
+        }
+        if changed & 0xFF00 != 0 {
+            self.interface
+                .write_register(OLATA + PORT_B, (output >> 8) as u8)
+                .map_err(DataBusError::BusError)?;
+        }
+        self.output = output;
+        Ok(())
+    }
+
+    /// Send a nibble, latched by an enable pulse formed by register writes. RS and D4-D7 are set
+    /// with E low first, so the address setup time has passed by the time E rises. RW is held low.
+    fn send_nibble(
+        &mut self,
+        nibble: u8,
+        transaction: &TransactionType,
+    ) -> Result<(), DataBusError<IF::Error>> {
+        let mut bits = self.output & !self.mapping.display_bits() | self.mapping.data_bits(nibble);
+        if let TransactionType::Data = transaction {
+            bits |= 1 << self.mapping.rs;
+        }
+        self.write_output(bits)?;
+        self.write_output(bits | 1 << self.mapping.en)?;
+        self.write_output(bits)
+    }
+}
+
+impl<IF: ReadRegister> Mcp23017Bus<IF> {
+    /// Read the state of the buttons.
+    pub fn read_buttons(&mut self) -> Result<Buttons, DataBusError<IF::Error>> {
+        let low = self
+            .interface
+            .read_register(GPIOA)
+            .map_err(DataBusError::BusError)?;
+        let high = self
+            .interface
+            .read_register(GPIOA + PORT_B)
+            .map_err(DataBusError::BusError)?;
+        let inputs = u16::from(high) << 8 | u16::from(low);
+
+        // Pressed buttons pull their pin low
+        let pressed = |pin: u8| inputs & (1 << pin) == 0;
+        let [select, right, down, up, left] = self.mapping.buttons;
+        Ok(Buttons {
+            select: pressed(select),
+            right: pressed(right),
+            down: pressed(down),
+            up: pressed(up),
+            left: pressed(left),
+        })
+    }
+}
+
+impl<IF: WriteRegister> DataBus for Mcp23017Bus<IF> {
+    type Error = DataBusError<IF::Error>;
+    type Hal = IF::Hal;
+
+    fn timing(&self) -> &Timing {
+        &self.timing
+    }
+
+    fn set_timing(&mut self, timing: Timing) {
+        self.timing = timing;
+    }
+
+    fn write_byte<D: Delay<IF::Hal>>(
+        &mut self,
+        byte: u8,
+        transaction: TransactionType,
+        _delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        self.send_nibble(byte >> 4, &transaction)?;
+        self.send_nibble(byte & 0x0F, &transaction)
+    }
+
+    fn write_bytes<D: Delay<IF::Hal>>(
+        &mut self,
+        bytes: &[u8],
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        for (i, byte) in bytes.iter().enumerate() {
+            if i != 0 {
+                delay.delay_us(self.timing.execution_us);
+            }
+            self.send_nibble(byte >> 4, &transaction)?;
+            self.send_nibble(byte & 0x0F, &transaction)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<IF: WriteRegister> FourBitBus for Mcp23017Bus<IF> {
+    fn write_nibble<D: Delay<IF::Hal>>(
+        &mut self,
+        nibble: u8,
+        transaction: TransactionType,
+        _delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        self.send_nibble(nibble, &transaction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_bus::mcp23x::I2cInterface;
+    use crate::mock::{Delay, I2c};
+
+    extern crate std;
+    use std::vec;
+    use std::vec::Vec;
+
+    fn bus(i2c: &I2c) -> Mcp23017Bus<I2cInterface<I2c>> {
+        let interface = I2cInterface::new(i2c.clone(), 0x20);
+        Mcp23017Bus::new(interface, Mcp23017Mapping::adafruit_rgb_shield()).unwrap()
+    }
+
+    #[test]
+    fn preset_is_valid() {
+        let mapping = Mcp23017Mapping::adafruit_rgb_shield();
+        assert!(check_pin_mapping::<()>(&mapping.pins(), &mapping.backlight, 16).is_ok());
+    }
+
+    #[test]
+    fn custom_mapping_is_checked() {
+        let data = [12, 11, 10, 9];
+        let buttons = [0, 1, 2, 3, 4];
+        // Single colour backlight on one pin
+        assert!(Mcp23017Mapping::new::<()>(15, 14, 13, data, [5, 5, 5], true, buttons).is_ok());
+        assert!(matches!(
+            Mcp23017Mapping::new::<()>(15, 14, 13, data, [5, 5, 4], true, buttons),
+            Err(DataBusError::InvalidPinMapping)
+        ));
+        assert!(matches!(
+            Mcp23017Mapping::new::<()>(15, 14, 16, data, [5, 6, 7], true, buttons),
+            Err(DataBusError::InvalidPinMapping)
+        ));
+    }
+
+    #[test]
+    fn only_changed_ports_are_written() {
+        let i2c = I2c::default();
+        let mut bus = bus(&i2c);
+        let configured = i2c.writes().len();
+
+        bus.write_nibble(0x1, TransactionType::Data, &mut Delay::default())
+            .unwrap();
+        bus.set_backlight_color(Color::RED).unwrap();
+
+        let writes: Vec<_> = i2c.writes().into_iter().map(|(_, bytes)| bytes).collect();
+        // Backlight on white (active low), display and backlight pins as outputs, pull-ups on the
+        // buttons
+        assert_eq!(
+            writes[..configured],
+            [
+                vec![IOCON, 0x08],
+                vec![OLATA, 0x00],
+                vec![OLATA + PORT_B, 0x00],
+                vec![IODIRA, 0x3F],
+                vec![IODIRA + PORT_B, 0x00],
+                vec![GPPUA, 0x1F],
+                vec![GPPUA + PORT_B, 0x00],
+            ]
+        );
+        // RS on GPB7 and D4 on GPB4 set up before E on GPB5 is pulsed, then green (GPA7) and blue
+        // (GPB0) turned off
+        assert_eq!(
+            writes[configured..],
+            [
+                vec![OLATA + PORT_B, 0x90],
+                vec![OLATA + PORT_B, 0xB0],
+                vec![OLATA + PORT_B, 0x90],
+                vec![OLATA, 0x80],
+                vec![OLATA + PORT_B, 0x91],
+            ]
+        );
+    }
+
+    #[test]
+    fn pressed_buttons_read_low() {
+        let i2c = I2c::default();
+        let mut bus = bus(&i2c);
+        // Select on GPA0 and down on GPA2 pressed
+        i2c.respond(&[0xFA, 0xFF]);
+
+        let buttons = bus.read_buttons().unwrap();
+
+        assert_eq!(
+            buttons,
+            Buttons {
+                select: true,
+                down: true,
+                ..Default::default()
+            }
+        );
+        assert!(buttons.any());
+    }
+}
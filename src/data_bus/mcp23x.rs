@@ -0,0 +1,217 @@
+//! Register interfaces of the MCP23008/MCP23017 I/O expanders (I2C) and their MCP23S08/MCP23S17
+//! variants (SPI). The expander buses only access single registers, so either interface can be
+//! used with the same bus.
+
+use crate::data_bus::SpiError;
+#[cfg(feature = "hal-1")]
+use crate::hal::{DeviceSelect, Eh1};
+use crate::hal::{Eh02, I2cWrite, I2cWriteRead, OutputPin, SpiTransfer, SpiWrite};
+use core::marker::PhantomData;
+
+/// I2C address of an expander with A0-A2 pulled low. The address pins are added to this.
+pub const MCP23X_BASE_ADDRESS: u8 = 0x20;
+
+/// SPI opcode, followed by the hardware address (A2-A0) and the read/write bit.
+const SPI_OPCODE: u8 = 0x40;
+const SPI_READ: u8 = 0x01;
+
+/// Write access to the registers of an expander.
+pub trait WriteRegister {
+    type Error;
+
+    /// embedded-hal version of the peripheral, which the delay must implement as well.
+    type Hal;
+
+    /// Write `value` to `register`.
+    fn write_register(&mut self, register: u8, value: u8) -> Result<(), Self::Error>;
+
+    /// Write `value` to `register` regardless of whether hardware addressing has been enabled yet.
+    /// Only used to enable it (IOCON.HAEN) while configuring the expander.
+    fn write_register_broadcast(&mut self, register: u8, value: u8) -> Result<(), Self::Error> {
+        self.write_register(register, value)
+    }
+}
+
+/// Read access to the registers of an expander.
+pub trait ReadRegister: WriteRegister {
+    /// Read the value of `register`.
+    fn read_register(&mut self, register: u8) -> Result<u8, Self::Error>;
+}
+
+/// Expander on an I2C bus at a 7-bit address.
+pub struct I2cInterface<I2C, H = Eh02> {
+    i2c: I2C,
+    address: u8,
+    _hal: PhantomData<H>,
+}
+
+impl<I2C: I2cWrite<H>, H> I2cInterface<I2C, H> {
+    /// Create an interface to the expander at `address` (7-bit).
+    pub fn new(i2c: I2C, address: u8) -> I2cInterface<I2C, H> {
+        I2cInterface {
+            i2c,
+            address,
+            _hal: PhantomData,
+        }
+    }
+
+    /// Destroy the interface and return the I2C peripheral.
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+}
+
+impl<I2C: I2cWrite<H>, H> WriteRegister for I2cInterface<I2C, H> {
+    type Error = I2C::Error;
+    type Hal = H;
+
+    fn write_register(&mut self, register: u8, value: u8) -> Result<(), Self::Error> {
+        self.i2c.write(self.address, &[register, value])
+    }
+}
+
+impl<I2C: I2cWriteRead<H>, H> ReadRegister for I2cInterface<I2C, H> {
+    fn read_register(&mut self, register: u8) -> Result<u8, Self::Error> {
+        let mut value = [0];
+        self.i2c.write_read(self.address, &[register], &mut value)?;
+        Ok(value[0])
+    }
+}
+
+/// Expander on an SPI bus, selected by a chip select pin (active low) and its hardware address.
+pub struct SpiInterface<SPI, CS, H = Eh02> {
+    spi: SPI,
+    cs: CS,
+    address: u8,
+    _hal: PhantomData<H>,
+}
+
+impl<SPI: SpiWrite<H>, CS: OutputPin<H>, H> SpiInterface<SPI, CS, H> {
+    /// Create an interface to the expander selected by `cs` with hardware address `address` (A0-A1
+    /// on the MCP23S08, A0-A2 on the MCP23S17). Use address 0 if the address pins are not used.
+    pub fn new(spi: SPI, cs: CS, address: u8) -> SpiInterface<SPI, CS, H> {
+        SpiInterface {
+            spi,
+            cs,
+            address,
+            _hal: PhantomData,
+        }
+    }
+
+    /// Destroy the interface and return the SPI peripheral and chip select pin.
+    pub fn release(self) -> (SPI, CS) {
+        (self.spi, self.cs)
+    }
+
+    /// Opcode addressing the expander at `address`.
+    fn opcode(address: u8, read: bool) -> u8 {
+        let opcode = SPI_OPCODE | (address & 0x07) << 1;
+        if read {
+            opcode | SPI_READ
+        } else {
+            opcode
+        }
+    }
+}
+
+#[cfg(feature = "hal-1")]
+impl<SPI: SpiWrite<Eh1>> SpiInterface<SPI, DeviceSelect, Eh1> {
+    /// Create an interface to the expander on an embedded-hal 1.0 `SpiDevice`, which frames each
+    /// transfer with its own chip select, with hardware address `address`.
+    pub fn new_device(spi: SPI, address: u8) -> SpiInterface<SPI, DeviceSelect, Eh1> {
+        SpiInterface::new(spi, DeviceSelect::new(), address)
+    }
+}
+
+impl<SPI, CS, H> SpiInterface<SPI, CS, H>
+where
+    SPI: SpiWrite<H>,
+    CS: OutputPin<H>,
+{
+    /// Write a register of the expander at `address`, releasing chip select even if the transfer
+    /// fails.
+    fn write_at(
+        &mut self,
+        address: u8,
+        register: u8,
+        value: u8,
+    ) -> Result<(), SpiError<SPI::Error, CS::Error>> {
+        self.cs.set_low().map_err(SpiError::Pin)?;
+        let result = self
+            .spi
+            .write(&[Self::opcode(address, false), register, value])
+            .map_err(SpiError::Spi);
+        self.cs.set_high().map_err(SpiError::Pin)?;
+        result
+    }
+}
+
+impl<SPI, CS, H> WriteRegister for SpiInterface<SPI, CS, H>
+where
+    SPI: SpiWrite<H>,
+    CS: OutputPin<H>,
+{
+    type Error = SpiError<SPI::Error, CS::Error>;
+    type Hal = H;
+
+    fn write_register(&mut self, register: u8, value: u8) -> Result<(), Self::Error> {
+        self.write_at(self.address, register, value)
+    }
+
+    fn write_register_broadcast(&mut self, register: u8, value: u8) -> Result<(), Self::Error> {
+        // Until hardware addressing is enabled the expander only responds to address 0
+        self.write_at(0, register, value)?;
+        self.write_at(self.address, register, value)
+    }
+}
+
+impl<SPI, CS, H> ReadRegister for SpiInterface<SPI, CS, H>
+where
+    SPI: SpiTransfer<H>,
+    CS: OutputPin<H>,
+{
+    fn read_register(&mut self, register: u8) -> Result<u8, Self::Error> {
+        let mut buffer = [Self::opcode(self.address, true), register, 0];
+        self.cs.set_low().map_err(SpiError::Pin)?;
+        let result = self
+            .spi
+            .transfer(&mut buffer)
+            .map(|_| buffer[2])
+            .map_err(SpiError::Spi);
+        self.cs.set_high().map_err(SpiError::Pin)?;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{Port, Spi};
+
+    extern crate std;
+    use std::vec;
+
+    #[test]
+    fn spi_registers_are_accessed_with_chip_select_low() {
+        let port = Port::default();
+        let spi = Spi::on(&port);
+        let mut interface = SpiInterface::new(spi.clone(), port.pin(0), 3);
+        spi.respond(&[0, 0, 0x5A]);
+
+        interface.write_register_broadcast(0x05, 0x08).unwrap();
+        interface.write_register(0x0A, 0x81).unwrap();
+        let value = interface.read_register(0x09).unwrap();
+
+        assert_eq!(value, 0x5A);
+        assert_eq!(
+            spi.transfers(),
+            [
+                (0, vec![0x40, 0x05, 0x08]),
+                (0, vec![0x46, 0x05, 0x08]),
+                (0, vec![0x46, 0x0A, 0x81]),
+                (0, vec![0x47, 0x09, 0x00]),
+            ]
+        );
+        assert_eq!(port.levels(), 1);
+    }
+}
@@ -2,10 +2,22 @@
 
 pub mod bus4;
 pub mod bus8;
-mod pins;
+pub mod dual;
+pub mod mcp23008;
+pub mod mcp23017;
+pub mod mcp23x;
+pub mod pcf8574;
+pub mod pins;
+pub mod serial;
+pub mod shared;
+pub mod shift595;
+pub mod two_wire;
 
-use embedded_hal as hal;
-use hal::blocking::delay::{DelayMs, DelayUs};
+use crate::hal::Delay;
+use crate::timing::Timing;
+use crate::DataBusError;
+#[cfg(feature = "async")]
+use embedded_hal_async::delay::DelayNs;
 
 /// Select the instruction or data register
 #[repr(u8)]
@@ -14,20 +26,64 @@ pub enum TransactionType {
     Data = 1,
 }
 
+/// Error of an SPI attached bus, which drives a chip select or latch pin alongside the SPI
+/// peripheral.
+#[derive(Debug)]
+pub enum SpiError<S, P> {
+    // Error transferring data over SPI
+    Spi(S),
+
+    // Error setting the chip select or latch pin
+    Pin(P),
+}
+
+/// Check the pin mapping of an expander or shift register bus: every pin in `pins` must be below
+/// `count` and used only once, so the bus can shift by any mapped pin number. Pins in `shared` must
+/// also be below `count` and not in `pins`, but may repeat among themselves, e.g. a single colour
+/// backlight wired to all three colour pins.
+pub(crate) fn check_pin_mapping<E>(
+    pins: &[u8],
+    shared: &[u8],
+    count: u8,
+) -> Result<(), DataBusError<E>> {
+    for (i, pin) in pins.iter().enumerate() {
+        if *pin >= count || pins[i + 1..].contains(pin) {
+            return Err(DataBusError::InvalidPinMapping);
+        }
+    }
+    for pin in shared {
+        if *pin >= count || pins.contains(pin) {
+            return Err(DataBusError::InvalidPinMapping);
+        }
+    }
+    Ok(())
+}
+
 /// API of a data bus for the HD44780.
 pub trait DataBus {
     type Error;
 
-    /// Write a single byte of data to the instruction or data register.
-    fn write_byte<D: DelayMs<u16> + DelayUs<u16>>(
+    /// embedded-hal version of the pins or peripheral, which the delay must implement as well.
+    type Hal;
+
+    /// Timing profile used for bus transfers.
+    fn timing(&self) -> &Timing;
+
+    /// Replace the timing profile used for bus transfers.
+    fn set_timing(&mut self, timing: Timing);
+
+    /// Write a single byte of data to the instruction or data register. Does not wait for the
+    /// device to execute it.
+    fn write_byte<D: Delay<Self::Hal>>(
         &mut self,
         byte: u8,
         transaction: TransactionType,
         delay: &mut D,
     ) -> Result<(), Self::Error>;
 
-    /// Write multiple bytes of data to the instruction or data register.
-    fn write_bytes<D: DelayMs<u16> + DelayUs<u16>>(
+    /// Write multiple bytes of data to the instruction or data register, waiting for each byte to
+    /// be executed before writing the next. Does not wait for the last byte to be executed.
+    fn write_bytes<D: Delay<Self::Hal>>(
         &mut self,
         bytes: &[u8],
         transaction: TransactionType,
@@ -35,32 +91,128 @@ pub trait DataBus {
     ) -> Result<(), Self::Error>;
 }
 
-/// Additional API of a data bus with bidirectional data bit pins, allowing read operations from the
-/// HD44780 as well as write operations.
-pub trait ReadableDataBus {
+/// Additional API of a data bus connected to the upper four data pins (D4-D7) only.
+pub trait FourBitBus: DataBus {
+    /// Write a single nibble (lower 4 bits of `nibble`) to the instruction or data register. Only
+    /// used directly during initialization, while the device is still in 8-bit mode.
+    fn write_nibble<D: Delay<Self::Hal>>(
+        &mut self,
+        nibble: u8,
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error>;
+}
+
+/// Async API of a data bus for the HD44780. Delays and interface transfers are awaited rather than
+/// blocked on, so other tasks run while the device executes each byte.
+#[cfg(feature = "async")]
+#[allow(async_fn_in_trait)]
+pub trait AsyncDataBus {
     type Error;
 
-    /// Poll busy flag. Returns true if busy otherwise false.
-    fn read_busy_flag<D: DelayMs<u16> + DelayUs<u16>>(
+    /// Timing profile used for bus transfers.
+    fn timing(&self) -> &Timing;
+
+    /// Replace the timing profile used for bus transfers.
+    fn set_timing(&mut self, timing: Timing);
+
+    /// Write a single byte of data to the instruction or data register. Does not wait for the
+    /// device to execute it.
+    async fn write_byte<D: DelayNs>(
+        &mut self,
+        byte: u8,
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error>;
+
+    /// Write multiple bytes of data to the instruction or data register, waiting for each byte to
+    /// be executed before writing the next. Does not wait for the last byte to be executed.
+    async fn write_bytes<D: DelayNs>(
         &mut self,
+        bytes: &[u8],
+        transaction: TransactionType,
         delay: &mut D,
-    ) -> Result<bool, Self::Error>;
+    ) -> Result<(), Self::Error>;
+}
+
+/// Additional async API of a data bus connected to the upper four data pins (D4-D7) only.
+#[cfg(feature = "async")]
+#[allow(async_fn_in_trait)]
+pub trait AsyncFourBitBus: AsyncDataBus {
+    /// Write a single nibble (lower 4 bits of `nibble`) to the instruction or data register. Only
+    /// used directly during initialization, while the device is still in 8-bit mode.
+    async fn write_nibble<D: DelayNs>(
+        &mut self,
+        nibble: u8,
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error>;
+}
+
+/// Controllers of a display driven by two HD44780s, each with its own enable pin.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum EnableSelect {
+    First,
+    Second,
+    Both,
+}
+
+/// Additional API of a data bus shared by two controllers with separate enable pins.
+pub trait DualEnableBus: DataBus {
+    /// Select the controllers that following transfers are latched by.
+    fn select(&mut self, target: EnableSelect);
+}
+
+/// Additional API of a data bus with bidirectional data bit pins, allowing read operations from the
+/// HD44780 as well as write operations.
+pub trait ReadableDataBus: DataBus {
+    /// Poll busy flag. Returns true if busy otherwise false.
+    fn read_busy_flag<D: Delay<Self::Hal>>(&mut self, delay: &mut D) -> Result<bool, Self::Error>;
 
     /// Read address counter.
-    fn read_address_counter<D: DelayMs<u16> + DelayUs<u16>>(
+    fn read_address_counter<D: Delay<Self::Hal>>(
         &mut self,
         delay: &mut D,
     ) -> Result<u8, Self::Error>;
 
     /// Read both busy flag and address counter
-    fn read_busy_flag_and_address_counter<D: DelayMs<u16> + DelayUs<u16>>(
+    fn read_busy_flag_and_address_counter<D: Delay<Self::Hal>>(
         &mut self,
         delay: &mut D,
     ) -> Result<(bool, u8), Self::Error>;
 
     /// Reads data from DDRAM or CGRAM.
-    fn read_data<D: DelayMs<u16> + DelayUs<u16>>(
-        &mut self,
-        delay: &mut D,
-    ) -> Result<u8, Self::Error>;
+    fn read_data<D: Delay<Self::Hal>>(&mut self, delay: &mut D) -> Result<u8, Self::Error>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mapped_pins_must_exist_and_be_distinct() {
+        assert!(check_pin_mapping::<()>(&[0, 1, 2, 7], &[], 8).is_ok());
+        assert!(matches!(
+            check_pin_mapping::<()>(&[0, 1, 2, 8], &[], 8),
+            Err(DataBusError::InvalidPinMapping)
+        ));
+        assert!(matches!(
+            check_pin_mapping::<()>(&[0, 1, 2, 1], &[], 8),
+            Err(DataBusError::InvalidPinMapping)
+        ));
+        assert!(check_pin_mapping::<()>(&[15, 8], &[], 16).is_ok());
+    }
+
+    #[test]
+    fn shared_pins_may_only_repeat_among_themselves() {
+        assert!(check_pin_mapping::<()>(&[0, 1], &[5, 5, 5], 8).is_ok());
+        assert!(matches!(
+            check_pin_mapping::<()>(&[0, 1], &[5, 1, 5], 8),
+            Err(DataBusError::InvalidPinMapping)
+        ));
+        assert!(matches!(
+            check_pin_mapping::<()>(&[0, 1], &[5, 8, 5], 8),
+            Err(DataBusError::InvalidPinMapping)
+        ));
+    }
 }
@@ -0,0 +1,487 @@
+//! 4-bit data bus through a PCF8574/PCF8574A I2C I/O expander ("I2C backpack"). RS, RW, E, the
+//! backlight and D4-D7 are all driven from the eight expander outputs.
+//!
+//! The expander pins are quasi-bidirectional: an output written high is only weakly pulled up, so
+//! with D4-D7 written high and RW high the device can drive the data lines and they can be read
+//! back with an I2C read. Reading requires the I2C peripheral to implement `Read` as well.
+
+use crate::data_bus::*;
+#[cfg(feature = "async")]
+use crate::hal::Eh1;
+use crate::hal::{Delay, Eh02, I2cRead, I2cWrite};
+use crate::timing::Timing;
+use crate::DataBusError;
+use core::marker::PhantomData;
+#[cfg(feature = "async")]
+use embedded_hal_async::{delay::DelayNs, i2c::I2c};
+
+/// Default address of a PCF8574 backpack (A0-A2 pulled high).
+pub const PCF8574_DEFAULT_ADDRESS: u8 = 0x27;
+
+/// Default address of a PCF8574A backpack (A0-A2 pulled high).
+pub const PCF8574A_DEFAULT_ADDRESS: u8 = 0x3F;
+
+/// Assignment of the expander outputs (P0-P7) to the display pins, as bit numbers 0-7.
+#[derive(Copy, Clone, Debug)]
+pub struct Pcf8574Mapping {
+    rs: u8,
+    rw: u8,
+    en: u8,
+    backlight: u8,
+    data: [u8; 4],
+    backlight_active_high: bool,
+}
+
+impl Pcf8574Mapping {
+    /// Custom mapping. `data` holds the expander outputs of D4-D7, in that order. Returns
+    /// `InvalidPinMapping` if a pin is greater than 7 or assigned twice.
+    pub fn new<E>(
+        rs: u8,
+        rw: u8,
+        en: u8,
+        backlight: u8,
+        data: [u8; 4],
+        backlight_active_high: bool,
+    ) -> Result<Self, DataBusError<E>> {
+        let mapping = Pcf8574Mapping {
+            rs,
+            rw,
+            en,
+            backlight,
+            data,
+            backlight_active_high,
+        };
+        check_pin_mapping(&mapping.pins(), &[], 8)?;
+        Ok(mapping)
+    }
+
+    /// Mapping used by most backpacks: RS=P0, RW=P1, E=P2, backlight=P3 (active high), D4-D7=P4-P7.
+    pub const fn common() -> Self {
+        Pcf8574Mapping {
+            rs: 0,
+            rw: 1,
+            en: 2,
+            backlight: 3,
+            data: [4, 5, 6, 7],
+            backlight_active_high: true,
+        }
+    }
+
+    /// Mapping used by mjkdz and some other backpacks: D4-D7=P0-P3, E=P4, RW=P5, RS=P6,
+    /// backlight=P7 (active low).
+    pub const fn mjkdz() -> Self {
+        Pcf8574Mapping {
+            rs: 6,
+            rw: 5,
+            en: 4,
+            backlight: 7,
+            data: [0, 1, 2, 3],
+            backlight_active_high: false,
+        }
+    }
+
+    /// Every expander output used by the mapping.
+    fn pins(&self) -> [u8; 8] {
+        let [d4, d5, d6, d7] = self.data;
+        [self.rs, self.rw, self.en, self.backlight, d4, d5, d6, d7]
+    }
+
+    /// Expander output byte driving the lower nibble of `nibble` onto D4-D7.
+    fn data_bits(&self, nibble: u8) -> u8 {
+        let mut bits = 0;
+        for (i, pin) in self.data.iter().enumerate() {
+            if nibble & (1 << i) != 0 {
+                bits |= 1 << pin;
+            }
+        }
+        bits
+    }
+
+    /// Nibble on D4-D7 read back from the expander inputs.
+    fn nibble(&self, bits: u8) -> u8 {
+        let mut nibble = 0;
+        for (i, pin) in self.data.iter().enumerate() {
+            if bits & (1 << pin) != 0 {
+                nibble |= 1 << i;
+            }
+        }
+        nibble
+    }
+}
+
+impl Default for Pcf8574Mapping {
+    fn default() -> Self {
+        Pcf8574Mapping::common()
+    }
+}
+
+/// 4-bit data bus through a PCF8574 I/O expander. Also readable if the RW pin of the device is
+/// connected to the expander and the I2C peripheral supports reads.
+pub struct Pcf8574Bus<I2C, H = Eh02> {
+    i2c: I2C,
+    address: u8,
+    mapping: Pcf8574Mapping,
+    backlight: bool,
+    timing: Timing,
+    _hal: PhantomData<H>,
+}
+
+impl<I2C, H> Pcf8574Bus<I2C, H> {
+    /// Create a new bus for the expander at `address` (7-bit) with the given pin mapping. The
+    /// backlight is turned on by the first transfer.
+    pub fn new(i2c: I2C, address: u8, mapping: Pcf8574Mapping) -> Pcf8574Bus<I2C, H> {
+        Pcf8574Bus {
+            i2c,
+            address,
+            mapping,
+            backlight: true,
+            timing: Default::default(),
+            _hal: PhantomData,
+        }
+    }
+
+    /// Destroy the bus and return the I2C peripheral.
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    /// Expander output bit for the current backlight state.
+    fn backlight_bits(&self) -> u8 {
+        if self.backlight == self.mapping.backlight_active_high {
+            1 << self.mapping.backlight
+        } else {
+            0
+        }
+    }
+
+    /// Expander output byte for the control pins, with E low.
+    fn control_bits(&self, transaction: &TransactionType) -> u8 {
+        match transaction {
+            TransactionType::Instruction => self.backlight_bits(),
+            TransactionType::Data => self.backlight_bits() | 1 << self.mapping.rs,
+        }
+    }
+
+    /// Expander output bytes latching a sequence of up to two nibbles, each by an enable pulse.
+    /// The expander updates its outputs after every byte, so the enable pulse is formed by the I2C
+    /// transfer itself: RS and D4-D7 are set with E low first, so the address setup time has passed
+    /// by the time E rises. Returns the buffer and the number of bytes used.
+    fn nibble_frames(&self, nibbles: &[u8], transaction: &TransactionType) -> ([u8; 6], usize) {
+        let mut buffer = [0; 6];
+        let control = self.control_bits(transaction);
+        for (i, nibble) in nibbles.iter().enumerate() {
+            let bits = control | self.mapping.data_bits(*nibble);
+            buffer[3 * i] = bits;
+            buffer[3 * i + 1] = bits | 1 << self.mapping.en;
+            buffer[3 * i + 2] = bits;
+        }
+        (buffer, 3 * nibbles.len())
+    }
+}
+
+impl<I2C: I2cWrite<H>, H> Pcf8574Bus<I2C, H> {
+    /// Turn the backlight on or off. The data pins are left low.
+    pub fn set_backlight(&mut self, on: bool) -> Result<(), DataBusError<I2C::Error>> {
+        self.backlight = on;
+        let bits = self.backlight_bits();
+        self.i2c
+            .write(self.address, &[bits])
+            .map_err(DataBusError::BusError)
+    }
+
+    /// Send a sequence of up to two nibbles, each latched by an enable pulse.
+    fn send_nibbles(
+        &mut self,
+        nibbles: &[u8],
+        transaction: &TransactionType,
+    ) -> Result<(), DataBusError<I2C::Error>> {
+        let (buffer, len) = self.nibble_frames(nibbles, transaction);
+        self.i2c
+            .write(self.address, &buffer[..len])
+            .map_err(DataBusError::BusError)
+    }
+}
+
+impl<I2C, H> Pcf8574Bus<I2C, H>
+where
+    I2C: I2cRead<H>,
+{
+    /// Read a byte from the instruction register ([busy flag:address counter]) or data register as
+    /// two nibbles, high nibble first.
+    fn read_byte(&mut self, transaction: TransactionType) -> Result<u8, DataBusError<I2C::Error>> {
+        // Data pins written high so the device can pull them low
+        let idle =
+            self.control_bits(&transaction) | 1 << self.mapping.rw | self.mapping.data_bits(0x0F);
+        self.i2c
+            .write(self.address, &[idle])
+            .map_err(DataBusError::BusError)?;
+        let high = self.read_nibble(idle)?;
+        let low = self.read_nibble(idle)?;
+        Ok(high << 4 | low)
+    }
+
+    /// Read a nibble from D4-D7, sampled while enable is held high.
+    fn read_nibble(&mut self, idle: u8) -> Result<u8, DataBusError<I2C::Error>> {
+        let mut bits = [0];
+        self.i2c
+            .write(self.address, &[idle | 1 << self.mapping.en])
+            .map_err(DataBusError::BusError)?;
+        self.i2c
+            .read(self.address, &mut bits)
+            .map_err(DataBusError::BusError)?;
+        self.i2c
+            .write(self.address, &[idle])
+            .map_err(DataBusError::BusError)?;
+        Ok(self.mapping.nibble(bits[0]))
+    }
+}
+
+impl<I2C, H> ReadableDataBus for Pcf8574Bus<I2C, H>
+where
+    I2C: I2cRead<H>,
+{
+    fn read_busy_flag<D: Delay<H>>(&mut self, delay: &mut D) -> Result<bool, Self::Error> {
+        let (busy, _) = self.read_busy_flag_and_address_counter(delay)?;
+        Ok(busy)
+    }
+
+    fn read_address_counter<D: Delay<H>>(&mut self, delay: &mut D) -> Result<u8, Self::Error> {
+        let (_, address) = self.read_busy_flag_and_address_counter(delay)?;
+        Ok(address)
+    }
+
+    fn read_busy_flag_and_address_counter<D: Delay<H>>(
+        &mut self,
+        _delay: &mut D,
+    ) -> Result<(bool, u8), Self::Error> {
+        let data = self.read_byte(TransactionType::Instruction)?;
+        Ok((data & (1 << 7) != 0, data & !(1 << 7)))
+    }
+
+    fn read_data<D: Delay<H>>(&mut self, _delay: &mut D) -> Result<u8, Self::Error> {
+        self.read_byte(TransactionType::Data)
+    }
+}
+
+impl<I2C: I2cWrite<H>, H> DataBus for Pcf8574Bus<I2C, H> {
+    type Error = DataBusError<I2C::Error>;
+    type Hal = H;
+
+    fn timing(&self) -> &Timing {
+        &self.timing
+    }
+
+    fn set_timing(&mut self, timing: Timing) {
+        self.timing = timing;
+    }
+
+    fn write_byte<D: Delay<H>>(
+        &mut self,
+        byte: u8,
+        transaction: TransactionType,
+        _delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        self.send_nibbles(&[byte >> 4, byte & 0x0F], &transaction)
+    }
+
+    fn write_bytes<D: Delay<H>>(
+        &mut self,
+        bytes: &[u8],
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        for (i, byte) in bytes.iter().enumerate() {
+            if i != 0 {
+                delay.delay_us(self.timing.execution_us);
+            }
+            self.send_nibbles(&[byte >> 4, byte & 0x0F], &transaction)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<I2C: I2cWrite<H>, H> FourBitBus for Pcf8574Bus<I2C, H> {
+    fn write_nibble<D: Delay<H>>(
+        &mut self,
+        nibble: u8,
+        transaction: TransactionType,
+        _delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        self.send_nibbles(&[nibble], &transaction)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<I2C: I2c> Pcf8574Bus<I2C, Eh1> {
+    /// Turn the backlight on or off, awaiting the I2C transfer. The data pins are left low.
+    pub async fn set_backlight_async(&mut self, on: bool) -> Result<(), DataBusError<I2C::Error>> {
+        self.backlight = on;
+        let bits = self.backlight_bits();
+        self.i2c
+            .write(self.address, &[bits])
+            .await
+            .map_err(DataBusError::BusError)
+    }
+
+    /// Send a sequence of up to two nibbles, each latched by an enable pulse, awaiting the I2C
+    /// transfer.
+    async fn send_nibbles_async(
+        &mut self,
+        nibbles: &[u8],
+        transaction: &TransactionType,
+    ) -> Result<(), DataBusError<I2C::Error>> {
+        let (buffer, len) = self.nibble_frames(nibbles, transaction);
+        self.i2c
+            .write(self.address, &buffer[..len])
+            .await
+            .map_err(DataBusError::BusError)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<I2C: I2c> AsyncDataBus for Pcf8574Bus<I2C, Eh1> {
+    type Error = DataBusError<I2C::Error>;
+
+    fn timing(&self) -> &Timing {
+        &self.timing
+    }
+
+    fn set_timing(&mut self, timing: Timing) {
+        self.timing = timing;
+    }
+
+    async fn write_byte<D: DelayNs>(
+        &mut self,
+        byte: u8,
+        transaction: TransactionType,
+        _delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        self.send_nibbles_async(&[byte >> 4, byte & 0x0F], &transaction)
+            .await
+    }
+
+    async fn write_bytes<D: DelayNs>(
+        &mut self,
+        bytes: &[u8],
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        for (i, byte) in bytes.iter().enumerate() {
+            if i != 0 {
+                delay.delay_us(self.timing.execution_us.into()).await;
+            }
+            self.send_nibbles_async(&[byte >> 4, byte & 0x0F], &transaction)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<I2C: I2c> AsyncFourBitBus for Pcf8574Bus<I2C, Eh1> {
+    async fn write_nibble<D: DelayNs>(
+        &mut self,
+        nibble: u8,
+        transaction: TransactionType,
+        _delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        self.send_nibbles_async(&[nibble], &transaction).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{Delay, I2c, I2cOp};
+
+    extern crate std;
+    use std::vec;
+
+    #[test]
+    fn presets_are_valid() {
+        for mapping in [Pcf8574Mapping::common(), Pcf8574Mapping::mjkdz()] {
+            assert!(check_pin_mapping::<()>(&mapping.pins(), &[], 8).is_ok());
+        }
+    }
+
+    #[test]
+    fn custom_mapping_is_checked() {
+        assert!(Pcf8574Mapping::new::<()>(7, 6, 5, 4, [0, 1, 2, 3], true).is_ok());
+        assert!(matches!(
+            Pcf8574Mapping::new::<()>(0, 1, 2, 3, [4, 5, 6, 6], true),
+            Err(DataBusError::InvalidPinMapping)
+        ));
+        assert!(matches!(
+            Pcf8574Mapping::new::<()>(0, 1, 2, 3, [4, 5, 6, 8], true),
+            Err(DataBusError::InvalidPinMapping)
+        ));
+    }
+
+    #[test]
+    fn each_nibble_is_set_up_before_enable_rises() {
+        let i2c = I2c::default();
+        let mut bus = Pcf8574Bus::new(i2c.clone(), 0x27, Pcf8574Mapping::common());
+
+        bus.write_byte(0xA5, TransactionType::Data, &mut Delay::default())
+            .unwrap();
+
+        // Backlight on P3 and RS on P0, then E on P2 pulsed around each nibble on P4-P7
+        assert_eq!(
+            i2c.writes(),
+            [(0x27, vec![0xA9, 0xAD, 0xA9, 0x59, 0x5D, 0x59])]
+        );
+    }
+
+    #[test]
+    fn nibble_follows_the_mapping() {
+        let i2c = I2c::default();
+        let mut bus = Pcf8574Bus::new(i2c.clone(), 0x3F, Pcf8574Mapping::mjkdz());
+
+        bus.write_nibble(0x3, TransactionType::Instruction, &mut Delay::default())
+            .unwrap();
+        bus.set_backlight(false).unwrap();
+
+        // Backlight on P7 is active low, E is on P4 and D4-D7 on P0-P3
+        assert_eq!(
+            i2c.writes(),
+            [(0x3F, vec![0x03, 0x13, 0x03]), (0x3F, vec![0x80])]
+        );
+    }
+
+    #[test]
+    fn busy_flag_is_read_with_data_pins_released() {
+        let i2c = I2c::default();
+        let mut bus = Pcf8574Bus::new(i2c.clone(), 0x27, Pcf8574Mapping::common());
+        // Busy, address 0x45: high nibble then low nibble on P4-P7
+        i2c.respond(&[0xC0, 0x50]);
+
+        let read = bus.read_busy_flag_and_address_counter(&mut Delay::default());
+
+        assert_eq!(read.unwrap(), (true, 0x45));
+        // RW on P1 and D4-D7 written high, then each nibble read while E on P2 is high
+        assert_eq!(
+            i2c.ops(),
+            [
+                I2cOp::Write(0x27, vec![0xFA]),
+                I2cOp::Write(0x27, vec![0xFE]),
+                I2cOp::Read(0x27, 1),
+                I2cOp::Write(0x27, vec![0xFA]),
+                I2cOp::Write(0x27, vec![0xFE]),
+                I2cOp::Read(0x27, 1),
+                I2cOp::Write(0x27, vec![0xFA]),
+            ]
+        );
+    }
+
+    #[test]
+    fn data_is_read_with_rs_high() {
+        let i2c = I2c::default();
+        let mut bus = Pcf8574Bus::new(i2c.clone(), 0x27, Pcf8574Mapping::common());
+        i2c.respond(&[0x40, 0x10]);
+
+        assert_eq!(bus.read_data(&mut Delay::default()).unwrap(), 0x41);
+        assert!(i2c.writes().iter().all(|(_, bytes)| bytes[0] & 0x01 != 0));
+    }
+}
@@ -1,27 +1,47 @@
 //! Special pin definitions for the pins featured on the data bus.
 
-use embedded_hal as hal;
-// use hal::blocking::digital::OutputPin;
+use crate::hal::{Eh02, OutputPin};
 use crate::DataBusError;
-use hal::digital::v2::OutputPin;
+use core::marker::PhantomData;
 
-mod private {
-    use super::*;
-    pub trait Sealed {}
+/// Bidirectional pin, required for the data pins of a readable bus so that the busy flag, address
+/// counter and RAM contents can be read back from the device. Direction is switched at runtime.
+///
+/// embedded-hal does not (yet) provide an IoPin trait, so it is implemented by the user for their
+/// device's pins. When/if an official trait is provided, this will be replaced.
+pub trait IoPin {
+    type Error;
 
-    impl<T: OutputPin> Sealed for T {}
+    /// Configure the pin as an output so it can be driven high or low.
+    fn set_as_output(&mut self) -> Result<(), Self::Error>;
+
+    /// Configure the pin as an input (floating or pulled up) so it can be read.
+    fn set_as_input(&mut self) -> Result<(), Self::Error>;
+
+    /// Drive the pin high. Pin must be configured as an output.
+    fn set_high(&mut self) -> Result<(), Self::Error>;
+
+    /// Drive the pin low. Pin must be configured as an output.
+    fn set_low(&mut self) -> Result<(), Self::Error>;
+
+    /// Read the pin state. Pin must be configured as an input.
+    fn is_high(&self) -> Result<bool, Self::Error>;
 }
 
 /// ReadWrite pin.
 /// Select read or write mode.
-pub struct ReadWritePin<T: OutputPin> {
+pub struct ReadWritePin<T: OutputPin<H>, H = Eh02> {
     pin: T,
+    _hal: PhantomData<H>,
 }
 
-impl<T: OutputPin> ReadWritePin<T> {
+impl<T: OutputPin<H>, H> ReadWritePin<T, H> {
     /// Create a new read/write pin.
-    pub fn from_output_pin(pin: T) -> ReadWritePin<T> {
-        ReadWritePin { pin }
+    pub fn from_output_pin(pin: T) -> ReadWritePin<T, H> {
+        ReadWritePin {
+            pin,
+            _hal: PhantomData,
+        }
     }
 
     /// Destroy pin.
@@ -30,26 +50,30 @@ impl<T: OutputPin> ReadWritePin<T> {
     }
 
     /// Select read mode.
-    pub fn set_read(&mut self) -> Result<(), DataBusError> {
-        self.pin.set_high().map_err(|_| DataBusError::PinSetError)
+    pub fn set_read(&mut self) -> Result<(), DataBusError<T::Error>> {
+        self.pin.set_high().map_err(DataBusError::PinSetError)
     }
 
     /// Select write mode.
-    pub fn set_write(&mut self) -> Result<(), DataBusError> {
-        self.pin.set_low().map_err(|_| DataBusError::PinSetError)
+    pub fn set_write(&mut self) -> Result<(), DataBusError<T::Error>> {
+        self.pin.set_low().map_err(DataBusError::PinSetError)
     }
 }
 
 /// RegisterSelect pin.
 /// Select which of the instruction and data registers are currently selected.
-pub struct RegisterSelectPin<T: OutputPin> {
+pub struct RegisterSelectPin<T: OutputPin<H>, H = Eh02> {
     pin: T,
+    _hal: PhantomData<H>,
 }
 
-impl<T: OutputPin> RegisterSelectPin<T> {
+impl<T: OutputPin<H>, H> RegisterSelectPin<T, H> {
     /// Create a new register selection pin
     pub fn from_output_pin(pin: T) -> Self {
-        RegisterSelectPin { pin }
+        RegisterSelectPin {
+            pin,
+            _hal: PhantomData,
+        }
     }
 
     /// Destroy pin.
@@ -58,26 +82,30 @@ impl<T: OutputPin> RegisterSelectPin<T> {
     }
 
     /// Select the instruction register (for write) or [busy flag:address counter] (for read)
-    pub fn select_instruction_register(&mut self) -> Result<(), DataBusError> {
-        self.pin.set_low().map_err(|_| DataBusError::PinSetError)
+    pub fn select_instruction_register(&mut self) -> Result<(), DataBusError<T::Error>> {
+        self.pin.set_low().map_err(DataBusError::PinSetError)
     }
 
     /// Select the data register (for write and read)
-    pub fn select_data_register(&mut self) -> Result<(), DataBusError> {
-        self.pin.set_high().map_err(|_| DataBusError::PinSetError)
+    pub fn select_data_register(&mut self) -> Result<(), DataBusError<T::Error>> {
+        self.pin.set_high().map_err(DataBusError::PinSetError)
     }
 }
 
 /// Enable pin.
 /// Starts data read/write.
-pub struct EnablePin<T: OutputPin> {
+pub struct EnablePin<T: OutputPin<H>, H = Eh02> {
     pin: T,
+    _hal: PhantomData<H>,
 }
 
-impl<T: OutputPin> EnablePin<T> {
+impl<T: OutputPin<H>, H> EnablePin<T, H> {
     /// Create a new enable pin
     pub fn from_output_pin(pin: T) -> Self {
-        EnablePin { pin }
+        EnablePin {
+            pin,
+            _hal: PhantomData,
+        }
     }
 
     /// Destroy pin.
@@ -86,12 +114,12 @@ impl<T: OutputPin> EnablePin<T> {
     }
 
     /// Start data read/write
-    pub fn start_transaction(&mut self) -> Result<(), DataBusError> {
-        self.pin.set_high().map_err(|_| DataBusError::PinSetError)
+    pub fn start_transaction(&mut self) -> Result<(), DataBusError<T::Error>> {
+        self.pin.set_high().map_err(DataBusError::PinSetError)
     }
 
     /// Stop data read/write.
-    pub fn end_transaction(&mut self) -> Result<(), DataBusError> {
-        self.pin.set_low().map_err(|_| DataBusError::PinSetError)
+    pub fn end_transaction(&mut self) -> Result<(), DataBusError<T::Error>> {
+        self.pin.set_low().map_err(DataBusError::PinSetError)
     }
 }
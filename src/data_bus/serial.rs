@@ -0,0 +1,447 @@
+//! Serial interfaces of controllers that accept HD44780 instructions natively, such as the ST7032,
+//! ST7036 and AIP31068 (Grove LCDs). Whole bytes are transferred, so the device is used in 8-bit
+//! mode and no enable pulse is formed by the bus.
+//!
+//! Over I2C each transfer starts with a control byte: RS selects the instruction or data register
+//! and the continuation bit (Co) clear marks it as the last control byte, so every byte after it
+//! goes to the same register. Over SPI the register is selected by a separate RS pin instead.
+
+use crate::data_bus::*;
+use crate::hal::{Delay, Eh02, I2cWrite, OutputPin, SpiWrite};
+#[cfg(feature = "hal-1")]
+use crate::hal::{DeviceSelect, Eh1};
+use crate::timing::Timing;
+use crate::DataBusError;
+use core::marker::PhantomData;
+#[cfg(feature = "async")]
+use embedded_hal_1 as hal_1;
+#[cfg(feature = "async")]
+use embedded_hal_async::{delay::DelayNs, i2c::I2c, spi::SpiDevice};
+
+/// I2C address of the ST7032i and AIP31068L.
+pub const ST7032_I2C_ADDRESS: u8 = 0x3E;
+
+/// Control byte register select bit.
+const CONTROL_RS: u8 = 0x40;
+
+/// Largest payload sent in a single I2C transaction, enough for the entire DDRAM.
+const MAX_PAYLOAD: usize = 80;
+
+/// Clock speed of the I2C bus, which decides how many bytes are sent after each control byte.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum I2cSpeed {
+    /// Standard mode (100 kHz) or slower. Up to 80 bytes follow a single control byte and the
+    /// controller executes each while the next is clocked in, which only works because a byte
+    /// takes 90 µs on the bus at 100 kHz, longer than a write takes to execute.
+    #[default]
+    Standard,
+
+    /// Fast mode (400 kHz) or faster, where a byte takes about 22.5 µs on the bus. Each byte is sent
+    /// in a transaction of its own and the execution time is waited out between them.
+    Fast,
+}
+
+/// Control byte selecting the register. The continuation bit is left clear, so all following bytes
+/// of the transfer are written to the same register.
+fn control_byte(transaction: &TransactionType) -> u8 {
+    match transaction {
+        TransactionType::Instruction => 0,
+        TransactionType::Data => CONTROL_RS,
+    }
+}
+
+/// I2C interface of a controller with a native serial interface.
+pub struct SerialI2cBus<I2C, H = Eh02> {
+    i2c: I2C,
+    address: u8,
+    speed: I2cSpeed,
+    timing: Timing,
+    _hal: PhantomData<H>,
+}
+
+impl<I2C, H> SerialI2cBus<I2C, H> {
+    /// Create a new bus for the controller at `address` (7-bit) on an I2C bus clocked at `speed`.
+    pub fn new(i2c: I2C, address: u8, speed: I2cSpeed) -> SerialI2cBus<I2C, H> {
+        SerialI2cBus {
+            i2c,
+            address,
+            speed,
+            timing: Default::default(),
+            _hal: PhantomData,
+        }
+    }
+
+    /// Destroy the bus and return the I2C peripheral.
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    /// Number of bytes sent after the control byte of each transaction.
+    fn payload_len(&self) -> usize {
+        match self.speed {
+            I2cSpeed::Standard => MAX_PAYLOAD,
+            I2cSpeed::Fast => 1,
+        }
+    }
+}
+
+impl<I2C: I2cWrite<H>, H> DataBus for SerialI2cBus<I2C, H> {
+    type Error = DataBusError<I2C::Error>;
+    type Hal = H;
+
+    fn timing(&self) -> &Timing {
+        &self.timing
+    }
+
+    fn set_timing(&mut self, timing: Timing) {
+        self.timing = timing;
+    }
+
+    fn write_byte<D: Delay<H>>(
+        &mut self,
+        byte: u8,
+        transaction: TransactionType,
+        _delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        self.i2c
+            .write(self.address, &[control_byte(&transaction), byte])
+            .map_err(DataBusError::BusError)
+    }
+
+    fn write_bytes<D: Delay<H>>(
+        &mut self,
+        bytes: &[u8],
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        let mut buffer = [0; MAX_PAYLOAD + 1];
+        buffer[0] = control_byte(&transaction);
+        for (i, chunk) in bytes.chunks(self.payload_len()).enumerate() {
+            if i != 0 {
+                delay.delay_us(self.timing.execution_us);
+            }
+            buffer[1..=chunk.len()].copy_from_slice(chunk);
+            self.i2c
+                .write(self.address, &buffer[..=chunk.len()])
+                .map_err(DataBusError::BusError)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// 4-wire SPI interface of a controller with a native serial interface: chip select (active low)
+/// frames each byte and the RS pin selects the register.
+pub struct SerialSpiBus<SPI, CS: OutputPin<H>, RS: OutputPin<H>, H = Eh02> {
+    spi: SPI,
+    cs: CS,
+    rs: RS,
+    timing: Timing,
+    _hal: PhantomData<H>,
+}
+
+impl<SPI, CS, RS, H> SerialSpiBus<SPI, CS, RS, H>
+where
+    CS: OutputPin<H>,
+    RS: OutputPin<H, Error = CS::Error>,
+{
+    /// Create a new bus from the SPI peripheral and the chip select and register select pins.
+    pub fn new(spi: SPI, cs: CS, rs: RS) -> SerialSpiBus<SPI, CS, RS, H> {
+        SerialSpiBus {
+            spi,
+            cs,
+            rs,
+            timing: Default::default(),
+            _hal: PhantomData,
+        }
+    }
+
+    /// Destroy the bus and return the SPI peripheral and pins.
+    pub fn release(self) -> (SPI, CS, RS) {
+        (self.spi, self.cs, self.rs)
+    }
+}
+
+impl<SPI, CS, RS, H> SerialSpiBus<SPI, CS, RS, H>
+where
+    SPI: SpiWrite<H>,
+    CS: OutputPin<H>,
+    RS: OutputPin<H, Error = CS::Error>,
+{
+    /// Select the register, then send a byte framed by chip select. Chip select is released even
+    /// if the transfer fails.
+    fn send(
+        &mut self,
+        byte: u8,
+        transaction: &TransactionType,
+    ) -> Result<(), DataBusError<SpiError<SPI::Error, CS::Error>>> {
+        match transaction {
+            TransactionType::Instruction => self.rs.set_low(),
+            TransactionType::Data => self.rs.set_high(),
+        }
+        .map_err(|e| DataBusError::PinSetError(SpiError::Pin(e)))?;
+        self.cs
+            .set_low()
+            .map_err(|e| DataBusError::PinSetError(SpiError::Pin(e)))?;
+        let result = self
+            .spi
+            .write(&[byte])
+            .map_err(|e| DataBusError::BusError(SpiError::Spi(e)));
+        self.cs
+            .set_high()
+            .map_err(|e| DataBusError::PinSetError(SpiError::Pin(e)))?;
+        result
+    }
+}
+
+#[cfg(feature = "hal-1")]
+impl<SPI, RS: OutputPin<Eh1>> SerialSpiBus<SPI, DeviceSelect<RS::Error>, RS, Eh1> {
+    /// Create a new bus from an embedded-hal 1.0 `SpiDevice`, which frames each byte with its own
+    /// chip select, and the register select pin.
+    pub fn new_device(spi: SPI, rs: RS) -> SerialSpiBus<SPI, DeviceSelect<RS::Error>, RS, Eh1> {
+        SerialSpiBus::new(spi, DeviceSelect::new(), rs)
+    }
+}
+
+impl<SPI, CS, RS, H> DataBus for SerialSpiBus<SPI, CS, RS, H>
+where
+    SPI: SpiWrite<H>,
+    CS: OutputPin<H>,
+    RS: OutputPin<H, Error = CS::Error>,
+{
+    type Error = DataBusError<SpiError<SPI::Error, CS::Error>>;
+    type Hal = H;
+
+    fn timing(&self) -> &Timing {
+        &self.timing
+    }
+
+    fn set_timing(&mut self, timing: Timing) {
+        self.timing = timing;
+    }
+
+    fn write_byte<D: Delay<H>>(
+        &mut self,
+        byte: u8,
+        transaction: TransactionType,
+        _delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        self.send(byte, &transaction)
+    }
+
+    fn write_bytes<D: Delay<H>>(
+        &mut self,
+        bytes: &[u8],
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        for (i, byte) in bytes.iter().enumerate() {
+            if i != 0 {
+                delay.delay_us(self.timing.execution_us);
+            }
+            self.send(*byte, &transaction)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<I2C: I2c> AsyncDataBus for SerialI2cBus<I2C, Eh1> {
+    type Error = DataBusError<I2C::Error>;
+
+    fn timing(&self) -> &Timing {
+        &self.timing
+    }
+
+    fn set_timing(&mut self, timing: Timing) {
+        self.timing = timing;
+    }
+
+    async fn write_byte<D: DelayNs>(
+        &mut self,
+        byte: u8,
+        transaction: TransactionType,
+        _delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        self.i2c
+            .write(self.address, &[control_byte(&transaction), byte])
+            .await
+            .map_err(DataBusError::BusError)
+    }
+
+    async fn write_bytes<D: DelayNs>(
+        &mut self,
+        bytes: &[u8],
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        let mut buffer = [0; MAX_PAYLOAD + 1];
+        buffer[0] = control_byte(&transaction);
+        for (i, chunk) in bytes.chunks(self.payload_len()).enumerate() {
+            if i != 0 {
+                delay.delay_us(self.timing.execution_us.into()).await;
+            }
+            buffer[1..=chunk.len()].copy_from_slice(chunk);
+            self.i2c
+                .write(self.address, &buffer[..=chunk.len()])
+                .await
+                .map_err(DataBusError::BusError)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<SPI, RS> SerialSpiBus<SPI, DeviceSelect<RS::Error>, RS, Eh1>
+where
+    SPI: SpiDevice,
+    RS: hal_1::digital::OutputPin,
+{
+    /// Select the register, then send a byte, awaiting the SPI transfer. The device frames the
+    /// transfer with its own chip select.
+    async fn send_async(
+        &mut self,
+        byte: u8,
+        transaction: &TransactionType,
+    ) -> Result<(), DataBusError<SpiError<SPI::Error, RS::Error>>> {
+        match transaction {
+            TransactionType::Instruction => self.rs.set_low(),
+            TransactionType::Data => self.rs.set_high(),
+        }
+        .map_err(|e| DataBusError::PinSetError(SpiError::Pin(e)))?;
+        self.spi
+            .write(&[byte])
+            .await
+            .map_err(|e| DataBusError::BusError(SpiError::Spi(e)))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<SPI, RS> AsyncDataBus for SerialSpiBus<SPI, DeviceSelect<RS::Error>, RS, Eh1>
+where
+    SPI: SpiDevice,
+    RS: hal_1::digital::OutputPin,
+{
+    type Error = DataBusError<SpiError<SPI::Error, RS::Error>>;
+
+    fn timing(&self) -> &Timing {
+        &self.timing
+    }
+
+    fn set_timing(&mut self, timing: Timing) {
+        self.timing = timing;
+    }
+
+    async fn write_byte<D: DelayNs>(
+        &mut self,
+        byte: u8,
+        transaction: TransactionType,
+        _delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        self.send_async(byte, &transaction).await
+    }
+
+    async fn write_bytes<D: DelayNs>(
+        &mut self,
+        bytes: &[u8],
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        for (i, byte) in bytes.iter().enumerate() {
+            if i != 0 {
+                delay.delay_us(self.timing.execution_us.into()).await;
+            }
+            self.send_async(*byte, &transaction).await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{Delay, I2c, Port, Spi};
+
+    extern crate std;
+    use std::vec;
+    use std::vec::Vec;
+
+    #[test]
+    fn i2c_transfers_start_with_a_control_byte() {
+        let i2c = I2c::default();
+        let mut bus = SerialI2cBus::new(i2c.clone(), ST7032_I2C_ADDRESS, I2cSpeed::Standard);
+        let mut delay = Delay::default();
+
+        bus.write_byte(0x38, TransactionType::Instruction, &mut delay)
+            .unwrap();
+        bus.write_bytes(b"Hi", TransactionType::Data, &mut delay)
+            .unwrap();
+
+        assert_eq!(
+            i2c.writes(),
+            [
+                (ST7032_I2C_ADDRESS, vec![0x00, 0x38]),
+                (ST7032_I2C_ADDRESS, vec![0x40, b'H', b'i']),
+            ]
+        );
+        assert!(delay.log.is_empty());
+    }
+
+    #[test]
+    fn standard_mode_sends_up_to_80_bytes_per_control_byte() {
+        let i2c = I2c::default();
+        let mut bus = SerialI2cBus::new(i2c.clone(), ST7032_I2C_ADDRESS, I2cSpeed::Standard);
+        let mut delay = Delay::default();
+
+        bus.write_bytes(&[b'x'; 100], TransactionType::Data, &mut delay)
+            .unwrap();
+
+        let lengths: Vec<_> = i2c.writes().iter().map(|(_, bytes)| bytes.len()).collect();
+        assert_eq!(lengths, [81, 21]);
+        assert!(i2c.writes().iter().all(|(_, bytes)| bytes[0] == 0x40));
+        assert_eq!(delay.log, [u32::from(bus.timing().execution_us)]);
+    }
+
+    #[test]
+    fn fast_mode_sends_one_byte_per_control_byte() {
+        let i2c = I2c::default();
+        let mut bus = SerialI2cBus::new(i2c.clone(), ST7032_I2C_ADDRESS, I2cSpeed::Fast);
+        let mut delay = Delay::default();
+
+        bus.write_bytes(b"abc", TransactionType::Data, &mut delay)
+            .unwrap();
+
+        assert_eq!(
+            i2c.writes(),
+            [
+                (ST7032_I2C_ADDRESS, vec![0x40, b'a']),
+                (ST7032_I2C_ADDRESS, vec![0x40, b'b']),
+                (ST7032_I2C_ADDRESS, vec![0x40, b'c']),
+            ]
+        );
+        assert_eq!(delay.log.len(), 2);
+    }
+
+    #[test]
+    fn spi_bytes_are_framed_by_chip_select_with_register_selected() {
+        let port = Port::default();
+        let spi = Spi::on(&port);
+        let mut bus = SerialSpiBus::new(spi.clone(), port.pin(0), port.pin(1));
+        let mut delay = Delay::default();
+
+        bus.write_byte(0x38, TransactionType::Instruction, &mut delay)
+            .unwrap();
+        bus.write_bytes(b"Hi", TransactionType::Data, &mut delay)
+            .unwrap();
+
+        // CS on bit 0 low during each transfer, RS on bit 1
+        assert_eq!(
+            spi.transfers(),
+            [(0b00, vec![0x38]), (0b10, vec![b'H']), (0b10, vec![b'i']),]
+        );
+        assert_eq!(port.levels() & 1, 1);
+    }
+}
@@ -0,0 +1,334 @@
+//! 8-bit wide data bus shared by several displays. The data and register select pins are connected
+//! to every display, each display has its own enable pin and only latches transfers while it is
+//! enabled, so each additional display costs a single pin.
+//!
+//! The shared pins are borrowed through a `RefCell`, which the drivers only borrow for the length
+//! of a transfer. Once the drivers have been released, `RefCell::into_inner` returns the pins.
+//! Each driver owns its delay, so a delay that can be created or cloned per display is needed.
+//!
+//! ```rust, ignore
+//! let pins = RefCell::new(SharedPins8::from_pins(rs, d0, d1, d2, d3, d4, d5, d6, d7));
+//! let mut top = WriteOnlyHD44780::new_shared_bus8(en1, &pins, delay1, config)?;
+//! let mut bottom = WriteOnlyHD44780::new_shared_bus8(en2, &pins, delay2, config)?;
+//! ```
+
+use crate::data_bus::*;
+use crate::hal::{Delay, Eh02, OutputPin};
+use crate::timing::Timing;
+use crate::DataBusError;
+use core::cell::RefCell;
+use pins::*;
+
+/// Register select and data pins shared by several displays.
+pub struct SharedPins8<
+    RS: OutputPin<H>,
+    D0: OutputPin<H>,
+    D1: OutputPin<H>,
+    D2: OutputPin<H>,
+    D3: OutputPin<H>,
+    D4: OutputPin<H>,
+    D5: OutputPin<H>,
+    D6: OutputPin<H>,
+    D7: OutputPin<H>,
+    H = Eh02,
+> {
+    rs: RegisterSelectPin<RS, H>,
+    d0: D0,
+    d1: D1,
+    d2: D2,
+    d3: D3,
+    d4: D4,
+    d5: D5,
+    d6: D6,
+    d7: D7,
+}
+
+impl<RS, D0, D1, D2, D3, D4, D5, D6, D7, H> SharedPins8<RS, D0, D1, D2, D3, D4, D5, D6, D7, H>
+where
+    RS: OutputPin<H>,
+    D0: OutputPin<H, Error = RS::Error>,
+    D1: OutputPin<H, Error = RS::Error>,
+    D2: OutputPin<H, Error = RS::Error>,
+    D3: OutputPin<H, Error = RS::Error>,
+    D4: OutputPin<H, Error = RS::Error>,
+    D5: OutputPin<H, Error = RS::Error>,
+    D6: OutputPin<H, Error = RS::Error>,
+    D7: OutputPin<H, Error = RS::Error>,
+{
+    /// Group the shared pins.
+    pub fn from_pins(
+        rs: RS,
+        d0: D0,
+        d1: D1,
+        d2: D2,
+        d3: D3,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+    ) -> SharedPins8<RS, D0, D1, D2, D3, D4, D5, D6, D7, H> {
+        SharedPins8 {
+            rs: RegisterSelectPin::from_output_pin(rs),
+            d0,
+            d1,
+            d2,
+            d3,
+            d4,
+            d5,
+            d6,
+            d7,
+        }
+    }
+
+    /// Destroy the group and return the pins.
+    pub fn release(self) -> (RS, D0, D1, D2, D3, D4, D5, D6, D7) {
+        (
+            self.rs.release(),
+            self.d0,
+            self.d1,
+            self.d2,
+            self.d3,
+            self.d4,
+            self.d5,
+            self.d6,
+            self.d7,
+        )
+    }
+
+    /// Select the instruction or data register.
+    fn select_register(
+        &mut self,
+        transaction: &TransactionType,
+    ) -> Result<(), DataBusError<RS::Error>> {
+        match transaction {
+            TransactionType::Instruction => self.rs.select_instruction_register(),
+            TransactionType::Data => self.rs.select_data_register(),
+        }
+    }
+
+    /// Set the data pins to the provided value
+    pub fn set_pins(&mut self, data: u8) -> Result<(), DataBusError<RS::Error>> {
+        if data & (1 << 0) != 0 {
+            self.d0.set_high().map_err(DataBusError::PinSetError)?;
+        } else {
+            self.d0.set_low().map_err(DataBusError::PinSetError)?;
+        }
+        if data & (1 << 1) != 0 {
+            self.d1.set_high().map_err(DataBusError::PinSetError)?;
+        } else {
+            self.d1.set_low().map_err(DataBusError::PinSetError)?;
+        }
+        if data & (1 << 2) != 0 {
+            self.d2.set_high().map_err(DataBusError::PinSetError)?;
+        } else {
+            self.d2.set_low().map_err(DataBusError::PinSetError)?;
+        }
+        if data & (1 << 3) != 0 {
+            self.d3.set_high().map_err(DataBusError::PinSetError)?;
+        } else {
+            self.d3.set_low().map_err(DataBusError::PinSetError)?;
+        }
+        if data & (1 << 4) != 0 {
+            self.d4.set_high().map_err(DataBusError::PinSetError)?;
+        } else {
+            self.d4.set_low().map_err(DataBusError::PinSetError)?;
+        }
+        if data & (1 << 5) != 0 {
+            self.d5.set_high().map_err(DataBusError::PinSetError)?;
+        } else {
+            self.d5.set_low().map_err(DataBusError::PinSetError)?;
+        }
+        if data & (1 << 6) != 0 {
+            self.d6.set_high().map_err(DataBusError::PinSetError)?;
+        } else {
+            self.d6.set_low().map_err(DataBusError::PinSetError)?;
+        }
+        if data & (1 << 7) != 0 {
+            self.d7.set_high().map_err(DataBusError::PinSetError)?;
+        } else {
+            self.d7.set_low().map_err(DataBusError::PinSetError)?;
+        }
+        Ok(())
+    }
+}
+
+/// 8-bit data bus of one display, owning its enable pin and borrowing the pins shared with the
+/// other displays. Note: RW pin on each device should be tied low.
+pub struct SharedBus8<
+    'a,
+    EN: OutputPin<H>,
+    RS: OutputPin<H>,
+    D0: OutputPin<H>,
+    D1: OutputPin<H>,
+    D2: OutputPin<H>,
+    D3: OutputPin<H>,
+    D4: OutputPin<H>,
+    D5: OutputPin<H>,
+    D6: OutputPin<H>,
+    D7: OutputPin<H>,
+    H = Eh02,
+> {
+    en: EnablePin<EN, H>,
+    pins: &'a RefCell<SharedPins8<RS, D0, D1, D2, D3, D4, D5, D6, D7, H>>,
+    timing: Timing,
+}
+
+impl<'a, EN, RS, D0, D1, D2, D3, D4, D5, D6, D7, H>
+    SharedBus8<'a, EN, RS, D0, D1, D2, D3, D4, D5, D6, D7, H>
+where
+    EN: OutputPin<H>,
+    RS: OutputPin<H, Error = EN::Error>,
+    D0: OutputPin<H, Error = EN::Error>,
+    D1: OutputPin<H, Error = EN::Error>,
+    D2: OutputPin<H, Error = EN::Error>,
+    D3: OutputPin<H, Error = EN::Error>,
+    D4: OutputPin<H, Error = EN::Error>,
+    D5: OutputPin<H, Error = EN::Error>,
+    D6: OutputPin<H, Error = EN::Error>,
+    D7: OutputPin<H, Error = EN::Error>,
+{
+    /// Create a new bus from the enable pin of the display and the shared pins.
+    pub fn new(
+        en: EN,
+        pins: &'a RefCell<SharedPins8<RS, D0, D1, D2, D3, D4, D5, D6, D7, H>>,
+    ) -> SharedBus8<'a, EN, RS, D0, D1, D2, D3, D4, D5, D6, D7, H> {
+        SharedBus8 {
+            en: EnablePin::from_output_pin(en),
+            pins,
+            timing: Default::default(),
+        }
+    }
+
+    /// Destroy the bus and return the enable pin.
+    pub fn release(self) -> EN {
+        self.en.release()
+    }
+
+    /// Put a byte on the shared pins and latch it into this display. The register must already be
+    /// selected.
+    fn send_byte<D: Delay<H>>(
+        &mut self,
+        pins: &mut SharedPins8<RS, D0, D1, D2, D3, D4, D5, D6, D7, H>,
+        byte: u8,
+        delay: &mut D,
+    ) -> Result<(), DataBusError<EN::Error>> {
+        pins.set_pins(byte)?;
+        delay.delay_us(self.timing.address_setup_us);
+        self.en.start_transaction()?;
+        delay.delay_us(self.timing.enable_pulse_us);
+        self.en.end_transaction()
+    }
+}
+
+impl<'a, EN, RS, D0, D1, D2, D3, D4, D5, D6, D7, H> DataBus
+    for SharedBus8<'a, EN, RS, D0, D1, D2, D3, D4, D5, D6, D7, H>
+where
+    EN: OutputPin<H>,
+    RS: OutputPin<H, Error = EN::Error>,
+    D0: OutputPin<H, Error = EN::Error>,
+    D1: OutputPin<H, Error = EN::Error>,
+    D2: OutputPin<H, Error = EN::Error>,
+    D3: OutputPin<H, Error = EN::Error>,
+    D4: OutputPin<H, Error = EN::Error>,
+    D5: OutputPin<H, Error = EN::Error>,
+    D6: OutputPin<H, Error = EN::Error>,
+    D7: OutputPin<H, Error = EN::Error>,
+{
+    type Error = DataBusError<EN::Error>;
+    type Hal = H;
+
+    fn timing(&self) -> &Timing {
+        &self.timing
+    }
+
+    fn set_timing(&mut self, timing: Timing) {
+        self.timing = timing;
+    }
+
+    fn write_byte<D: Delay<H>>(
+        &mut self,
+        byte: u8,
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        let pins = self.pins;
+        let mut pins = pins.borrow_mut();
+        pins.select_register(&transaction)?;
+        self.send_byte(&mut pins, byte, delay)
+    }
+
+    fn write_bytes<D: Delay<H>>(
+        &mut self,
+        bytes: &[u8],
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        let pins = self.pins;
+        let mut pins = pins.borrow_mut();
+        pins.select_register(&transaction)?;
+        for (i, byte) in bytes.iter().enumerate() {
+            if i != 0 {
+                delay.delay_us(self.timing.execution_us);
+            }
+            self.send_byte(&mut pins, *byte, delay)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{Delay, Port};
+
+    extern crate std;
+    use std::vec::Vec;
+
+    /// Bytes latched by the display enabled by `enable` from transfer `from` on, as (RS, byte),
+    /// with RS on bit 2 and D0-D7 on bits 3-10.
+    fn latched_bytes(port: &Port, enable: u8, from: usize) -> Vec<(u32, u32)> {
+        port.latched(enable)[from..]
+            .iter()
+            .map(|levels| (levels >> 2 & 1, levels >> 3 & 0xFF))
+            .collect()
+    }
+
+    #[test]
+    fn only_the_enabled_display_latches_a_transfer() {
+        let port = Port::default();
+        let pins = RefCell::new(SharedPins8::from_pins(
+            port.pin(2),
+            port.pin(3),
+            port.pin(4),
+            port.pin(5),
+            port.pin(6),
+            port.pin(7),
+            port.pin(8),
+            port.pin(9),
+            port.pin(10),
+        ));
+        let mut top = SharedBus8::new(port.pin(0), &pins);
+        let mut bottom = SharedBus8::new(port.pin(1), &pins);
+        let mut delay = Delay::default();
+
+        top.write_byte(0x38, TransactionType::Instruction, &mut delay)
+            .unwrap();
+        bottom
+            .write_bytes(b"ab", TransactionType::Data, &mut delay)
+            .unwrap();
+        top.write_byte(b'c', TransactionType::Data, &mut delay)
+            .unwrap();
+
+        assert_eq!(
+            latched_bytes(&port, 0, 0),
+            [(0, 0x38), (1, u32::from(b'c'))]
+        );
+        assert_eq!(
+            latched_bytes(&port, 1, 0),
+            [(1, u32::from(b'a')), (1, u32::from(b'b'))]
+        );
+        // The shared pins are only borrowed during a transfer
+        assert!(pins.try_borrow_mut().is_ok());
+    }
+}
@@ -0,0 +1,391 @@
+//! 4-bit data bus through a 74HC595 shift register ("3-wire LCD" modules). The register is loaded
+//! over SPI (data and clock) and its outputs are updated on the rising edge of the latch pin. RS,
+//! E, the backlight and D4-D7 are driven from the register outputs (Q0-Q7); RW must be tied low.
+
+use crate::data_bus::*;
+use crate::hal::{Delay, Eh02, OutputPin, SpiWrite};
+#[cfg(feature = "hal-1")]
+use crate::hal::{DeviceSelect, Eh1};
+use crate::timing::Timing;
+use crate::DataBusError;
+use core::marker::PhantomData;
+#[cfg(feature = "async")]
+use embedded_hal_async::{delay::DelayNs, spi::SpiDevice};
+
+/// Assignment of the register outputs (Q0-Q7) to the display pins, as bit numbers 0-7 of the byte
+/// shifted out most significant bit first.
+#[derive(Copy, Clone, Debug)]
+pub struct Shift595Mapping {
+    rs: u8,
+    en: u8,
+    backlight: u8,
+    data: [u8; 4],
+    backlight_active_high: bool,
+}
+
+impl Shift595Mapping {
+    /// Custom mapping. `data` holds the register outputs of D4-D7, in that order. Returns
+    /// `InvalidPinMapping` if a pin is greater than 7 or assigned twice.
+    pub fn new<E>(
+        rs: u8,
+        en: u8,
+        backlight: u8,
+        data: [u8; 4],
+        backlight_active_high: bool,
+    ) -> Result<Self, DataBusError<E>> {
+        let mapping = Shift595Mapping {
+            rs,
+            en,
+            backlight,
+            data,
+            backlight_active_high,
+        };
+        check_pin_mapping(&mapping.pins(), &[], 8)?;
+        Ok(mapping)
+    }
+
+    /// Mapping of the Adafruit I2C/SPI backpack in SPI mode: RS=Q1, E=Q2, D4=Q6, D5=Q5, D6=Q4,
+    /// D7=Q3, backlight=Q7 (active high).
+    pub const fn adafruit_backpack() -> Self {
+        Shift595Mapping {
+            rs: 1,
+            en: 2,
+            backlight: 7,
+            data: [6, 5, 4, 3],
+            backlight_active_high: true,
+        }
+    }
+
+    /// Every register output used by the mapping.
+    fn pins(&self) -> [u8; 7] {
+        let [d4, d5, d6, d7] = self.data;
+        [self.rs, self.en, self.backlight, d4, d5, d6, d7]
+    }
+
+    /// Register byte driving the lower nibble of `nibble` onto D4-D7.
+    fn data_bits(&self, nibble: u8) -> u8 {
+        let mut bits = 0;
+        for (i, pin) in self.data.iter().enumerate() {
+            if nibble & (1 << i) != 0 {
+                bits |= 1 << pin;
+            }
+        }
+        bits
+    }
+}
+
+impl Default for Shift595Mapping {
+    fn default() -> Self {
+        Shift595Mapping::adafruit_backpack()
+    }
+}
+
+/// 4-bit data bus through a 74HC595 shift register.
+pub struct Shift595Bus<SPI, LATCH, H = Eh02> {
+    spi: SPI,
+    latch: LATCH,
+    mapping: Shift595Mapping,
+    backlight: bool,
+    timing: Timing,
+    _hal: PhantomData<H>,
+}
+
+impl<SPI, LATCH, H> Shift595Bus<SPI, LATCH, H> {
+    /// Create a new bus with the given bit mapping. The backlight is turned on by the first
+    /// transfer.
+    pub fn new(spi: SPI, latch: LATCH, mapping: Shift595Mapping) -> Shift595Bus<SPI, LATCH, H> {
+        Shift595Bus {
+            spi,
+            latch,
+            mapping,
+            backlight: true,
+            timing: Default::default(),
+            _hal: PhantomData,
+        }
+    }
+
+    /// Destroy the bus and return the SPI peripheral and latch pin.
+    pub fn release(self) -> (SPI, LATCH) {
+        (self.spi, self.latch)
+    }
+
+    /// Register bit for the current backlight state.
+    fn backlight_bits(&self) -> u8 {
+        if self.backlight == self.mapping.backlight_active_high {
+            1 << self.mapping.backlight
+        } else {
+            0
+        }
+    }
+
+    /// Register byte for the control pins, with E low.
+    fn control_bits(&self, transaction: &TransactionType) -> u8 {
+        match transaction {
+            TransactionType::Instruction => self.backlight_bits(),
+            TransactionType::Data => self.backlight_bits() | 1 << self.mapping.rs,
+        }
+    }
+}
+
+impl<SPI, LATCH, H> Shift595Bus<SPI, LATCH, H>
+where
+    SPI: SpiWrite<H>,
+    LATCH: OutputPin<H>,
+{
+    /// Turn the backlight on or off. The data pins are left low.
+    pub fn set_backlight(
+        &mut self,
+        on: bool,
+    ) -> Result<(), DataBusError<SpiError<SPI::Error, LATCH::Error>>> {
+        self.backlight = on;
+        self.shift_out(self.backlight_bits())
+    }
+
+    /// Shift a byte into the register and latch it onto the outputs.
+    fn shift_out(
+        &mut self,
+        bits: u8,
+    ) -> Result<(), DataBusError<SpiError<SPI::Error, LATCH::Error>>> {
+        self.latch
+            .set_low()
+            .map_err(|e| DataBusError::PinSetError(SpiError::Pin(e)))?;
+        self.spi
+            .write(&[bits])
+            .map_err(|e| DataBusError::BusError(SpiError::Spi(e)))?;
+        self.latch
+            .set_high()
+            .map_err(|e| DataBusError::PinSetError(SpiError::Pin(e)))
+    }
+
+    /// Send a nibble, latched into the device by an enable pulse.
+    fn send_nibble<D: Delay<H>>(
+        &mut self,
+        nibble: u8,
+        transaction: &TransactionType,
+        delay: &mut D,
+    ) -> Result<(), DataBusError<SpiError<SPI::Error, LATCH::Error>>> {
+        let bits = self.control_bits(transaction) | self.mapping.data_bits(nibble);
+        self.shift_out(bits)?;
+        delay.delay_us(self.timing.address_setup_us);
+        self.shift_out(bits | 1 << self.mapping.en)?;
+        delay.delay_us(self.timing.enable_pulse_us);
+        self.shift_out(bits)
+    }
+}
+
+#[cfg(feature = "hal-1")]
+impl<SPI> Shift595Bus<SPI, DeviceSelect, Eh1> {
+    /// Create a new bus on an embedded-hal 1.0 `SpiDevice`, with its chip select wired to the latch
+    /// pin: the register outputs are updated as the device releases chip select after each
+    /// transfer.
+    pub fn new_device(spi: SPI, mapping: Shift595Mapping) -> Shift595Bus<SPI, DeviceSelect, Eh1> {
+        Shift595Bus::new(spi, DeviceSelect::new(), mapping)
+    }
+}
+
+impl<SPI, LATCH, H> DataBus for Shift595Bus<SPI, LATCH, H>
+where
+    SPI: SpiWrite<H>,
+    LATCH: OutputPin<H>,
+{
+    type Error = DataBusError<SpiError<SPI::Error, LATCH::Error>>;
+    type Hal = H;
+
+    fn timing(&self) -> &Timing {
+        &self.timing
+    }
+
+    fn set_timing(&mut self, timing: Timing) {
+        self.timing = timing;
+    }
+
+    fn write_byte<D: Delay<H>>(
+        &mut self,
+        byte: u8,
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        self.send_nibble(byte >> 4, &transaction, delay)?;
+        self.send_nibble(byte & 0x0F, &transaction, delay)
+    }
+
+    fn write_bytes<D: Delay<H>>(
+        &mut self,
+        bytes: &[u8],
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        for (i, byte) in bytes.iter().enumerate() {
+            if i != 0 {
+                delay.delay_us(self.timing.execution_us);
+            }
+            self.send_nibble(byte >> 4, &transaction, delay)?;
+            self.send_nibble(byte & 0x0F, &transaction, delay)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<SPI, LATCH, H> FourBitBus for Shift595Bus<SPI, LATCH, H>
+where
+    SPI: SpiWrite<H>,
+    LATCH: OutputPin<H>,
+{
+    fn write_nibble<D: Delay<H>>(
+        &mut self,
+        nibble: u8,
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        self.send_nibble(nibble, &transaction, delay)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<SPI: SpiDevice> Shift595Bus<SPI, DeviceSelect, Eh1> {
+    /// Turn the backlight on or off, awaiting the SPI transfer. The data pins are left low.
+    pub async fn set_backlight_async(&mut self, on: bool) -> Result<(), DataBusError<SPI::Error>> {
+        self.backlight = on;
+        self.shift_out_async(self.backlight_bits()).await
+    }
+
+    /// Shift a byte into the register, awaiting the SPI transfer. The device latches it onto the
+    /// outputs as it releases chip select.
+    async fn shift_out_async(&mut self, bits: u8) -> Result<(), DataBusError<SPI::Error>> {
+        self.spi
+            .write(&[bits])
+            .await
+            .map_err(DataBusError::BusError)
+    }
+
+    /// Send a nibble, latched into the device by an enable pulse, awaiting the transfers and
+    /// delays.
+    async fn send_nibble_async<D: DelayNs>(
+        &mut self,
+        nibble: u8,
+        transaction: &TransactionType,
+        delay: &mut D,
+    ) -> Result<(), DataBusError<SPI::Error>> {
+        let bits = self.control_bits(transaction) | self.mapping.data_bits(nibble);
+        self.shift_out_async(bits).await?;
+        delay.delay_us(self.timing.address_setup_us.into()).await;
+        self.shift_out_async(bits | 1 << self.mapping.en).await?;
+        delay.delay_us(self.timing.enable_pulse_us.into()).await;
+        self.shift_out_async(bits).await
+    }
+}
+
+#[cfg(feature = "async")]
+impl<SPI: SpiDevice> AsyncDataBus for Shift595Bus<SPI, DeviceSelect, Eh1> {
+    type Error = DataBusError<SPI::Error>;
+
+    fn timing(&self) -> &Timing {
+        &self.timing
+    }
+
+    fn set_timing(&mut self, timing: Timing) {
+        self.timing = timing;
+    }
+
+    async fn write_byte<D: DelayNs>(
+        &mut self,
+        byte: u8,
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        self.send_nibble_async(byte >> 4, &transaction, delay)
+            .await?;
+        self.send_nibble_async(byte & 0x0F, &transaction, delay)
+            .await
+    }
+
+    async fn write_bytes<D: DelayNs>(
+        &mut self,
+        bytes: &[u8],
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        for (i, byte) in bytes.iter().enumerate() {
+            if i != 0 {
+                delay.delay_us(self.timing.execution_us.into()).await;
+            }
+            self.send_nibble_async(byte >> 4, &transaction, delay)
+                .await?;
+            self.send_nibble_async(byte & 0x0F, &transaction, delay)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<SPI: SpiDevice> AsyncFourBitBus for Shift595Bus<SPI, DeviceSelect, Eh1> {
+    async fn write_nibble<D: DelayNs>(
+        &mut self,
+        nibble: u8,
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        self.send_nibble_async(nibble, &transaction, delay).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{Delay, Pin, Port, Spi};
+
+    extern crate std;
+    use std::vec;
+
+    fn bus(port: &Port, spi: &Spi) -> Shift595Bus<Spi, Pin> {
+        Shift595Bus::new(
+            spi.clone(),
+            port.pin(0),
+            Shift595Mapping::adafruit_backpack(),
+        )
+    }
+
+    #[test]
+    fn preset_is_valid() {
+        let mapping = Shift595Mapping::adafruit_backpack();
+        assert!(check_pin_mapping::<()>(&mapping.pins(), &[], 8).is_ok());
+    }
+
+    #[test]
+    fn custom_mapping_is_checked() {
+        assert!(Shift595Mapping::new::<()>(0, 1, 2, [3, 4, 5, 6], false).is_ok());
+        assert!(matches!(
+            Shift595Mapping::new::<()>(0, 1, 9, [3, 4, 5, 6], false),
+            Err(DataBusError::InvalidPinMapping)
+        ));
+    }
+
+    #[test]
+    fn each_byte_is_shifted_with_latch_low_then_latched() {
+        let port = Port::default();
+        let spi = Spi::on(&port);
+        let mut bus = bus(&port, &spi);
+
+        bus.write_byte(0xA5, TransactionType::Data, &mut Delay::default())
+            .unwrap();
+
+        // Backlight on Q7 and RS on Q1 with each nibble set up before E on Q2 is pulsed
+        assert_eq!(
+            spi.transfers(),
+            [
+                (0, vec![0xAA]),
+                (0, vec![0xAE]),
+                (0, vec![0xAA]),
+                (0, vec![0xD2]),
+                (0, vec![0xD6]),
+                (0, vec![0xD2]),
+            ]
+        );
+        // The latch rises after every byte, moving it to the outputs
+        assert_eq!(port.latched(0).len(), 5);
+        assert_eq!(port.levels(), 1);
+    }
+}
@@ -0,0 +1,239 @@
+//! 4-bit data bus through a 74HC164 shift register on two pins, data and clock. The enable input of
+//! the device is a diode AND of the data line and the last register output (QH): once a byte with
+//! QH set has been shifted in, raising the data line raises enable.
+//!
+//! Each nibble is sent by clearing the register (so enable stays low while shifting), shifting in
+//! the enable guard bit, RS and the nibble, then pulsing the data line. RW must be tied low.
+
+use crate::data_bus::*;
+use crate::hal::{Delay, Eh02, OutputPin};
+use crate::timing::Timing;
+use crate::DataBusError;
+use core::marker::PhantomData;
+
+/// Register bit of the last output (QH), ANDed with the data line to form enable.
+const ENABLE_GUARD: u8 = 1 << 7;
+
+/// Assignment of the register outputs (QA-QH as bits 0-7, shifted out most significant bit first)
+/// to the display pins. QH is the enable guard and QA must be left unused, as its bit is what the
+/// data line rests at once the byte has been shifted in.
+#[derive(Copy, Clone, Debug)]
+pub struct TwoWireMapping {
+    rs: u8,
+    data: [u8; 4],
+}
+
+impl TwoWireMapping {
+    /// Custom mapping. `data` holds the register outputs of D4-D7, in that order. Returns
+    /// `InvalidPinMapping` if a pin is assigned twice or is not one of QB-QG (1-6).
+    pub fn new<E>(rs: u8, data: [u8; 4]) -> Result<Self, DataBusError<E>> {
+        let mapping = TwoWireMapping { rs, data };
+        check_pin_mapping(&mapping.pins(), &[], 8)?;
+        Ok(mapping)
+    }
+
+    /// Wiring of the common two-wire LiquidCrystal circuit: RS=QC, D4-D7=QD-QG.
+    pub const fn common() -> Self {
+        TwoWireMapping {
+            rs: 2,
+            data: [3, 4, 5, 6],
+        }
+    }
+
+    /// Every register output used by the mapping, including QA and QH so no display pin can be
+    /// assigned to them.
+    fn pins(&self) -> [u8; 7] {
+        let [d4, d5, d6, d7] = self.data;
+        [self.rs, d4, d5, d6, d7, 0, 7]
+    }
+
+    /// Register byte driving the lower nibble of `nibble` onto D4-D7.
+    fn data_bits(&self, nibble: u8) -> u8 {
+        let mut bits = 0;
+        for (i, pin) in self.data.iter().enumerate() {
+            if nibble & (1 << i) != 0 {
+                bits |= 1 << pin;
+            }
+        }
+        bits
+    }
+}
+
+impl Default for TwoWireMapping {
+    fn default() -> Self {
+        TwoWireMapping::common()
+    }
+}
+
+/// 4-bit data bus through a 74HC164 shift register with diode AND enable.
+pub struct TwoWireBus<DATA: OutputPin<H>, CLK: OutputPin<H>, H = Eh02> {
+    data: DATA,
+    clk: CLK,
+    mapping: TwoWireMapping,
+    timing: Timing,
+    _hal: PhantomData<H>,
+}
+
+impl<DATA, CLK, H> TwoWireBus<DATA, CLK, H>
+where
+    DATA: OutputPin<H>,
+    CLK: OutputPin<H, Error = DATA::Error>,
+{
+    /// Create a new instance of a two-wire bus from the data and clock pins of the register.
+    pub fn from_pins(data: DATA, clk: CLK, mapping: TwoWireMapping) -> TwoWireBus<DATA, CLK, H> {
+        TwoWireBus {
+            data,
+            clk,
+            mapping,
+            timing: Default::default(),
+            _hal: PhantomData,
+        }
+    }
+
+    /// Destroy the bus and return the pins.
+    pub fn release(self) -> (DATA, CLK) {
+        (self.data, self.clk)
+    }
+
+    /// Shift a byte into the register, most significant bit first. Leaves the data line low.
+    fn shift_out(&mut self, bits: u8) -> Result<(), DataBusError<DATA::Error>> {
+        for i in (0..8).rev() {
+            if bits & (1 << i) != 0 {
+                self.data.set_high().map_err(DataBusError::PinSetError)?;
+            } else {
+                self.data.set_low().map_err(DataBusError::PinSetError)?;
+            }
+            self.clk.set_high().map_err(DataBusError::PinSetError)?;
+            self.clk.set_low().map_err(DataBusError::PinSetError)?;
+        }
+        self.data.set_low().map_err(DataBusError::PinSetError)
+    }
+
+    /// Send a nibble, latched into the device by pulsing the data line.
+    fn send_nibble<D: Delay<H>>(
+        &mut self,
+        nibble: u8,
+        transaction: &TransactionType,
+        delay: &mut D,
+    ) -> Result<(), DataBusError<DATA::Error>> {
+        let mut bits = ENABLE_GUARD | self.mapping.data_bits(nibble);
+        if let TransactionType::Data = transaction {
+            bits |= 1 << self.mapping.rs;
+        }
+
+        // Clear the register first so the enable guard is low while shifting
+        self.shift_out(0)?;
+        self.shift_out(bits)?;
+
+        delay.delay_us(self.timing.address_setup_us);
+        self.data.set_high().map_err(DataBusError::PinSetError)?;
+        delay.delay_us(self.timing.enable_pulse_us);
+        self.data.set_low().map_err(DataBusError::PinSetError)
+    }
+}
+
+impl<DATA, CLK, H> DataBus for TwoWireBus<DATA, CLK, H>
+where
+    DATA: OutputPin<H>,
+    CLK: OutputPin<H, Error = DATA::Error>,
+{
+    type Error = DataBusError<DATA::Error>;
+    type Hal = H;
+
+    fn timing(&self) -> &Timing {
+        &self.timing
+    }
+
+    fn set_timing(&mut self, timing: Timing) {
+        self.timing = timing;
+    }
+
+    fn write_byte<D: Delay<H>>(
+        &mut self,
+        byte: u8,
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        self.send_nibble(byte >> 4, &transaction, delay)?;
+        self.send_nibble(byte & 0x0F, &transaction, delay)
+    }
+
+    fn write_bytes<D: Delay<H>>(
+        &mut self,
+        bytes: &[u8],
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        for (i, byte) in bytes.iter().enumerate() {
+            if i != 0 {
+                delay.delay_us(self.timing.execution_us);
+            }
+            self.send_nibble(byte >> 4, &transaction, delay)?;
+            self.send_nibble(byte & 0x0F, &transaction, delay)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<DATA, CLK, H> FourBitBus for TwoWireBus<DATA, CLK, H>
+where
+    DATA: OutputPin<H>,
+    CLK: OutputPin<H, Error = DATA::Error>,
+{
+    fn write_nibble<D: Delay<H>>(
+        &mut self,
+        nibble: u8,
+        transaction: TransactionType,
+        delay: &mut D,
+    ) -> Result<(), Self::Error> {
+        self.send_nibble(nibble, &transaction, delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{Delay, Port};
+
+    extern crate std;
+    use std::vec::Vec;
+
+    #[test]
+    fn preset_is_valid() {
+        let mapping = TwoWireMapping::common();
+        assert!(check_pin_mapping::<()>(&mapping.pins(), &[], 8).is_ok());
+    }
+
+    #[test]
+    fn custom_mapping_is_checked() {
+        assert!(TwoWireMapping::new::<()>(1, [3, 4, 5, 6]).is_ok());
+        assert!(matches!(
+            TwoWireMapping::new::<()>(0, [3, 4, 5, 6]),
+            Err(DataBusError::InvalidPinMapping)
+        ));
+        assert!(matches!(
+            TwoWireMapping::new::<()>(2, [3, 4, 5, 7]),
+            Err(DataBusError::InvalidPinMapping)
+        ));
+    }
+
+    #[test]
+    fn nibble_is_shifted_after_clearing_then_enabled_by_data_line() {
+        let port = Port::default();
+        let mut bus = TwoWireBus::from_pins(port.pin(0), port.pin(1), TwoWireMapping::common());
+
+        bus.write_nibble(0x5, TransactionType::Data, &mut Delay::default())
+            .unwrap();
+
+        // Data line level at each clock: eight zeros clearing the register, then QH (enable guard),
+        // QG-QA most significant first with RS on QC, D4 on QD and D6 on QF
+        let bits: Vec<u32> = port.latched(1).iter().map(|levels| levels & 1).collect();
+        let mut expected = Vec::from([0; 8]);
+        expected.extend([1, 0, 1, 0, 1, 1, 0, 0]);
+        assert_eq!(bits, expected);
+        // Enable pulse on the data line with the clock held low
+        assert_eq!(port.latched(0).last(), Some(&0b01));
+        assert_eq!(port.levels(), 0);
+    }
+}
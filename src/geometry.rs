@@ -0,0 +1,225 @@
+//! Layout of the character cells of a display in DDRAM.
+
+use crate::instructions::NumberOfDisplayLines;
+
+/// DDRAM address of the start of the second line in two-line mode.
+const SECOND_LINE_ADDRESS: u8 = 0x40;
+
+/// DDRAM addresses of the start of each line in four-line mode.
+const FOUR_LINE_ROW_OFFSETS: [u8; 4] = [0x00, 0x20, 0x40, 0x60];
+
+/// Size of a display and the DDRAM address each of its rows starts at.
+///
+/// ```rust, ignore
+/// let geometry = Geometry::lcd_20x4();
+/// assert_eq!(geometry.address(2, 0), Some(0x14));
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Geometry {
+    columns: u8,
+    rows: u8,
+    row_offsets: [u8; 4],
+    split_at: Option<u8>,
+    four_lines: bool,
+}
+
+impl Geometry {
+    /// Custom geometry. Only the first `rows` entries of `row_offsets` are used.
+    ///
+    /// Panics if `rows` is greater than 4.
+    pub const fn new(columns: u8, rows: u8, row_offsets: [u8; 4]) -> Self {
+        assert!(rows <= 4, "at most 4 rows are supported");
+        Geometry {
+            columns,
+            rows,
+            row_offsets,
+            split_at: None,
+            four_lines: false,
+        }
+    }
+
+    /// Display of `columns` (at most 20) x 4 on a controller in four-line mode (KS0073, SSD1803A,
+    /// US2066), where each row has its own 32 address line.
+    ///
+    /// Panics if `columns` is greater than 20.
+    pub const fn four_line(columns: u8) -> Self {
+        assert!(
+            columns <= 20,
+            "at most 20 columns are supported in four-line mode"
+        );
+        Geometry {
+            columns,
+            rows: 4,
+            row_offsets: FOUR_LINE_ROW_OFFSETS,
+            split_at: None,
+            four_lines: true,
+        }
+    }
+
+    /// 8x1 display.
+    pub const fn lcd_8x1() -> Self {
+        Geometry::new(8, 1, [0x00, 0x00, 0x00, 0x00])
+    }
+
+    /// 16x1 display addressed as a single 16 character line.
+    pub const fn lcd_16x1() -> Self {
+        Geometry::new(16, 1, [0x00, 0x00, 0x00, 0x00])
+    }
+
+    /// 16x1 display wired as two 8 character lines side by side ("type 1"): columns 0-7 start at
+    /// 0x00 and columns 8-15 at 0x40. Requires two-line mode.
+    pub const fn lcd_16x1_split() -> Self {
+        Geometry {
+            columns: 16,
+            rows: 1,
+            row_offsets: [0x00, 0x00, 0x00, 0x00],
+            split_at: Some(8),
+            four_lines: false,
+        }
+    }
+
+    /// 8x2 display.
+    pub const fn lcd_8x2() -> Self {
+        Geometry::new(8, 2, [0x00, 0x40, 0x00, 0x00])
+    }
+
+    /// 16x2 display.
+    pub const fn lcd_16x2() -> Self {
+        Geometry::new(16, 2, [0x00, 0x40, 0x00, 0x00])
+    }
+
+    /// 20x2 display.
+    pub const fn lcd_20x2() -> Self {
+        Geometry::new(20, 2, [0x00, 0x40, 0x00, 0x00])
+    }
+
+    /// 24x2 display.
+    pub const fn lcd_24x2() -> Self {
+        Geometry::new(24, 2, [0x00, 0x40, 0x00, 0x00])
+    }
+
+    /// 40x2 display.
+    pub const fn lcd_40x2() -> Self {
+        Geometry::new(40, 2, [0x00, 0x40, 0x00, 0x00])
+    }
+
+    /// 16x4 display. Rows 3 and 4 continue on from the end of rows 1 and 2.
+    pub const fn lcd_16x4() -> Self {
+        Geometry::new(16, 4, [0x00, 0x40, 0x10, 0x50])
+    }
+
+    /// 20x4 display. Rows 3 and 4 continue on from the end of rows 1 and 2.
+    pub const fn lcd_20x4() -> Self {
+        Geometry::new(20, 4, [0x00, 0x40, 0x14, 0x54])
+    }
+
+    /// 40x4 display driven by two controllers, each with its own enable pin: rows 1 and 2 belong to
+    /// the first and rows 3 and 4 to the second (see `WriteOnlyDualHD44780`).
+    pub const fn lcd_40x4() -> Self {
+        Geometry::new(40, 4, [0x00, 0x40, 0x00, 0x40])
+    }
+
+    /// 12x4 display of a controller in four-line mode.
+    pub const fn lcd_12x4_four_line() -> Self {
+        Geometry::four_line(12)
+    }
+
+    /// 20x4 display of a controller in four-line mode.
+    pub const fn lcd_20x4_four_line() -> Self {
+        Geometry::four_line(20)
+    }
+
+    /// Number of columns.
+    pub fn columns(&self) -> u8 {
+        self.columns
+    }
+
+    /// Number of rows.
+    pub fn rows(&self) -> u8 {
+        self.rows
+    }
+
+    /// Number of display lines the device must be configured for to drive this geometry.
+    pub fn num_lines(&self) -> NumberOfDisplayLines {
+        if self.four_lines {
+            NumberOfDisplayLines::FourLines
+        } else if self.rows > 1 || self.split_at.is_some() {
+            NumberOfDisplayLines::TwoLines
+        } else {
+            NumberOfDisplayLines::OneLine
+        }
+    }
+
+    /// DDRAM address of the character at `row`, `col` (both zero-based), or `None` if the position
+    /// is off the panel or past the end of the address range with a custom geometry.
+    pub fn address(&self, row: u8, col: u8) -> Option<u8> {
+        if row >= self.rows || col >= self.columns {
+            return None;
+        }
+        let offset = self.row_offsets[row as usize];
+        match self.split_at {
+            Some(split) if col >= split => offset
+                .checked_add(SECOND_LINE_ADDRESS)?
+                .checked_add(col - split),
+            _ => offset.checked_add(col),
+        }
+    }
+}
+
+impl Default for Geometry {
+    fn default() -> Self {
+        Geometry::lcd_16x2()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn two_line_rows_start_at_second_line() {
+        let geometry = Geometry::lcd_16x2();
+        assert_eq!(geometry.address(0, 0), Some(0x00));
+        assert_eq!(geometry.address(1, 15), Some(0x4F));
+        assert_eq!(geometry.address(1, 16), None);
+        assert_eq!(geometry.address(2, 0), None);
+    }
+
+    #[test]
+    fn four_row_displays_continue_lines() {
+        let geometry = Geometry::lcd_20x4();
+        assert_eq!(geometry.address(2, 0), Some(0x14));
+        assert_eq!(geometry.address(3, 5), Some(0x59));
+    }
+
+    #[test]
+    fn four_line_mode_rows_start_every_32_addresses() {
+        let geometry = Geometry::lcd_20x4_four_line();
+        assert_eq!(geometry.address(1, 0), Some(0x20));
+        assert_eq!(geometry.address(3, 19), Some(0x73));
+        assert_eq!(geometry.address(3, 20), None);
+        assert!(matches!(
+            geometry.num_lines(),
+            NumberOfDisplayLines::FourLines
+        ));
+    }
+
+    #[test]
+    fn split_16x1_moves_to_second_line() {
+        let geometry = Geometry::lcd_16x1_split();
+        assert_eq!(geometry.address(0, 7), Some(0x07));
+        assert_eq!(geometry.address(0, 8), Some(0x40));
+        assert_eq!(geometry.address(0, 15), Some(0x47));
+        assert!(matches!(
+            geometry.num_lines(),
+            NumberOfDisplayLines::TwoLines
+        ));
+    }
+
+    #[test]
+    fn custom_geometry_past_address_range_is_none() {
+        let geometry = Geometry::new(200, 2, [0x00, 0x40, 0x00, 0x00]);
+        assert_eq!(geometry.address(1, 199), None);
+        assert_eq!(geometry.address(0, 199), Some(199));
+    }
+}
@@ -0,0 +1,413 @@
+//! Pin, delay, I2C and SPI traits the buses and drivers are built on, implemented for every
+//! embedded-hal 0.2 implementation and, with feature = hal-1, every embedded-hal 1.0
+//! implementation.
+//!
+//! Each trait takes the embedded-hal version as a marker type parameter (`Eh02` or `Eh1`) so both
+//! sets of implementations can coexist. The buses carry the marker as their last type parameter,
+//! inferred from the pins or peripheral they are created with, and the delay given to a driver must
+//! implement the traits of the same version.
+
+#[cfg(feature = "hal-1")]
+use core::marker::PhantomData;
+use embedded_hal as hal_02;
+#[cfg(feature = "hal-1")]
+use embedded_hal_1 as hal_1;
+
+/// Pins, delays and peripherals implementing the embedded-hal 0.2 traits.
+pub struct Eh02;
+
+/// Pins, delays and peripherals implementing the embedded-hal 1.0 traits.
+#[cfg(feature = "hal-1")]
+pub struct Eh1;
+
+/// Push-pull output pin.
+pub trait OutputPin<H> {
+    type Error;
+
+    fn set_low(&mut self) -> Result<(), Self::Error>;
+
+    fn set_high(&mut self) -> Result<(), Self::Error>;
+
+    /// Drive the pin high if `high`, low otherwise.
+    fn set_state(&mut self, high: bool) -> Result<(), Self::Error>;
+}
+
+/// Blocking delay, for at least the given time.
+pub trait Delay<H> {
+    fn delay_ms(&mut self, ms: u16);
+
+    fn delay_us(&mut self, us: u16);
+}
+
+/// I2C peripheral writing to a device at a 7-bit address.
+pub trait I2cWrite<H> {
+    type Error;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error>;
+}
+
+/// I2C peripheral also reading from a device.
+pub trait I2cRead<H>: I2cWrite<H> {
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error>;
+}
+
+/// I2C peripheral also writing to and then reading from a device in a single transaction.
+pub trait I2cWriteRead<H>: I2cWrite<H> {
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error>;
+}
+
+/// SPI peripheral writing bytes. With embedded-hal 0.2 the bus drives chip select around each
+/// write; an embedded-hal 1.0 `SpiDevice` frames each write itself.
+pub trait SpiWrite<H> {
+    type Error;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error>;
+}
+
+/// SPI peripheral also exchanging bytes, replacing the bytes sent with those received.
+pub trait SpiTransfer<H>: SpiWrite<H> {
+    fn transfer(&mut self, words: &mut [u8]) -> Result<(), Self::Error>;
+}
+
+impl<P: hal_02::digital::v2::OutputPin> OutputPin<Eh02> for P {
+    type Error = P::Error;
+
+    fn set_low(&mut self) -> Result<(), P::Error> {
+        hal_02::digital::v2::OutputPin::set_low(self)
+    }
+
+    fn set_high(&mut self) -> Result<(), P::Error> {
+        hal_02::digital::v2::OutputPin::set_high(self)
+    }
+
+    fn set_state(&mut self, high: bool) -> Result<(), P::Error> {
+        hal_02::digital::v2::OutputPin::set_state(self, high.into())
+    }
+}
+
+impl<D> Delay<Eh02> for D
+where
+    D: hal_02::blocking::delay::DelayMs<u16> + hal_02::blocking::delay::DelayUs<u16>,
+{
+    fn delay_ms(&mut self, ms: u16) {
+        hal_02::blocking::delay::DelayMs::delay_ms(self, ms)
+    }
+
+    fn delay_us(&mut self, us: u16) {
+        hal_02::blocking::delay::DelayUs::delay_us(self, us)
+    }
+}
+
+impl<I: hal_02::blocking::i2c::Write> I2cWrite<Eh02> for I {
+    type Error = I::Error;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), I::Error> {
+        hal_02::blocking::i2c::Write::write(self, address, bytes)
+    }
+}
+
+impl<I> I2cRead<Eh02> for I
+where
+    I: hal_02::blocking::i2c::Write
+        + hal_02::blocking::i2c::Read<Error = <I as hal_02::blocking::i2c::Write>::Error>,
+{
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        hal_02::blocking::i2c::Read::read(self, address, buffer)
+    }
+}
+
+impl<I> I2cWriteRead<Eh02> for I
+where
+    I: hal_02::blocking::i2c::Write
+        + hal_02::blocking::i2c::WriteRead<Error = <I as hal_02::blocking::i2c::Write>::Error>,
+{
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        hal_02::blocking::i2c::WriteRead::write_read(self, address, bytes, buffer)
+    }
+}
+
+impl<S: hal_02::blocking::spi::Write<u8>> SpiWrite<Eh02> for S {
+    type Error = S::Error;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), S::Error> {
+        hal_02::blocking::spi::Write::write(self, words)
+    }
+}
+
+impl<S> SpiTransfer<Eh02> for S
+where
+    S: hal_02::blocking::spi::Write<u8>
+        + hal_02::blocking::spi::Transfer<u8, Error = <S as hal_02::blocking::spi::Write<u8>>::Error>,
+{
+    fn transfer(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+        hal_02::blocking::spi::Transfer::transfer(self, words).map(|_| ())
+    }
+}
+
+#[cfg(feature = "hal-1")]
+impl<P: hal_1::digital::OutputPin> OutputPin<Eh1> for P {
+    type Error = P::Error;
+
+    fn set_low(&mut self) -> Result<(), P::Error> {
+        hal_1::digital::OutputPin::set_low(self)
+    }
+
+    fn set_high(&mut self) -> Result<(), P::Error> {
+        hal_1::digital::OutputPin::set_high(self)
+    }
+
+    fn set_state(&mut self, high: bool) -> Result<(), P::Error> {
+        hal_1::digital::OutputPin::set_state(self, high.into())
+    }
+}
+
+#[cfg(feature = "hal-1")]
+impl<D: hal_1::delay::DelayNs> Delay<Eh1> for D {
+    fn delay_ms(&mut self, ms: u16) {
+        hal_1::delay::DelayNs::delay_ms(self, ms.into())
+    }
+
+    fn delay_us(&mut self, us: u16) {
+        hal_1::delay::DelayNs::delay_us(self, us.into())
+    }
+}
+
+#[cfg(feature = "hal-1")]
+impl<I: hal_1::i2c::I2c> I2cWrite<Eh1> for I {
+    type Error = I::Error;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), I::Error> {
+        hal_1::i2c::I2c::write(self, address, bytes)
+    }
+}
+
+#[cfg(feature = "hal-1")]
+impl<I: hal_1::i2c::I2c> I2cRead<Eh1> for I {
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), I::Error> {
+        hal_1::i2c::I2c::read(self, address, buffer)
+    }
+}
+
+#[cfg(feature = "hal-1")]
+impl<I: hal_1::i2c::I2c> I2cWriteRead<Eh1> for I {
+    fn write_read(&mut self, address: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), I::Error> {
+        hal_1::i2c::I2c::write_read(self, address, bytes, buffer)
+    }
+}
+
+#[cfg(feature = "hal-1")]
+impl<S: hal_1::spi::SpiDevice> SpiWrite<Eh1> for S {
+    type Error = S::Error;
+
+    fn write(&mut self, words: &[u8]) -> Result<(), S::Error> {
+        hal_1::spi::SpiDevice::write(self, words)
+    }
+}
+
+#[cfg(feature = "hal-1")]
+impl<S: hal_1::spi::SpiDevice> SpiTransfer<Eh1> for S {
+    fn transfer(&mut self, words: &mut [u8]) -> Result<(), S::Error> {
+        hal_1::spi::SpiDevice::transfer_in_place(self, words)
+    }
+}
+
+/// Chip select (or 74HC595 latch) of a bus on an embedded-hal 1.0 `SpiDevice`, which asserts its
+/// own chip select around each transfer: setting it does nothing. `E` is the error type of the
+/// other pins of the bus, if any.
+#[cfg(feature = "hal-1")]
+pub struct DeviceSelect<E = core::convert::Infallible> {
+    _error: PhantomData<E>,
+}
+
+#[cfg(feature = "hal-1")]
+impl<E> DeviceSelect<E> {
+    pub fn new() -> Self {
+        DeviceSelect {
+            _error: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "hal-1")]
+impl<E> Default for DeviceSelect<E> {
+    fn default() -> Self {
+        DeviceSelect::new()
+    }
+}
+
+#[cfg(feature = "hal-1")]
+impl<E> OutputPin<Eh1> for DeviceSelect<E> {
+    type Error = E;
+
+    fn set_low(&mut self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn set_state(&mut self, _high: bool) -> Result<(), E> {
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "hal-1"))]
+mod tests {
+    use crate::config::Hd44780Config;
+    use crate::data_bus::bus8::WriteOnlyBus8;
+    use crate::data_bus::mcp23x::{ReadRegister, SpiInterface};
+    use crate::data_bus::pcf8574::Pcf8574Mapping;
+    use crate::data_bus::shift595::Shift595Mapping;
+    use crate::data_bus::{DataBus, TransactionType};
+    use crate::mock::{Delay, Hal1, I2c, Port, Spi};
+    use crate::{ReadableHd44780, WriteOnlyHD44780};
+
+    extern crate std;
+    use std::vec;
+
+    #[test]
+    fn pins_and_delays_of_either_version_drive_the_same_transfers() {
+        let port = Port::default();
+        let lcd = WriteOnlyHD44780::new_bus4(
+            port.pin(0),
+            port.pin(1),
+            port.pin(2),
+            port.pin(3),
+            port.pin(4),
+            port.pin(5),
+            Delay::default(),
+            Hd44780Config::new(),
+        )
+        .unwrap();
+        let port_1 = Port::default();
+        let lcd_1 = WriteOnlyHD44780::new_bus4(
+            Hal1(port_1.pin(0)),
+            Hal1(port_1.pin(1)),
+            Hal1(port_1.pin(2)),
+            Hal1(port_1.pin(3)),
+            Hal1(port_1.pin(4)),
+            Hal1(port_1.pin(5)),
+            Hal1(Delay::default()),
+            Hd44780Config::new(),
+        )
+        .unwrap();
+
+        assert_eq!(port_1.latched(0), port.latched(0));
+        assert_eq!((lcd_1.release().1).0.log, lcd.release().1.log);
+    }
+
+    #[test]
+    fn bus8_data_pins_are_set_by_state() {
+        let port = Port::default();
+        let mut bus = WriteOnlyBus8::from_pins(
+            Hal1(port.pin(0)),
+            Hal1(port.pin(1)),
+            Hal1(port.pin(2)),
+            Hal1(port.pin(3)),
+            Hal1(port.pin(4)),
+            Hal1(port.pin(5)),
+            Hal1(port.pin(6)),
+            Hal1(port.pin(7)),
+            Hal1(port.pin(8)),
+            Hal1(port.pin(9)),
+        );
+
+        bus.write_byte(0xA5, TransactionType::Data, &mut Hal1(Delay::default()))
+            .unwrap();
+
+        // E on bit 0, RS on bit 1 and D0-D7 on bits 2-9
+        assert_eq!(port.latched(0), [0xA5 << 2 | 0b11]);
+    }
+
+    #[test]
+    fn i2c_of_version_1_reads_through_the_expander() {
+        let i2c = I2c::default();
+        ReadableHd44780::new_pcf8574(
+            i2c.clone(),
+            0x27,
+            Pcf8574Mapping::common(),
+            Delay::default(),
+            Hd44780Config::new(),
+        )
+        .unwrap();
+        let i2c_1 = I2c::default();
+        ReadableHd44780::new_pcf8574(
+            Hal1(i2c_1.clone()),
+            0x27,
+            Pcf8574Mapping::common(),
+            Hal1(Delay::default()),
+            Hd44780Config::new(),
+        )
+        .unwrap();
+
+        assert_eq!(i2c_1.ops(), i2c.ops());
+    }
+
+    #[test]
+    fn spi_device_frames_each_transfer_like_the_chip_select_pin() {
+        let port = Port::default();
+        let spi = Spi::on(&port);
+        WriteOnlyHD44780::new_serial_spi(
+            spi.clone(),
+            port.pin(0),
+            port.pin(1),
+            Delay::default(),
+            Hd44780Config::new(),
+        )
+        .unwrap();
+        let port_1 = Port::default();
+        let spi_1 = Spi::on(&port_1);
+        WriteOnlyHD44780::new_serial_spi_device(
+            Hal1((spi_1.clone(), port_1.pin(0))),
+            Hal1(port_1.pin(1)),
+            Hal1(Delay::default()),
+            Hd44780Config::new(),
+        )
+        .unwrap();
+
+        assert_eq!(spi_1.transfers(), spi.transfers());
+        assert_eq!(port_1.levels(), port.levels());
+    }
+
+    #[test]
+    fn shift595_is_latched_by_the_device_chip_select() {
+        let port = Port::default();
+        let spi = Spi::on(&port);
+        WriteOnlyHD44780::new_shift595_device(
+            Hal1((spi.clone(), port.pin(0))),
+            Shift595Mapping::adafruit_backpack(),
+            Hal1(Delay::default()),
+            Hd44780Config::new(),
+        )
+        .unwrap();
+
+        // Every byte is shifted in with chip select low and latched as it rises
+        let transfers = spi.transfers();
+        assert!(transfers.iter().all(|(levels, _)| levels & 1 == 0));
+        assert_eq!(port.latched(0).len(), transfers.len() - 1);
+        assert_eq!(port.levels() & 1, 1);
+    }
+
+    #[test]
+    fn expander_registers_are_read_through_the_device() {
+        let port = Port::default();
+        let spi = Spi::on(&port);
+        let mut interface = SpiInterface::new_device(Hal1((spi.clone(), port.pin(0))), 3);
+        spi.respond(&[0, 0, 0x5A]);
+
+        assert_eq!(interface.read_register(0x09).unwrap(), 0x5A);
+        assert_eq!(spi.transfers(), [(0, vec![0x47, 0x09, 0x00])]);
+        assert_eq!(port.levels(), 1);
+    }
+}
@@ -9,6 +9,10 @@ const FUNCTION_SET_OPCODE: u8 = 1 << 5;
 const SET_CGRAM_ADDRESS_OPCODE: u8 = 1 << 6;
 const SET_DDRAM_ADDRESS_OPCODE: u8 = 1 << 7;
 
+const EXTENDED_FUNCTION_SET_OPCODE: u8 = 1 << 3;
+const SCROLL_ENABLE_OPCODE: u8 = 1 << 4;
+const SCROLL_QUANTITY_OPCODE: u8 = 1 << 7;
+
 #[repr(u8)]
 pub enum Opcodes {
     ClearDisplay = CLEAR_DISPLAY_OPCODE,
@@ -21,132 +25,231 @@ pub enum Opcodes {
     SetDdramAddress = SET_DDRAM_ADDRESS_OPCODE,
 }
 
+/// Opcodes of the extended instruction set (RE = 1) of KS0073 and SSD1803A-family controllers.
+#[repr(u8)]
+pub enum ExtendedOpcodes {
+    ExtendedFunctionSet = EXTENDED_FUNCTION_SET_OPCODE,
+    ScrollEnable = SCROLL_ENABLE_OPCODE,
+    ScrollQuantity = SCROLL_QUANTITY_OPCODE,
+}
+
 #[repr(u8)]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Default)]
 pub enum IncrementDecrement {
+    #[default]
     Increment = 1,
     Decrement = 0,
 }
 
-impl Default for IncrementDecrement {
-    fn default() -> Self {
-        IncrementDecrement::Increment
-    }
-}
-
 #[repr(u8)]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Default)]
 pub enum AccompaniesDisplayShift {
     Shift = 1,
+    #[default]
     NoShift = 0,
 }
 
-impl Default for AccompaniesDisplayShift {
-    fn default() -> Self {
-        AccompaniesDisplayShift::NoShift
+impl From<bool> for AccompaniesDisplayShift {
+    fn from(value: bool) -> Self {
+        if value {
+            AccompaniesDisplayShift::Shift
+        } else {
+            AccompaniesDisplayShift::NoShift
+        }
     }
 }
 
 #[repr(u8)]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Default)]
 pub enum ShiftType {
+    #[default]
     DisplayShift = 1,
     CursorMove = 0,
 }
 
-impl Default for ShiftType {
-    fn default() -> Self {
-        ShiftType::DisplayShift
-    }
-}
-
 #[repr(u8)]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Default)]
 pub enum ShiftDirection {
     ShiftRight = 1,
+    #[default]
     ShiftLeft = 0,
 }
 
-impl Default for ShiftDirection {
-    fn default() -> Self {
-        ShiftDirection::ShiftLeft
-    }
-}
-
 #[repr(u8)]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Default)]
 pub enum ShowDisplay {
     On = 1,
+    #[default]
     Off = 0,
 }
 
-impl Default for ShowDisplay {
-    fn default() -> Self {
-        ShowDisplay::Off
+impl From<bool> for ShowDisplay {
+    fn from(value: bool) -> Self {
+        if value {
+            ShowDisplay::On
+        } else {
+            ShowDisplay::Off
+        }
     }
 }
 
 #[repr(u8)]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Default)]
 pub enum ShowCursor {
     On = 1,
+    #[default]
     Off = 0,
 }
 
-impl Default for ShowCursor {
-    fn default() -> Self {
-        ShowCursor::Off
+impl From<bool> for ShowCursor {
+    fn from(value: bool) -> Self {
+        if value {
+            ShowCursor::On
+        } else {
+            ShowCursor::Off
+        }
     }
 }
 
 #[repr(u8)]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Default)]
 pub enum Blink {
     On = 1,
+    #[default]
     Off = 0,
 }
 
-impl Default for Blink {
-    fn default() -> Self {
-        Blink::Off
+impl From<bool> for Blink {
+    fn from(value: bool) -> Self {
+        if value {
+            Blink::On
+        } else {
+            Blink::Off
+        }
     }
 }
 
 #[repr(u8)]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Default)]
 pub enum DataLength {
+    #[default]
     EightBits = 1,
     FourBits = 0,
 }
 
-impl Default for DataLength {
-    fn default() -> Self {
-        DataLength::EightBits
-    }
-}
-
 #[repr(u8)]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Default)]
 pub enum NumberOfDisplayLines {
     TwoLines = 1,
+    #[default]
     OneLine = 0,
-}
-
-impl Default for NumberOfDisplayLines {
-    fn default() -> Self {
-        NumberOfDisplayLines::OneLine
-    }
+    /// Four-line mode of KS0073 and SSD1803A-family controllers, with rows starting at 0x00, 0x20,
+    /// 0x40 and 0x60. Sets N like two-line mode; the controller profile enables four lines with the
+    /// NW bit of the extended function set.
+    FourLines = 2,
 }
 
 #[repr(u8)]
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Default)]
 pub enum CharacterFont {
     FiveByTen = 1,
+    #[default]
     FiveByEight = 0,
 }
 
-impl Default for CharacterFont {
-    fn default() -> Self {
-        CharacterFont::FiveByEight
-    }
+/// Instruction table selected by the IS bit of function set on ST7032-family controllers.
+#[repr(u8)]
+#[derive(Copy, Clone, Default)]
+pub enum InstructionTable {
+    #[default]
+    Normal = 0,
+    Extended = 1,
+}
+
+/// LCD bias voltage ratio of ST7032-family controllers.
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Default)]
+pub enum Bias {
+    #[default]
+    OneFifth = 0,
+    OneQuarter = 1,
+}
+
+/// Character ROM of US2066-family controllers.
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Default)]
+pub enum CharacterRom {
+    #[default]
+    A = 0,
+    B = 1,
+    C = 2,
+}
+
+/// Fade out or blinking of the whole display on US2066-family controllers.
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Default)]
+pub enum FadeMode {
+    #[default]
+    Off = 0b00,
+    FadeOut = 0b10,
+    Blink = 0b11,
+}
+
+/// Rows merged into double height lines on US2066 and SSD1803A-family controllers in four-line
+/// mode. In two-line mode the whole display is a single double height line.
+#[repr(u8)]
+#[derive(Copy, Clone, Debug)]
+pub enum DoubleHeight {
+    /// Rows 1-2 double, rows 3 and 4 normal.
+    Top = 0b00,
+    /// Row 1 normal, rows 2-3 double, row 4 normal.
+    Middle = 0b01,
+    /// Rows 1-2 and rows 3-4 double.
+    Both = 0b10,
+    /// Rows 1 and 2 normal, rows 3-4 double.
+    Bottom = 0b11,
+}
+
+/// Character width of KS0073 and SSD1803A-family controllers, selected by the FW bit of the extended
+/// function set.
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Default)]
+pub enum FontWidth {
+    #[default]
+    FiveDots = 0,
+    SixDots = 1,
+}
+
+/// LCD bias voltage ratio of SSD1803A-family controllers, selected by the BS1 and BS0 bits.
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Default)]
+pub enum BiasRatio {
+    #[default]
+    OneFifth = 0b00,
+    OneQuarter = 0b01,
+    OneSeventh = 0b10,
+    OneSixth = 0b11,
+}
+
+/// Display brightness of VFD controllers (PT6314, Noritake CU-series), selected by the lowest bits
+/// of function set.
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Default)]
+pub enum Brightness {
+    #[default]
+    Full = 0b00,
+    ThreeQuarters = 0b01,
+    Half = 0b10,
+    Quarter = 0b11,
+}
+
+/// Character font table of WS0010-family controllers, selected by the lowest bits of function set.
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Default)]
+pub enum FontTable {
+    #[default]
+    EnglishJapanese = 0b00,
+    WesternEuropean1 = 0b01,
+    EnglishRussian = 0b10,
+    WesternEuropean2 = 0b11,
 }
@@ -1,44 +1,86 @@
 //! Driver for a HD44780-compatible LCD.
 //!
-//! Built upon embedded-hal pins to provide generic support for any device.
-//! Supports (will support) both embedded-hal v0.2.5 and embedded-hal-alpha v1.0.4alpha (select
-//! feature = hal-alpha to activate).
+//! Built upon embedded-hal pins to provide generic support for any device. Pins, delays and I2C/SPI
+//! peripherals implementing embedded-hal 0.2 are supported and, with feature = hal-1, those
+//! implementing embedded-hal 1.0 (`OutputPin`, `DelayNs`, `I2c`, `SpiDevice`) as well. See the
+//! [`hal`] module for how the two are told apart.
 //!
-//! Supports both full feature device control and communication and simplified model with write only
-//! capability (select feature = write-only). This API is (most likely anyway) less power expensive
-//! as pin direction changes are reduced, instead blocking to provide sufficient time for operations
-//! to occur. Write-only is more modular as well, requiring only Output pins to be provided, whereas
-//! full-feature requires custom IoPin trait to be implemented, allowing the data pins to be both
-//! read from and written to, required to poll the device busy flag and addresses. When/if
-//! embedded-hal provides an official IoPin trait, this will be used instead. An implementation of
-//! the IoPin trait is provided for the stm32f3xx series of devices (config feature = stm32f3xx-io).
+//! An async write only driver is available on embedded-hal-async (select feature = async, which
+//! implies hal-1). `AsyncWriteOnlyHD44780` implements `AsyncDriver` over the 4 and 8-bit buses with
+//! embedded-hal 1.0 output pins, the PCF8574 and 74HC595 backpacks and the native serial
+//! interfaces, awaiting `DelayNs` and the I2C or SPI transfers instead of blocking. Over SPI the
+//! `SpiDevice` frames each transfer with its own chip select, which also latches the 74HC595.
+//!
+//! Supports both full feature device control and communication (`ReadableHd44780`) and a
+//! simplified model with write only capability (`WriteOnlyHD44780`). The write-only model is
+//! (most likely anyway) less power expensive as pin direction changes are reduced, instead blocking
+//! to provide sufficient time for operations to occur. Write-only is more modular as well, requiring
+//! only Output pins to be provided, whereas full-feature requires the IoPin trait to be implemented
+//! by the user for their device's pins, allowing the data pins to be both read from and written to,
+//! required to poll the device busy flag and addresses. When/if embedded-hal provides an official
+//! IoPin trait, this will be used instead.
 
 // todo! examples
-// todo! non-blocking mode
 // todo! remove driver trait and directly implement instead
-// todo! features for embedded-hal-alpha, read-write/write-only modes
-// todo! improve error handling/propagation
 
 #![no_std]
+// Buses and drivers take each pin individually, by design
+#![allow(clippy::too_many_arguments, clippy::type_complexity)]
 
 pub mod commands;
-mod data_bus;
+pub mod config;
+pub mod controller;
+pub mod data_bus;
+pub mod geometry;
+pub mod hal;
 pub mod instructions;
-mod state;
+#[cfg(test)]
+mod mock;
+pub mod state;
+pub mod timing;
 
 use crate::commands::*;
-use crate::data_bus::bus8::WriteOnlyBus8;
+use crate::config::Hd44780Config;
+use crate::data_bus::bus4::WriteOnlyBus4;
+use crate::data_bus::bus8::{ReadWriteBus8, WriteOnlyBus8};
+use crate::data_bus::dual::WriteOnlyDualBus4;
+use crate::data_bus::mcp23008::{Mcp23008Bus, Mcp23008Mapping};
+use crate::data_bus::mcp23017::{Buttons, Color, Mcp23017Bus, Mcp23017Mapping};
+use crate::data_bus::mcp23x::{ReadRegister, WriteRegister};
+use crate::data_bus::pcf8574::{Pcf8574Bus, Pcf8574Mapping};
+use crate::data_bus::pins::IoPin;
+use crate::data_bus::serial::{I2cSpeed, SerialI2cBus, SerialSpiBus};
+use crate::data_bus::shared::{SharedBus8, SharedPins8};
+use crate::data_bus::shift595::{Shift595Bus, Shift595Mapping};
+use crate::data_bus::two_wire::{TwoWireBus, TwoWireMapping};
 use crate::data_bus::*;
+use crate::geometry::Geometry;
+use crate::hal::{Delay, I2cRead, I2cWrite, OutputPin, SpiWrite};
+#[cfg(feature = "hal-1")]
+use crate::hal::{DeviceSelect, Eh1};
 use crate::instructions::*;
 use crate::state::*;
-use embedded_hal::blocking::delay::{DelayMs, DelayUs};
-use embedded_hal::digital::v2::OutputPin;
+use crate::timing::Timing;
+use core::cell::RefCell;
+#[cfg(feature = "hal-1")]
+use core::convert::Infallible;
+use core::fmt;
+#[cfg(feature = "async")]
+use embedded_hal_1 as hal_1;
+#[cfg(feature = "async")]
+use embedded_hal_async::{delay::DelayNs, i2c::I2c as AsyncI2c, spi::SpiDevice as AsyncSpiDevice};
 
-/// Errors
+/// Errors, generic over the error type `E` of the underlying pins or bus.
 #[derive(Debug)]
-pub enum DataBusError {
+pub enum DataBusError<E> {
     // Error setting a pin high or low
-    PinSetError,
+    PinSetError(E),
+
+    // Error reading a pin or changing its direction
+    PinReadError(E),
+
+    // Error communicating over the interface bus (I2C/SPI), e.g. an I/O expander not acknowledging
+    BusError(E),
 
     // Issue with blocking
     DelayError,
@@ -48,63 +90,81 @@ pub enum DataBusError {
 
     // RAM address out of range
     AddressOutOfRange,
+
+    // Pin mapping of an expander or shift register uses a pin twice or one that does not exist
+    InvalidPinMapping,
 }
 
-/// Fully supported with bidirectional pins
+/// Interval between polls of the busy flag, in microseconds.
+const BUSY_POLL_INTERVAL_US: u16 = 10;
+
+/// Default time to wait for the busy flag to clear before giving up, in microseconds.
+const DEFAULT_BUSY_TIMEOUT_US: u32 = 10_000;
+
+/// Fully supported with bidirectional pins. Instead of blocking for a fixed period after each
+/// instruction, the busy flag is polled until the device is ready to accept the next one.
 pub struct ReadableHd44780<B, D>
 where
     B: DataBus + ReadableDataBus,
-    D: DelayMs<u16> + DelayUs<u16>,
+    D: Delay<B::Hal>,
 {
     bus: B,
     delay: D,
     entry_mode: EntryMode,
-    display_mode: DisplayControl,
+    display_control: DisplayControl,
+    display_shift: DisplayShift,
+    function_set: FunctionSet,
+    geometry: Geometry,
+    address_counter: AddressCounter,
+    busy_timeout_us: u32,
 }
 
 impl<B, D> ReadableHd44780<B, D>
 where
     B: DataBus + ReadableDataBus,
-    D: DelayMs<u16> + DelayUs<u16>,
+    D: Delay<B::Hal>,
 {
-    pub fn from_pins() {}
-
-    pub fn release() {}
+    /// Wrap an initialized bus with the configured state, before any instructions are sent.
+    fn from_bus(mut bus: B, delay: D, config: &Hd44780Config, data_length: DataLength) -> Self {
+        bus.set_timing(config.timing);
+        ReadableHd44780 {
+            bus,
+            delay,
+            entry_mode: config.entry_mode,
+            display_control: config.display_control,
+            display_shift: Default::default(),
+            function_set: FunctionSet {
+                data_length,
+                ..config.function_set
+            },
+            geometry: config.geometry,
+            address_counter: Default::default(),
+            busy_timeout_us: DEFAULT_BUSY_TIMEOUT_US,
+        }
+    }
 }
 
-/// Write only commands, no IO pins required.
-pub struct WriteOnlyHD44780<B, D>
+impl<EN, RS, RW, D0, D1, D2, D3, D4, D5, D6, D7, H, D>
+    ReadableHd44780<ReadWriteBus8<EN, RS, RW, D0, D1, D2, D3, D4, D5, D6, D7, H>, D>
 where
-    B: DataBus,
-    D: DelayMs<u16> + DelayUs<u16>,
-{
-    bus: B,
-    delay: D,
-    entry_mode: EntryMode,
-    display_control: DisplayControl,
-    display_shift: DisplayShift,
-    function_set: FunctionSet,
-}
-
-impl<EN, RS, D0, D1, D2, D3, D4, D5, D6, D7, D>
-    WriteOnlyHD44780<WriteOnlyBus8<EN, RS, D0, D1, D2, D3, D4, D5, D6, D7>, D>
-where
-    EN: OutputPin,
-    RS: OutputPin,
-    D0: OutputPin,
-    D1: OutputPin,
-    D2: OutputPin,
-    D3: OutputPin,
-    D4: OutputPin,
-    D5: OutputPin,
-    D6: OutputPin,
-    D7: OutputPin,
-    D: DelayMs<u16> + DelayUs<u16>,
+    EN: OutputPin<H>,
+    RS: OutputPin<H, Error = EN::Error>,
+    RW: OutputPin<H, Error = EN::Error>,
+    D0: IoPin<Error = EN::Error>,
+    D1: IoPin<Error = EN::Error>,
+    D2: IoPin<Error = EN::Error>,
+    D3: IoPin<Error = EN::Error>,
+    D4: IoPin<Error = EN::Error>,
+    D5: IoPin<Error = EN::Error>,
+    D6: IoPin<Error = EN::Error>,
+    D7: IoPin<Error = EN::Error>,
+    D: Delay<H>,
 {
-    /// Create an instance of a write-only capable HD44780 with an 8-bit wide data bus.
+    /// Create an instance of a fully featured HD44780 with an 8-bit wide bidirectional data bus.
     pub fn new_bus8(
         en: EN,
         rs: RS,
+        rw: RW,
         d0: D0,
         d1: D1,
         d2: D2,
@@ -114,63 +174,170 @@ where
         d6: D6,
         d7: D7,
         delay: D,
-    ) -> WriteOnlyHD44780<WriteOnlyBus8<EN, RS, D0, D1, D2, D3, D4, D5, D6, D7>, D> {
-        let mut lcd = WriteOnlyHD44780 {
-            bus: WriteOnlyBus8::from_pins(en, rs, d0, d1, d2, d3, d4, d5, d6, d7),
-            delay,
-            entry_mode: Default::default(),
-            display_control: Default::default(),
-            display_shift: Default::default(),
-            function_set: Default::default(),
-        };
+        config: Hd44780Config,
+    ) -> Result<
+        ReadableHd44780<ReadWriteBus8<EN, RS, RW, D0, D1, D2, D3, D4, D5, D6, D7, H>, D>,
+        DataBusError<EN::Error>,
+    > {
+        let bus = ReadWriteBus8::from_pins(en, rs, rw, d0, d1, d2, d3, d4, d5, d6, d7);
+        let mut lcd = ReadableHd44780::from_bus(bus, delay, &config, DataLength::EightBits);
 
-        lcd.delay.delay_ms(15);
-        lcd.function_set(
-            lcd.function_set.data_length,
-            lcd.function_set.num_lines,
-            lcd.function_set.char_font,
-        );
-        lcd.delay.delay_ms(100);
-        lcd.set_entry_mode(
-            lcd.entry_mode.cursor_direction,
-            lcd.entry_mode.display_shift,
-        );
-        lcd.delay.delay_ms(100);
-        lcd.set_display_control(ShowDisplay::On, ShowCursor::On, Blink::On);
-        lcd.delay.delay_ms(5);
+        // Busy flag cannot be checked until after the first function set
+        lcd.delay.delay_ms(config.timing.power_on_ms);
+        lcd.init(config.clear_on_init)?;
 
-        lcd
+        Ok(lcd)
     }
 
     /// Destroy the HD44780 instance and return the pins and delays provided.
-    pub fn release(self) -> ((EN, RS, D0, D1, D2, D3, D4, D5, D6, D7), D) {
+    pub fn release(self) -> ((EN, RS, RW, D0, D1, D2, D3, D4, D5, D6, D7), D) {
         (self.bus.release(), self.delay)
     }
 }
 
-impl<B, D> Driver for WriteOnlyHD44780<B, D>
+impl<I2C, H, D> ReadableHd44780<Pcf8574Bus<I2C, H>, D>
 where
-    B: DataBus,
-    D: DelayMs<u16> + DelayUs<u16>,
+    I2C: I2cRead<H>,
+    D: Delay<H>,
 {
-    type Error = DataBusError;
+    /// Create an instance of a fully featured HD44780 connected through a PCF8574 I2C backpack at
+    /// `address`, with the expander pins assigned according to `mapping`. The RW pin of the device
+    /// must be connected to the expander.
+    pub fn new_pcf8574(
+        i2c: I2C,
+        address: u8,
+        mapping: Pcf8574Mapping,
+        delay: D,
+        config: Hd44780Config,
+    ) -> Result<ReadableHd44780<Pcf8574Bus<I2C, H>, D>, DataBusError<I2C::Error>> {
+        let bus = Pcf8574Bus::new(i2c, address, mapping);
+        let mut lcd = ReadableHd44780::from_bus(bus, delay, &config, DataLength::FourBits);
 
-    fn clear_display(&mut self) -> Result<(), DataBusError> {
-        self.bus.write_byte(
-            Opcodes::ClearDisplay as u8,
-            TransactionType::Instruction,
-            &mut self.delay,
-        );
-        let data = Opcodes::ClearDisplay as u8;
+        // Busy flag cannot be checked until after the first function set
+        lcd.delay.delay_ms(config.timing.power_on_ms);
+        lcd.enter_four_bit_mode()?;
+        lcd.init(config.clear_on_init)?;
+
+        Ok(lcd)
+    }
+
+    /// Turn the backlight on or off.
+    pub fn set_backlight(&mut self, on: bool) -> Result<(), DataBusError<I2C::Error>> {
+        self.bus.set_backlight(on)
+    }
+
+    /// Destroy the HD44780 instance and return the I2C peripheral and delays provided.
+    pub fn release(self) -> (I2C, D) {
+        (self.bus.release(), self.delay)
+    }
+}
+
+impl<B, D, E> ReadableHd44780<B, D>
+where
+    B: FourBitBus<Error = DataBusError<E>> + ReadableDataBus,
+    D: Delay<B::Hal>,
+{
+    /// Initialization by instruction: the device may be in either 8-bit mode or half way through a
+    /// 4-bit transfer, so force 8-bit mode three times before switching to 4-bit. The busy flag
+    /// cannot be checked until this is complete.
+    fn enter_four_bit_mode(&mut self) -> Result<(), DataBusError<E>> {
+        let timing = *self.bus.timing();
+        self.bus
+            .write_nibble(0x03, TransactionType::Instruction, &mut self.delay)?;
+        self.delay.delay_us(timing.reset_wait_us);
+        self.bus
+            .write_nibble(0x03, TransactionType::Instruction, &mut self.delay)?;
+        self.delay.delay_us(100); // at least 100 µs, fixed by the datasheet
+        self.bus
+            .write_nibble(0x03, TransactionType::Instruction, &mut self.delay)?;
+        self.delay.delay_us(timing.execution_us);
         self.bus
-            .write_byte(data, TransactionType::Instruction, &mut self.delay);
+            .write_nibble(0x02, TransactionType::Instruction, &mut self.delay)?;
+        self.delay.delay_us(timing.execution_us);
         Ok(())
     }
+}
 
-    fn return_home(&mut self) -> Result<(), DataBusError> {
-        let data = Opcodes::ReturnHome as u8;
-        self.bus
-            .write_byte(data, TransactionType::Instruction, &mut self.delay);
+impl<B, D, E> ReadableHd44780<B, D>
+where
+    B: DataBus<Error = DataBusError<E>> + ReadableDataBus,
+    D: Delay<B::Hal>,
+{
+    /// Size and row layout of the display.
+    pub fn geometry(&self) -> &Geometry {
+        &self.geometry
+    }
+
+    /// Replace the timing profile used by the bus.
+    pub fn set_timing(&mut self, timing: Timing) {
+        self.bus.set_timing(timing);
+    }
+
+    /// Apply the stored configuration once the interface data length has been established.
+    fn init(&mut self, clear: bool) -> Result<(), DataBusError<E>> {
+        let function_set = self.function_set;
+        let display_control = self.display_control;
+        let entry_mode = self.entry_mode;
+        self.function_set(
+            function_set.data_length,
+            function_set.num_lines,
+            function_set.char_font,
+        )?;
+        self.set_display_control(
+            display_control.display,
+            display_control.cursor,
+            display_control.blink,
+        )?;
+        if clear {
+            self.clear_display()?;
+        }
+        self.set_entry_mode(entry_mode.cursor_direction, entry_mode.display_shift)
+    }
+
+    /// Set the maximum time to wait for the busy flag to clear after an instruction before
+    /// returning `DataBusError::BlockTooLongError`.
+    pub fn set_busy_timeout_us(&mut self, timeout_us: u32) {
+        self.busy_timeout_us = timeout_us;
+    }
+
+    /// Block until the busy flag is cleared or the busy timeout expires.
+    fn wait_until_ready(&mut self) -> Result<(), DataBusError<E>> {
+        let mut waited_us = 0;
+        while self.bus.read_busy_flag(&mut self.delay)? {
+            if waited_us >= self.busy_timeout_us {
+                return Err(DataBusError::BlockTooLongError);
+            }
+            self.delay.delay_us(BUSY_POLL_INTERVAL_US);
+            waited_us += BUSY_POLL_INTERVAL_US as u32;
+        }
+        Ok(())
+    }
+
+    /// Write a byte to the instruction or data register and wait for it to be processed.
+    fn write(&mut self, byte: u8, transaction: TransactionType) -> Result<(), DataBusError<E>> {
+        self.bus.write_byte(byte, transaction, &mut self.delay)?;
+        self.wait_until_ready()
+    }
+}
+
+impl<B, D, E> Driver for ReadableHd44780<B, D>
+where
+    B: DataBus<Error = DataBusError<E>> + ReadableDataBus,
+    D: Delay<B::Hal>,
+{
+    type Error = DataBusError<E>;
+
+    fn clear_display(&mut self) -> Result<(), DataBusError<E>> {
+        self.write(Opcodes::ClearDisplay as u8, TransactionType::Instruction)?;
+        // Clear display also sets the cursor direction to increment
+        self.entry_mode.cursor_direction = IncrementDecrement::Increment;
+        self.address_counter = Default::default();
+        Ok(())
+    }
+
+    fn return_home(&mut self) -> Result<(), DataBusError<E>> {
+        self.write(Opcodes::ReturnHome as u8, TransactionType::Instruction)?;
+        self.address_counter = Default::default();
         Ok(())
     }
 
@@ -178,14 +345,14 @@ where
         &mut self,
         direction: IncrementDecrement,
         display_shift: AccompaniesDisplayShift,
-    ) -> Result<(), DataBusError> {
+    ) -> Result<(), DataBusError<E>> {
         let entry_mode = EntryMode {
             cursor_direction: direction,
             display_shift,
         };
         let data = Opcodes::EntryMode as u8 | entry_mode.as_byte();
-        self.bus
-            .write_byte(data, TransactionType::Instruction, &mut self.delay);
+        self.write(data, TransactionType::Instruction)?;
+        self.entry_mode = entry_mode;
         Ok(())
     }
 
@@ -194,37 +361,59 @@ where
         display_on: ShowDisplay,
         cursor_displayed: ShowCursor,
         cursor_blink: Blink,
-    ) -> Result<(), DataBusError> {
+    ) -> Result<(), DataBusError<E>> {
         let display_control = DisplayControl {
             display: display_on,
             cursor: cursor_displayed,
             blink: cursor_blink,
         };
         let data = Opcodes::DisplayControl as u8 | display_control.as_byte();
-        self.bus
-            .write_byte(data, TransactionType::Instruction, &mut self.delay);
+        self.write(data, TransactionType::Instruction)?;
+        self.display_control = display_control;
         Ok(())
     }
 
-    fn shift_cursor(&mut self, shift_direction: ShiftDirection) -> Result<(), DataBusError> {
+    fn current_entry_mode(&self) -> EntryMode {
+        self.entry_mode
+    }
+
+    fn current_display_control(&self) -> DisplayControl {
+        self.display_control
+    }
+
+    fn current_function_set(&self) -> FunctionSet {
+        self.function_set
+    }
+
+    fn cursor_address(&self) -> u8 {
+        self.address_counter.ddram_address
+    }
+
+    fn shift_cursor(&mut self, shift_direction: ShiftDirection) -> Result<(), DataBusError<E>> {
         let operands = DisplayShift {
             shift_type: ShiftType::CursorMove,
             shift_direction,
         };
         let data = Opcodes::DisplayShift as u8 | operands.as_byte();
-        self.bus
-            .write_byte(data, TransactionType::Instruction, &mut self.delay);
+        self.write(data, TransactionType::Instruction)?;
+        self.display_shift = operands;
+        let direction = match shift_direction {
+            ShiftDirection::ShiftRight => IncrementDecrement::Increment,
+            ShiftDirection::ShiftLeft => IncrementDecrement::Decrement,
+        };
+        self.address_counter
+            .step(direction, self.function_set.num_lines);
         Ok(())
     }
 
-    fn shift_display(&mut self, shift_direction: ShiftDirection) -> Result<(), DataBusError> {
+    fn shift_display(&mut self, shift_direction: ShiftDirection) -> Result<(), DataBusError<E>> {
         let operands = DisplayShift {
             shift_type: ShiftType::DisplayShift,
             shift_direction,
         };
         let data = Opcodes::DisplayShift as u8 | operands.as_byte();
-        self.bus
-            .write_byte(data, TransactionType::Instruction, &mut self.delay);
+        self.write(data, TransactionType::Instruction)?;
+        self.display_shift = operands;
         Ok(())
     }
 
@@ -233,53 +422,2429 @@ where
         data_length: DataLength,
         num_lines: NumberOfDisplayLines,
         font: CharacterFont,
-    ) -> Result<(), DataBusError> {
+    ) -> Result<(), DataBusError<E>> {
         let function_set = FunctionSet {
             data_length,
             num_lines,
             char_font: font,
         };
         let data = Opcodes::FunctionSet as u8 | function_set.as_byte();
-        self.bus
-            .write_byte(data, TransactionType::Instruction, &mut self.delay);
+        self.write(data, TransactionType::Instruction)?;
+        self.function_set = function_set;
+        // The cursor may be left at an address that no longer exists, e.g. on the second line
+        // after switching to one line mode
+        if !function_set.is_valid_ddram_address(self.address_counter.ddram_address) {
+            self.set_position(0)?;
+        }
         Ok(())
     }
 
-    fn set_cgram_address(&mut self, address: u8) -> Result<(), DataBusError> {
+    fn set_cgram_address(&mut self, address: u8) -> Result<(), DataBusError<E>> {
         if address >= Opcodes::SetCgramAddress as u8 {
             return Err(DataBusError::AddressOutOfRange);
         }
         let data = Opcodes::SetCgramAddress as u8 | address;
-        self.bus
-            .write_byte(data, TransactionType::Instruction, &mut self.delay);
+        self.write(data, TransactionType::Instruction)?;
+        self.address_counter.cgram_selected = true;
         Ok(())
     }
 
-    fn set_position(&mut self, address: u8) -> Result<(), DataBusError> {
-        // todo! proper out of bounds checking
+    fn set_position(&mut self, address: u8) -> Result<(), DataBusError<E>> {
+        if !self.function_set.is_valid_ddram_address(address) {
+            return Err(DataBusError::AddressOutOfRange);
+        }
         let data = Opcodes::SetDdramAddress as u8 | address;
-        self.bus
-            .write_byte(data, TransactionType::Instruction, &mut self.delay);
+        self.write(data, TransactionType::Instruction)?;
+        self.address_counter = AddressCounter {
+            ddram_address: address,
+            cgram_selected: false,
+        };
         Ok(())
     }
 
-    fn write_char(&mut self, data: char) -> Result<(), DataBusError> {
+    fn set_cursor(&mut self, row: u8, col: u8) -> Result<(), DataBusError<E>> {
+        let address = self
+            .geometry
+            .address(row, col)
+            .ok_or(DataBusError::AddressOutOfRange)?;
+        self.set_position(address)
+    }
+
+    fn write_char(&mut self, data: char) -> Result<(), DataBusError<E>> {
         self.write_byte(data as u8)
     }
 
-    fn write_str(&mut self, str: &str) -> Result<(), DataBusError> {
+    fn write_str(&mut self, str: &str) -> Result<(), DataBusError<E>> {
         self.write_bytes(str.as_bytes())
     }
 
-    fn write_byte(&mut self, byte: u8) -> Result<(), DataBusError> {
-        self.bus
-            .write_byte(byte, TransactionType::Data, &mut self.delay);
+    fn write_byte(&mut self, byte: u8) -> Result<(), DataBusError<E>> {
+        self.write(byte, TransactionType::Data)?;
+        self.address_counter.step(
+            self.entry_mode.cursor_direction,
+            self.function_set.num_lines,
+        );
         Ok(())
     }
 
-    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), DataBusError> {
-        self.bus
-            .write_bytes(bytes, TransactionType::Data, &mut self.delay);
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), DataBusError<E>> {
+        // Each byte must be processed before the next can be written
+        for byte in bytes {
+            self.write_byte(*byte)?;
+        }
         Ok(())
     }
 }
+
+impl<B, D, E> ReadableDriver for ReadableHd44780<B, D>
+where
+    B: DataBus<Error = DataBusError<E>> + ReadableDataBus,
+    D: Delay<B::Hal>,
+{
+    type Error = DataBusError<E>;
+
+    fn read_busy_flag_and_address_counter(&mut self) -> Result<u8, DataBusError<E>> {
+        let (busy, address) = self
+            .bus
+            .read_busy_flag_and_address_counter(&mut self.delay)?;
+        Ok(((busy as u8) << 7) | address)
+    }
+
+    fn read_data(&mut self) -> Result<u8, DataBusError<E>> {
+        let data = self.bus.read_data(&mut self.delay)?;
+        // Address counter is updated after the read
+        self.wait_until_ready()?;
+        self.address_counter.step(
+            self.entry_mode.cursor_direction,
+            self.function_set.num_lines,
+        );
+        Ok(data)
+    }
+}
+
+/// Write only commands, no IO pins required.
+pub struct WriteOnlyHD44780<B, D>
+where
+    B: DataBus,
+    D: Delay<B::Hal>,
+{
+    bus: B,
+    delay: D,
+    entry_mode: EntryMode,
+    display_control: DisplayControl,
+    display_shift: DisplayShift,
+    function_set: FunctionSet,
+    geometry: Geometry,
+    address_counter: AddressCounter,
+    // Function set bits of controllers which use the bits the HD44780 leaves unused, or give them
+    // another meaning, sent with every function set
+    function_set_extension: u8,
+    fmt_error: Option<B::Error>,
+}
+
+impl<B, D> WriteOnlyHD44780<B, D>
+where
+    B: DataBus,
+    D: Delay<B::Hal>,
+{
+    /// Wrap an initialized bus with the configured state, before any instructions are sent.
+    fn from_bus(mut bus: B, delay: D, config: &Hd44780Config, data_length: DataLength) -> Self {
+        bus.set_timing(config.timing);
+        WriteOnlyHD44780 {
+            bus,
+            delay,
+            entry_mode: config.entry_mode,
+            display_control: config.display_control,
+            display_shift: Default::default(),
+            function_set: FunctionSet {
+                data_length,
+                ..config.function_set
+            },
+            geometry: config.geometry,
+            address_counter: Default::default(),
+            function_set_extension: 0,
+            fmt_error: None,
+        }
+    }
+}
+
+impl<EN, RS, D0, D1, D2, D3, D4, D5, D6, D7, H, D>
+    WriteOnlyHD44780<WriteOnlyBus8<EN, RS, D0, D1, D2, D3, D4, D5, D6, D7, H>, D>
+where
+    EN: OutputPin<H>,
+    RS: OutputPin<H, Error = EN::Error>,
+    D0: OutputPin<H, Error = EN::Error>,
+    D1: OutputPin<H, Error = EN::Error>,
+    D2: OutputPin<H, Error = EN::Error>,
+    D3: OutputPin<H, Error = EN::Error>,
+    D4: OutputPin<H, Error = EN::Error>,
+    D5: OutputPin<H, Error = EN::Error>,
+    D6: OutputPin<H, Error = EN::Error>,
+    D7: OutputPin<H, Error = EN::Error>,
+    D: Delay<H>,
+{
+    /// Create an instance of a write-only capable HD44780 with an 8-bit wide data bus.
+    pub fn new_bus8(
+        en: EN,
+        rs: RS,
+        d0: D0,
+        d1: D1,
+        d2: D2,
+        d3: D3,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        delay: D,
+        config: Hd44780Config,
+    ) -> Result<
+        WriteOnlyHD44780<WriteOnlyBus8<EN, RS, D0, D1, D2, D3, D4, D5, D6, D7, H>, D>,
+        DataBusError<EN::Error>,
+    > {
+        let bus = WriteOnlyBus8::from_pins(en, rs, d0, d1, d2, d3, d4, d5, d6, d7);
+        let mut lcd = WriteOnlyHD44780::from_bus(bus, delay, &config, DataLength::EightBits);
+
+        lcd.delay.delay_ms(config.timing.power_on_ms);
+        lcd.init(config.clear_on_init)?;
+
+        Ok(lcd)
+    }
+
+    /// Destroy the HD44780 instance and return the pins and delays provided.
+    pub fn release(self) -> ((EN, RS, D0, D1, D2, D3, D4, D5, D6, D7), D) {
+        (self.bus.release(), self.delay)
+    }
+}
+
+impl<'a, EN, RS, D0, D1, D2, D3, D4, D5, D6, D7, H, D>
+    WriteOnlyHD44780<SharedBus8<'a, EN, RS, D0, D1, D2, D3, D4, D5, D6, D7, H>, D>
+where
+    EN: OutputPin<H>,
+    RS: OutputPin<H, Error = EN::Error>,
+    D0: OutputPin<H, Error = EN::Error>,
+    D1: OutputPin<H, Error = EN::Error>,
+    D2: OutputPin<H, Error = EN::Error>,
+    D3: OutputPin<H, Error = EN::Error>,
+    D4: OutputPin<H, Error = EN::Error>,
+    D5: OutputPin<H, Error = EN::Error>,
+    D6: OutputPin<H, Error = EN::Error>,
+    D7: OutputPin<H, Error = EN::Error>,
+    D: Delay<H>,
+{
+    /// Create an instance of a write-only capable HD44780 with an 8-bit wide data bus shared with
+    /// other displays, selected by its own enable pin `en`.
+    pub fn new_shared_bus8(
+        en: EN,
+        pins: &'a RefCell<SharedPins8<RS, D0, D1, D2, D3, D4, D5, D6, D7, H>>,
+        delay: D,
+        config: Hd44780Config,
+    ) -> Result<
+        WriteOnlyHD44780<SharedBus8<'a, EN, RS, D0, D1, D2, D3, D4, D5, D6, D7, H>, D>,
+        DataBusError<EN::Error>,
+    > {
+        let bus = SharedBus8::new(en, pins);
+        let mut lcd = WriteOnlyHD44780::from_bus(bus, delay, &config, DataLength::EightBits);
+
+        lcd.delay.delay_ms(config.timing.power_on_ms);
+        lcd.init(config.clear_on_init)?;
+
+        Ok(lcd)
+    }
+
+    /// Destroy the HD44780 instance and return the enable pin and delays provided.
+    pub fn release(self) -> (EN, D) {
+        (self.bus.release(), self.delay)
+    }
+}
+
+impl<EN, RS, D4, D5, D6, D7, H, D> WriteOnlyHD44780<WriteOnlyBus4<EN, RS, D4, D5, D6, D7, H>, D>
+where
+    EN: OutputPin<H>,
+    RS: OutputPin<H, Error = EN::Error>,
+    D4: OutputPin<H, Error = EN::Error>,
+    D5: OutputPin<H, Error = EN::Error>,
+    D6: OutputPin<H, Error = EN::Error>,
+    D7: OutputPin<H, Error = EN::Error>,
+    D: Delay<H>,
+{
+    /// Create an instance of a write-only capable HD44780 with a 4-bit wide data bus (D4-D7).
+    pub fn new_bus4(
+        en: EN,
+        rs: RS,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        delay: D,
+        config: Hd44780Config,
+    ) -> Result<
+        WriteOnlyHD44780<WriteOnlyBus4<EN, RS, D4, D5, D6, D7, H>, D>,
+        DataBusError<EN::Error>,
+    > {
+        let bus = WriteOnlyBus4::from_pins(en, rs, d4, d5, d6, d7);
+        let mut lcd = WriteOnlyHD44780::from_bus(bus, delay, &config, DataLength::FourBits);
+
+        lcd.delay.delay_ms(config.timing.power_on_ms);
+        lcd.enter_four_bit_mode()?;
+        lcd.init(config.clear_on_init)?;
+
+        Ok(lcd)
+    }
+
+    /// Destroy the HD44780 instance and return the pins and delays provided.
+    pub fn release(self) -> ((EN, RS, D4, D5, D6, D7), D) {
+        (self.bus.release(), self.delay)
+    }
+}
+
+impl<I2C, H, D> WriteOnlyHD44780<Pcf8574Bus<I2C, H>, D>
+where
+    I2C: I2cWrite<H>,
+    D: Delay<H>,
+{
+    /// Create an instance of a write-only capable HD44780 connected through a PCF8574 I2C
+    /// backpack at `address`, with the expander pins assigned according to `mapping`.
+    pub fn new_pcf8574(
+        i2c: I2C,
+        address: u8,
+        mapping: Pcf8574Mapping,
+        delay: D,
+        config: Hd44780Config,
+    ) -> Result<WriteOnlyHD44780<Pcf8574Bus<I2C, H>, D>, DataBusError<I2C::Error>> {
+        let bus = Pcf8574Bus::new(i2c, address, mapping);
+        let mut lcd = WriteOnlyHD44780::from_bus(bus, delay, &config, DataLength::FourBits);
+
+        lcd.delay.delay_ms(config.timing.power_on_ms);
+        lcd.enter_four_bit_mode()?;
+        lcd.init(config.clear_on_init)?;
+
+        Ok(lcd)
+    }
+
+    /// Turn the backlight on or off.
+    pub fn set_backlight(&mut self, on: bool) -> Result<(), DataBusError<I2C::Error>> {
+        self.bus.set_backlight(on)
+    }
+
+    /// Destroy the HD44780 instance and return the I2C peripheral and delays provided.
+    pub fn release(self) -> (I2C, D) {
+        (self.bus.release(), self.delay)
+    }
+}
+
+impl<I2C, H, D> WriteOnlyHD44780<SerialI2cBus<I2C, H>, D>
+where
+    I2C: I2cWrite<H>,
+    D: Delay<H>,
+{
+    /// Create an instance of a write-only capable controller with a native I2C interface (e.g.
+    /// ST7032i, AIP31068L) at `address`. `speed` must match the I2C bus clock: at
+    /// `I2cSpeed::Standard` strings are sent in one transaction, which a faster bus clocks in before
+    /// the controller has executed each byte.
+    pub fn new_serial_i2c(
+        i2c: I2C,
+        address: u8,
+        speed: I2cSpeed,
+        delay: D,
+        config: Hd44780Config,
+    ) -> Result<WriteOnlyHD44780<SerialI2cBus<I2C, H>, D>, DataBusError<I2C::Error>> {
+        let bus = SerialI2cBus::new(i2c, address, speed);
+        let mut lcd = WriteOnlyHD44780::from_bus(bus, delay, &config, DataLength::EightBits);
+
+        lcd.delay.delay_ms(config.timing.power_on_ms);
+        lcd.init(config.clear_on_init)?;
+
+        Ok(lcd)
+    }
+
+    /// Destroy the HD44780 instance and return the I2C peripheral and delays provided.
+    pub fn release(self) -> (I2C, D) {
+        (self.bus.release(), self.delay)
+    }
+}
+
+impl<SPI, CS, RS, H, D> WriteOnlyHD44780<SerialSpiBus<SPI, CS, RS, H>, D>
+where
+    SPI: SpiWrite<H>,
+    CS: OutputPin<H>,
+    RS: OutputPin<H, Error = CS::Error>,
+    D: Delay<H>,
+{
+    /// Create an instance of a write-only capable controller with a native 4-wire SPI interface
+    /// (e.g. ST7032, ST7036), selected by `cs` and with the register selected by `rs`.
+    pub fn new_serial_spi(
+        spi: SPI,
+        cs: CS,
+        rs: RS,
+        delay: D,
+        config: Hd44780Config,
+    ) -> Result<
+        WriteOnlyHD44780<SerialSpiBus<SPI, CS, RS, H>, D>,
+        DataBusError<SpiError<SPI::Error, CS::Error>>,
+    > {
+        let bus = SerialSpiBus::new(spi, cs, rs);
+        let mut lcd = WriteOnlyHD44780::from_bus(bus, delay, &config, DataLength::EightBits);
+
+        lcd.delay.delay_ms(config.timing.power_on_ms);
+        lcd.init(config.clear_on_init)?;
+
+        Ok(lcd)
+    }
+
+    /// Destroy the HD44780 instance and return the SPI peripheral, pins and delays provided.
+    pub fn release(self) -> ((SPI, CS, RS), D) {
+        (self.bus.release(), self.delay)
+    }
+}
+
+#[cfg(feature = "hal-1")]
+impl<SPI, RS, D> WriteOnlyHD44780<SerialSpiBus<SPI, DeviceSelect<RS::Error>, RS, Eh1>, D>
+where
+    SPI: SpiWrite<Eh1>,
+    RS: OutputPin<Eh1>,
+    D: Delay<Eh1>,
+{
+    /// Create an instance of a write-only capable controller with a native 4-wire SPI interface on
+    /// an embedded-hal 1.0 `SpiDevice`, which frames each byte with its own chip select, and with
+    /// the register selected by `rs`.
+    pub fn new_serial_spi_device(
+        spi: SPI,
+        rs: RS,
+        delay: D,
+        config: Hd44780Config,
+    ) -> Result<
+        WriteOnlyHD44780<SerialSpiBus<SPI, DeviceSelect<RS::Error>, RS, Eh1>, D>,
+        DataBusError<SpiError<SPI::Error, RS::Error>>,
+    > {
+        WriteOnlyHD44780::new_serial_spi(spi, DeviceSelect::new(), rs, delay, config)
+    }
+}
+
+impl<SPI, LATCH, H, D> WriteOnlyHD44780<Shift595Bus<SPI, LATCH, H>, D>
+where
+    SPI: SpiWrite<H>,
+    LATCH: OutputPin<H>,
+    D: Delay<H>,
+{
+    /// Create an instance of a write-only capable HD44780 connected through a 74HC595 shift
+    /// register loaded over SPI, with the register outputs assigned according to `mapping`.
+    pub fn new_shift595(
+        spi: SPI,
+        latch: LATCH,
+        mapping: Shift595Mapping,
+        delay: D,
+        config: Hd44780Config,
+    ) -> Result<
+        WriteOnlyHD44780<Shift595Bus<SPI, LATCH, H>, D>,
+        DataBusError<SpiError<SPI::Error, LATCH::Error>>,
+    > {
+        let bus = Shift595Bus::new(spi, latch, mapping);
+        let mut lcd = WriteOnlyHD44780::from_bus(bus, delay, &config, DataLength::FourBits);
+
+        lcd.delay.delay_ms(config.timing.power_on_ms);
+        lcd.enter_four_bit_mode()?;
+        lcd.init(config.clear_on_init)?;
+
+        Ok(lcd)
+    }
+
+    /// Turn the backlight on or off.
+    pub fn set_backlight(
+        &mut self,
+        on: bool,
+    ) -> Result<(), DataBusError<SpiError<SPI::Error, LATCH::Error>>> {
+        self.bus.set_backlight(on)
+    }
+
+    /// Destroy the HD44780 instance and return the SPI peripheral, latch pin and delays provided.
+    pub fn release(self) -> ((SPI, LATCH), D) {
+        (self.bus.release(), self.delay)
+    }
+}
+
+#[cfg(feature = "hal-1")]
+impl<SPI, D> WriteOnlyHD44780<Shift595Bus<SPI, DeviceSelect, Eh1>, D>
+where
+    SPI: SpiWrite<Eh1>,
+    D: Delay<Eh1>,
+{
+    /// Create an instance of a write-only capable HD44780 connected through a 74HC595 shift
+    /// register on an embedded-hal 1.0 `SpiDevice`, with the chip select of the device wired to the
+    /// latch pin and the register outputs assigned according to `mapping`.
+    pub fn new_shift595_device(
+        spi: SPI,
+        mapping: Shift595Mapping,
+        delay: D,
+        config: Hd44780Config,
+    ) -> Result<
+        WriteOnlyHD44780<Shift595Bus<SPI, DeviceSelect, Eh1>, D>,
+        DataBusError<SpiError<SPI::Error, Infallible>>,
+    > {
+        WriteOnlyHD44780::new_shift595(spi, DeviceSelect::new(), mapping, delay, config)
+    }
+}
+
+impl<DATA, CLK, H, D> WriteOnlyHD44780<TwoWireBus<DATA, CLK, H>, D>
+where
+    DATA: OutputPin<H>,
+    CLK: OutputPin<H, Error = DATA::Error>,
+    D: Delay<H>,
+{
+    /// Create an instance of a write-only capable HD44780 connected through a 74HC164 shift
+    /// register driven by a data and a clock pin, with the register outputs assigned according to
+    /// `mapping`.
+    pub fn new_two_wire(
+        data: DATA,
+        clk: CLK,
+        mapping: TwoWireMapping,
+        delay: D,
+        config: Hd44780Config,
+    ) -> Result<WriteOnlyHD44780<TwoWireBus<DATA, CLK, H>, D>, DataBusError<DATA::Error>> {
+        let bus = TwoWireBus::from_pins(data, clk, mapping);
+        let mut lcd = WriteOnlyHD44780::from_bus(bus, delay, &config, DataLength::FourBits);
+
+        lcd.delay.delay_ms(config.timing.power_on_ms);
+        lcd.enter_four_bit_mode()?;
+        lcd.init(config.clear_on_init)?;
+
+        Ok(lcd)
+    }
+
+    /// Destroy the HD44780 instance and return the pins and delays provided.
+    pub fn release(self) -> ((DATA, CLK), D) {
+        (self.bus.release(), self.delay)
+    }
+}
+
+impl<IF, D> WriteOnlyHD44780<Mcp23008Bus<IF>, D>
+where
+    IF: WriteRegister,
+    D: Delay<IF::Hal>,
+{
+    /// Create an instance of a write-only capable HD44780 connected through an MCP23008 (I2C) or
+    /// MCP23S08 (SPI) expander, with the expander pins assigned according to `mapping`.
+    ///
+    /// ```rust, ignore
+    /// let interface = I2cInterface::new(i2c, MCP23X_BASE_ADDRESS);
+    /// let lcd = WriteOnlyHD44780::new_mcp23008(interface, Mcp23008Mapping::adafruit_backpack(), delay, config)?;
+    /// ```
+    pub fn new_mcp23008(
+        interface: IF,
+        mapping: Mcp23008Mapping,
+        delay: D,
+        config: Hd44780Config,
+    ) -> Result<WriteOnlyHD44780<Mcp23008Bus<IF>, D>, DataBusError<IF::Error>> {
+        let bus = Mcp23008Bus::new(interface, mapping)?;
+        let mut lcd = WriteOnlyHD44780::from_bus(bus, delay, &config, DataLength::FourBits);
+
+        lcd.delay.delay_ms(config.timing.power_on_ms);
+        lcd.enter_four_bit_mode()?;
+        lcd.init(config.clear_on_init)?;
+
+        Ok(lcd)
+    }
+
+    /// Turn the backlight on or off.
+    pub fn set_backlight(&mut self, on: bool) -> Result<(), DataBusError<IF::Error>> {
+        self.bus.set_backlight(on)
+    }
+
+    /// Destroy the HD44780 instance and return the expander interface and delays provided.
+    pub fn release(self) -> (IF, D) {
+        (self.bus.release(), self.delay)
+    }
+}
+
+impl<IF, D> WriteOnlyHD44780<Mcp23017Bus<IF>, D>
+where
+    IF: WriteRegister,
+    D: Delay<IF::Hal>,
+{
+    /// Create an instance of a write-only capable HD44780 connected through an MCP23017 (I2C) or
+    /// MCP23S17 (SPI) expander, with the expander pins assigned according to `mapping`.
+    ///
+    /// ```rust, ignore
+    /// let interface = I2cInterface::new(i2c, MCP23X_BASE_ADDRESS);
+    /// let mut lcd = WriteOnlyHD44780::new_mcp23017(interface, Mcp23017Mapping::adafruit_rgb_shield(), delay, config)?;
+    /// lcd.set_backlight_color(Color::TEAL)?;
+    /// if lcd.read_buttons()?.select { ... }
+    /// ```
+    pub fn new_mcp23017(
+        interface: IF,
+        mapping: Mcp23017Mapping,
+        delay: D,
+        config: Hd44780Config,
+    ) -> Result<WriteOnlyHD44780<Mcp23017Bus<IF>, D>, DataBusError<IF::Error>> {
+        let bus = Mcp23017Bus::new(interface, mapping)?;
+        let mut lcd = WriteOnlyHD44780::from_bus(bus, delay, &config, DataLength::FourBits);
+
+        lcd.delay.delay_ms(config.timing.power_on_ms);
+        lcd.enter_four_bit_mode()?;
+        lcd.init(config.clear_on_init)?;
+
+        Ok(lcd)
+    }
+
+    /// Light the RGB backlight in `color`.
+    pub fn set_backlight_color(&mut self, color: Color) -> Result<(), DataBusError<IF::Error>> {
+        self.bus.set_backlight_color(color)
+    }
+
+    /// Destroy the HD44780 instance and return the expander interface and delays provided.
+    pub fn release(self) -> (IF, D) {
+        (self.bus.release(), self.delay)
+    }
+}
+
+impl<IF, D> WriteOnlyHD44780<Mcp23017Bus<IF>, D>
+where
+    IF: ReadRegister,
+    D: Delay<IF::Hal>,
+{
+    /// Read the state of the buttons connected to the expander.
+    pub fn read_buttons(&mut self) -> Result<Buttons, DataBusError<IF::Error>> {
+        self.bus.read_buttons()
+    }
+}
+
+impl<B, D, E> WriteOnlyHD44780<B, D>
+where
+    B: FourBitBus<Error = DataBusError<E>>,
+    D: Delay<B::Hal>,
+{
+    /// Initialization by instruction: the device may be in either 8-bit mode or half way through a
+    /// 4-bit transfer, so force 8-bit mode three times before switching to 4-bit.
+    fn enter_four_bit_mode(&mut self) -> Result<(), DataBusError<E>> {
+        let timing = *self.bus.timing();
+        self.bus
+            .write_nibble(0x03, TransactionType::Instruction, &mut self.delay)?;
+        self.delay.delay_us(timing.reset_wait_us);
+        self.bus
+            .write_nibble(0x03, TransactionType::Instruction, &mut self.delay)?;
+        self.delay.delay_us(100); // at least 100 µs, fixed by the datasheet
+        self.bus
+            .write_nibble(0x03, TransactionType::Instruction, &mut self.delay)?;
+        self.delay.delay_us(timing.execution_us);
+        self.bus
+            .write_nibble(0x02, TransactionType::Instruction, &mut self.delay)?;
+        self.delay.delay_us(timing.execution_us);
+        Ok(())
+    }
+}
+
+impl<B, D, E> WriteOnlyHD44780<B, D>
+where
+    B: DataBus<Error = DataBusError<E>>,
+    D: Delay<B::Hal>,
+{
+    /// Size and row layout of the display.
+    pub fn geometry(&self) -> &Geometry {
+        &self.geometry
+    }
+
+    /// Replace the timing profile used by the bus and driver.
+    pub fn set_timing(&mut self, timing: Timing) {
+        self.bus.set_timing(timing);
+    }
+
+    /// Take the bus error that caused the last `fmt::Error` returned by `write!`, if any.
+    pub fn take_fmt_error(&mut self) -> Option<DataBusError<E>> {
+        self.fmt_error.take()
+    }
+
+    /// Apply the stored configuration once the interface data length has been established.
+    fn init(&mut self, clear: bool) -> Result<(), DataBusError<E>> {
+        let function_set = self.function_set;
+        let display_control = self.display_control;
+        let entry_mode = self.entry_mode;
+        self.function_set(
+            function_set.data_length,
+            function_set.num_lines,
+            function_set.char_font,
+        )?;
+        self.set_display_control(
+            display_control.display,
+            display_control.cursor,
+            display_control.blink,
+        )?;
+        if clear {
+            self.clear_display()?;
+        }
+        self.set_entry_mode(entry_mode.cursor_direction, entry_mode.display_shift)
+    }
+
+    /// Write an instruction and block until it has been executed.
+    fn write_instruction(&mut self, data: u8) -> Result<(), DataBusError<E>> {
+        self.bus
+            .write_byte(data, TransactionType::Instruction, &mut self.delay)?;
+        self.delay.delay_us(self.bus.timing().execution_us);
+        Ok(())
+    }
+
+    /// Function set operands as last sent, including the controller specific bits.
+    fn function_set_operands(&self) -> u8 {
+        self.function_set.as_byte() | self.function_set_extension
+    }
+
+    /// Send function set with controller specific `bits`, which are kept for every following
+    /// function set.
+    fn set_function_set_extension(&mut self, bits: u8) -> Result<(), DataBusError<E>> {
+        let data = Opcodes::FunctionSet as u8 | self.function_set.as_byte() | bits;
+        self.write_instruction(data)?;
+        self.function_set_extension = bits;
+        Ok(())
+    }
+}
+
+impl<B, D, E> Driver for WriteOnlyHD44780<B, D>
+where
+    B: DataBus<Error = DataBusError<E>>,
+    D: Delay<B::Hal>,
+{
+    type Error = DataBusError<E>;
+
+    fn clear_display(&mut self) -> Result<(), DataBusError<E>> {
+        let data = Opcodes::ClearDisplay as u8;
+        self.bus
+            .write_byte(data, TransactionType::Instruction, &mut self.delay)?;
+        self.delay.delay_us(self.bus.timing().clear_home_us);
+        // Clear display also sets the cursor direction to increment
+        self.entry_mode.cursor_direction = IncrementDecrement::Increment;
+        self.address_counter = Default::default();
+        Ok(())
+    }
+
+    fn return_home(&mut self) -> Result<(), DataBusError<E>> {
+        let data = Opcodes::ReturnHome as u8;
+        self.bus
+            .write_byte(data, TransactionType::Instruction, &mut self.delay)?;
+        self.delay.delay_us(self.bus.timing().clear_home_us);
+        self.address_counter = Default::default();
+        Ok(())
+    }
+
+    fn set_entry_mode(
+        &mut self,
+        direction: IncrementDecrement,
+        display_shift: AccompaniesDisplayShift,
+    ) -> Result<(), DataBusError<E>> {
+        let entry_mode = EntryMode {
+            cursor_direction: direction,
+            display_shift,
+        };
+        let data = Opcodes::EntryMode as u8 | entry_mode.as_byte();
+        self.write_instruction(data)?;
+        self.entry_mode = entry_mode;
+        Ok(())
+    }
+
+    fn set_display_control(
+        &mut self,
+        display_on: ShowDisplay,
+        cursor_displayed: ShowCursor,
+        cursor_blink: Blink,
+    ) -> Result<(), DataBusError<E>> {
+        let display_control = DisplayControl {
+            display: display_on,
+            cursor: cursor_displayed,
+            blink: cursor_blink,
+        };
+        let data = Opcodes::DisplayControl as u8 | display_control.as_byte();
+        self.write_instruction(data)?;
+        self.display_control = display_control;
+        Ok(())
+    }
+
+    fn current_entry_mode(&self) -> EntryMode {
+        self.entry_mode
+    }
+
+    fn current_display_control(&self) -> DisplayControl {
+        self.display_control
+    }
+
+    fn current_function_set(&self) -> FunctionSet {
+        self.function_set
+    }
+
+    fn cursor_address(&self) -> u8 {
+        self.address_counter.ddram_address
+    }
+
+    fn shift_cursor(&mut self, shift_direction: ShiftDirection) -> Result<(), DataBusError<E>> {
+        let operands = DisplayShift {
+            shift_type: ShiftType::CursorMove,
+            shift_direction,
+        };
+        let data = Opcodes::DisplayShift as u8 | operands.as_byte();
+        self.write_instruction(data)?;
+        self.display_shift = operands;
+        let direction = match shift_direction {
+            ShiftDirection::ShiftRight => IncrementDecrement::Increment,
+            ShiftDirection::ShiftLeft => IncrementDecrement::Decrement,
+        };
+        self.address_counter
+            .step(direction, self.function_set.num_lines);
+        Ok(())
+    }
+
+    fn shift_display(&mut self, shift_direction: ShiftDirection) -> Result<(), DataBusError<E>> {
+        let operands = DisplayShift {
+            shift_type: ShiftType::DisplayShift,
+            shift_direction,
+        };
+        let data = Opcodes::DisplayShift as u8 | operands.as_byte();
+        self.write_instruction(data)?;
+        self.display_shift = operands;
+        Ok(())
+    }
+
+    fn function_set(
+        &mut self,
+        data_length: DataLength,
+        num_lines: NumberOfDisplayLines,
+        font: CharacterFont,
+    ) -> Result<(), DataBusError<E>> {
+        let function_set = FunctionSet {
+            data_length,
+            num_lines,
+            char_font: font,
+        };
+        let data =
+            Opcodes::FunctionSet as u8 | function_set.as_byte() | self.function_set_extension;
+        self.write_instruction(data)?;
+        self.function_set = function_set;
+        // The cursor may be left at an address that no longer exists, e.g. on the second line
+        // after switching to one line mode
+        if !function_set.is_valid_ddram_address(self.address_counter.ddram_address) {
+            self.set_position(0)?;
+        }
+        Ok(())
+    }
+
+    fn set_cgram_address(&mut self, address: u8) -> Result<(), DataBusError<E>> {
+        if address >= Opcodes::SetCgramAddress as u8 {
+            return Err(DataBusError::AddressOutOfRange);
+        }
+        let data = Opcodes::SetCgramAddress as u8 | address;
+        self.write_instruction(data)?;
+        self.address_counter.cgram_selected = true;
+        Ok(())
+    }
+
+    fn set_position(&mut self, address: u8) -> Result<(), DataBusError<E>> {
+        if !self.function_set.is_valid_ddram_address(address) {
+            return Err(DataBusError::AddressOutOfRange);
+        }
+        let data = Opcodes::SetDdramAddress as u8 | address;
+        self.write_instruction(data)?;
+        self.address_counter = AddressCounter {
+            ddram_address: address,
+            cgram_selected: false,
+        };
+        Ok(())
+    }
+
+    fn set_cursor(&mut self, row: u8, col: u8) -> Result<(), DataBusError<E>> {
+        let address = self
+            .geometry
+            .address(row, col)
+            .ok_or(DataBusError::AddressOutOfRange)?;
+        self.set_position(address)
+    }
+
+    fn write_char(&mut self, data: char) -> Result<(), DataBusError<E>> {
+        self.write_byte(data as u8)
+    }
+
+    fn write_str(&mut self, str: &str) -> Result<(), DataBusError<E>> {
+        self.write_bytes(str.as_bytes())
+    }
+
+    fn write_byte(&mut self, byte: u8) -> Result<(), DataBusError<E>> {
+        self.bus
+            .write_byte(byte, TransactionType::Data, &mut self.delay)?;
+        self.delay.delay_us(self.bus.timing().execution_us);
+        self.address_counter.step(
+            self.entry_mode.cursor_direction,
+            self.function_set.num_lines,
+        );
+        Ok(())
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), DataBusError<E>> {
+        self.bus
+            .write_bytes(bytes, TransactionType::Data, &mut self.delay)?;
+        self.delay.delay_us(self.bus.timing().execution_us);
+        for _ in bytes {
+            self.address_counter.step(
+                self.entry_mode.cursor_direction,
+                self.function_set.num_lines,
+            );
+        }
+        Ok(())
+    }
+}
+
+impl<B, D, E> fmt::Write for WriteOnlyHD44780<B, D>
+where
+    B: DataBus<Error = DataBusError<E>>,
+    D: Delay<B::Hal>,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        Driver::write_str(self, s).map_err(|e| {
+            self.fmt_error = Some(e);
+            fmt::Error
+        })
+    }
+}
+
+/// Write only driver for a display driven by two HD44780s that share the data and register select
+/// pins but each have their own enable pin, such as a 40x4 display where each controller drives two
+/// of the rows. Presented as a single display: settings, clearing and display shifts apply to both
+/// controllers, while data is written to the controller the cursor was last positioned on, which is
+/// the only one showing the cursor.
+pub struct WriteOnlyDualHD44780<B, D>
+where
+    B: DualEnableBus,
+    D: Delay<B::Hal>,
+{
+    bus: B,
+    delay: D,
+    entry_mode: EntryMode,
+    display_control: DisplayControl,
+    display_shift: DisplayShift,
+    function_set: FunctionSet,
+    geometry: Geometry,
+    address_counters: [AddressCounter; 2],
+    cursor_controller: EnableSelect,
+    fmt_error: Option<B::Error>,
+}
+
+impl<B, D> WriteOnlyDualHD44780<B, D>
+where
+    B: DualEnableBus,
+    D: Delay<B::Hal>,
+{
+    /// Wrap an initialized bus with the configured state, before any instructions are sent.
+    fn from_bus(mut bus: B, delay: D, config: &Hd44780Config, data_length: DataLength) -> Self {
+        bus.set_timing(config.timing);
+        WriteOnlyDualHD44780 {
+            bus,
+            delay,
+            entry_mode: config.entry_mode,
+            display_control: config.display_control,
+            display_shift: Default::default(),
+            function_set: FunctionSet {
+                data_length,
+                ..config.function_set
+            },
+            geometry: config.geometry,
+            address_counters: Default::default(),
+            cursor_controller: EnableSelect::First,
+            fmt_error: None,
+        }
+    }
+}
+
+impl<EN1, EN2, RS, D4, D5, D6, D7, H, D>
+    WriteOnlyDualHD44780<WriteOnlyDualBus4<EN1, EN2, RS, D4, D5, D6, D7, H>, D>
+where
+    EN1: OutputPin<H>,
+    EN2: OutputPin<H, Error = EN1::Error>,
+    RS: OutputPin<H, Error = EN1::Error>,
+    D4: OutputPin<H, Error = EN1::Error>,
+    D5: OutputPin<H, Error = EN1::Error>,
+    D6: OutputPin<H, Error = EN1::Error>,
+    D7: OutputPin<H, Error = EN1::Error>,
+    D: Delay<H>,
+{
+    /// Create an instance of a write-only capable dual controller display with a shared 4-bit wide
+    /// data bus (D4-D7). `en1` enables the controller of the upper half of the rows and `en2` that
+    /// of the lower half. Use with `Geometry::lcd_40x4`.
+    pub fn new_dual_bus4(
+        en1: EN1,
+        en2: EN2,
+        rs: RS,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        delay: D,
+        config: Hd44780Config,
+    ) -> Result<
+        WriteOnlyDualHD44780<WriteOnlyDualBus4<EN1, EN2, RS, D4, D5, D6, D7, H>, D>,
+        DataBusError<EN1::Error>,
+    > {
+        let bus = WriteOnlyDualBus4::from_pins(en1, en2, rs, d4, d5, d6, d7);
+        let mut lcd = WriteOnlyDualHD44780::from_bus(bus, delay, &config, DataLength::FourBits);
+
+        lcd.delay.delay_ms(config.timing.power_on_ms);
+        lcd.enter_four_bit_mode()?;
+        lcd.init(config.clear_on_init)?;
+
+        Ok(lcd)
+    }
+
+    /// Destroy the HD44780 instance and return the pins and delays provided.
+    pub fn release(self) -> ((EN1, EN2, RS, D4, D5, D6, D7), D) {
+        (self.bus.release(), self.delay)
+    }
+}
+
+impl<B, D, E> WriteOnlyDualHD44780<B, D>
+where
+    B: FourBitBus<Error = DataBusError<E>> + DualEnableBus,
+    D: Delay<B::Hal>,
+{
+    /// Initialization by instruction of both controllers at once: either may be in 8-bit mode or
+    /// half way through a 4-bit transfer, so force 8-bit mode three times before switching to 4-bit.
+    fn enter_four_bit_mode(&mut self) -> Result<(), DataBusError<E>> {
+        let timing = *self.bus.timing();
+        self.bus.select(EnableSelect::Both);
+        self.bus
+            .write_nibble(0x03, TransactionType::Instruction, &mut self.delay)?;
+        self.delay.delay_us(timing.reset_wait_us);
+        self.bus
+            .write_nibble(0x03, TransactionType::Instruction, &mut self.delay)?;
+        self.delay.delay_us(100); // at least 100 µs, fixed by the datasheet
+        self.bus
+            .write_nibble(0x03, TransactionType::Instruction, &mut self.delay)?;
+        self.delay.delay_us(timing.execution_us);
+        self.bus
+            .write_nibble(0x02, TransactionType::Instruction, &mut self.delay)?;
+        self.delay.delay_us(timing.execution_us);
+        Ok(())
+    }
+}
+
+impl<B, D, E> WriteOnlyDualHD44780<B, D>
+where
+    B: DualEnableBus<Error = DataBusError<E>>,
+    D: Delay<B::Hal>,
+{
+    /// Size and row layout of the display.
+    pub fn geometry(&self) -> &Geometry {
+        &self.geometry
+    }
+
+    /// Replace the timing profile used by the bus and driver.
+    pub fn set_timing(&mut self, timing: Timing) {
+        self.bus.set_timing(timing);
+    }
+
+    /// Take the bus error that caused the last `fmt::Error` returned by `write!`, if any.
+    pub fn take_fmt_error(&mut self) -> Option<DataBusError<E>> {
+        self.fmt_error.take()
+    }
+
+    /// Controller the cursor is on, which data is written to.
+    pub fn cursor_controller(&self) -> EnableSelect {
+        self.cursor_controller
+    }
+
+    /// Apply the stored configuration to both controllers once the interface data length has been
+    /// established.
+    fn init(&mut self, clear: bool) -> Result<(), DataBusError<E>> {
+        let function_set = self.function_set;
+        let display_control = self.display_control;
+        let entry_mode = self.entry_mode;
+        self.function_set(
+            function_set.data_length,
+            function_set.num_lines,
+            function_set.char_font,
+        )?;
+        self.set_display_control(
+            display_control.display,
+            display_control.cursor,
+            display_control.blink,
+        )?;
+        if clear {
+            self.clear_display()?;
+        }
+        self.set_entry_mode(entry_mode.cursor_direction, entry_mode.display_shift)
+    }
+
+    /// Write an instruction to the `target` controllers and block until it has been executed.
+    fn write_instruction(&mut self, target: EnableSelect, data: u8) -> Result<(), DataBusError<E>> {
+        self.bus.select(target);
+        self.bus
+            .write_byte(data, TransactionType::Instruction, &mut self.delay)?;
+        self.delay.delay_us(self.bus.timing().execution_us);
+        Ok(())
+    }
+
+    /// Send `display_control` to the controller with the cursor and the display setting alone to
+    /// the other, so only one cursor is ever visible.
+    fn write_display_control(
+        &mut self,
+        display_control: DisplayControl,
+        cursor_controller: EnableSelect,
+    ) -> Result<(), DataBusError<E>> {
+        let other = other_controller(cursor_controller);
+        let hidden = DisplayControl {
+            display: display_control.display,
+            ..Default::default()
+        };
+        let opcode = Opcodes::DisplayControl as u8;
+        self.write_instruction(cursor_controller, opcode | display_control.as_byte())?;
+        self.write_instruction(other, opcode | hidden.as_byte())
+    }
+
+    /// Move the cursor over to `controller`, if it is not there already.
+    fn set_cursor_controller(&mut self, controller: EnableSelect) -> Result<(), DataBusError<E>> {
+        if controller != self.cursor_controller {
+            self.write_display_control(self.display_control, controller)?;
+            self.cursor_controller = controller;
+        }
+        Ok(())
+    }
+
+    /// Set the DDRAM address of `controller`.
+    fn set_position_on(
+        &mut self,
+        controller: EnableSelect,
+        address: u8,
+    ) -> Result<(), DataBusError<E>> {
+        if !self.function_set.is_valid_ddram_address(address) {
+            return Err(DataBusError::AddressOutOfRange);
+        }
+        // Data is written to both controllers while CGRAM is selected, so return the other one to
+        // DDRAM as well, at the address it was left at
+        let other = other_controller(controller);
+        let other_counter = self.address_counters[counter_index(other)];
+        if other_counter.cgram_selected {
+            let data = Opcodes::SetDdramAddress as u8 | other_counter.ddram_address;
+            self.write_instruction(other, data)?;
+            self.address_counters[counter_index(other)].cgram_selected = false;
+        }
+        let data = Opcodes::SetDdramAddress as u8 | address;
+        self.write_instruction(controller, data)?;
+        self.address_counters[counter_index(controller)] = AddressCounter {
+            ddram_address: address,
+            cgram_selected: false,
+        };
+        Ok(())
+    }
+
+    /// Controllers that data is written to: both while CGRAM is selected, so custom characters are
+    /// available on the whole display, otherwise the one with the cursor.
+    fn data_target(&self) -> EnableSelect {
+        if self.address_counters[counter_index(self.cursor_controller)].cgram_selected {
+            EnableSelect::Both
+        } else {
+            self.cursor_controller
+        }
+    }
+}
+
+/// Index of the address counter of a single controller.
+fn counter_index(controller: EnableSelect) -> usize {
+    match controller {
+        EnableSelect::Second => 1,
+        _ => 0,
+    }
+}
+
+/// The controller that is not `controller`.
+fn other_controller(controller: EnableSelect) -> EnableSelect {
+    match controller {
+        EnableSelect::Second => EnableSelect::First,
+        _ => EnableSelect::Second,
+    }
+}
+
+impl<B, D, E> Driver for WriteOnlyDualHD44780<B, D>
+where
+    B: DualEnableBus<Error = DataBusError<E>>,
+    D: Delay<B::Hal>,
+{
+    type Error = DataBusError<E>;
+
+    fn clear_display(&mut self) -> Result<(), DataBusError<E>> {
+        let data = Opcodes::ClearDisplay as u8;
+        self.bus.select(EnableSelect::Both);
+        self.bus
+            .write_byte(data, TransactionType::Instruction, &mut self.delay)?;
+        self.delay.delay_us(self.bus.timing().clear_home_us);
+        // Clear display also sets the cursor direction to increment
+        self.entry_mode.cursor_direction = IncrementDecrement::Increment;
+        self.address_counters = Default::default();
+        self.set_cursor_controller(EnableSelect::First)
+    }
+
+    fn return_home(&mut self) -> Result<(), DataBusError<E>> {
+        let data = Opcodes::ReturnHome as u8;
+        self.bus.select(EnableSelect::Both);
+        self.bus
+            .write_byte(data, TransactionType::Instruction, &mut self.delay)?;
+        self.delay.delay_us(self.bus.timing().clear_home_us);
+        self.address_counters = Default::default();
+        self.set_cursor_controller(EnableSelect::First)
+    }
+
+    fn set_entry_mode(
+        &mut self,
+        direction: IncrementDecrement,
+        display_shift: AccompaniesDisplayShift,
+    ) -> Result<(), DataBusError<E>> {
+        let entry_mode = EntryMode {
+            cursor_direction: direction,
+            display_shift,
+        };
+        let data = Opcodes::EntryMode as u8 | entry_mode.as_byte();
+        self.write_instruction(EnableSelect::Both, data)?;
+        self.entry_mode = entry_mode;
+        Ok(())
+    }
+
+    fn set_display_control(
+        &mut self,
+        display_on: ShowDisplay,
+        cursor_displayed: ShowCursor,
+        cursor_blink: Blink,
+    ) -> Result<(), DataBusError<E>> {
+        let display_control = DisplayControl {
+            display: display_on,
+            cursor: cursor_displayed,
+            blink: cursor_blink,
+        };
+        self.write_display_control(display_control, self.cursor_controller)?;
+        self.display_control = display_control;
+        Ok(())
+    }
+
+    fn current_entry_mode(&self) -> EntryMode {
+        self.entry_mode
+    }
+
+    fn current_display_control(&self) -> DisplayControl {
+        self.display_control
+    }
+
+    fn current_function_set(&self) -> FunctionSet {
+        self.function_set
+    }
+
+    fn cursor_address(&self) -> u8 {
+        self.address_counters[counter_index(self.cursor_controller)].ddram_address
+    }
+
+    fn shift_cursor(&mut self, shift_direction: ShiftDirection) -> Result<(), DataBusError<E>> {
+        let operands = DisplayShift {
+            shift_type: ShiftType::CursorMove,
+            shift_direction,
+        };
+        let data = Opcodes::DisplayShift as u8 | operands.as_byte();
+        self.write_instruction(self.cursor_controller, data)?;
+        self.display_shift = operands;
+        let direction = match shift_direction {
+            ShiftDirection::ShiftRight => IncrementDecrement::Increment,
+            ShiftDirection::ShiftLeft => IncrementDecrement::Decrement,
+        };
+        self.address_counters[counter_index(self.cursor_controller)]
+            .step(direction, self.function_set.num_lines);
+        Ok(())
+    }
+
+    fn shift_display(&mut self, shift_direction: ShiftDirection) -> Result<(), DataBusError<E>> {
+        let operands = DisplayShift {
+            shift_type: ShiftType::DisplayShift,
+            shift_direction,
+        };
+        let data = Opcodes::DisplayShift as u8 | operands.as_byte();
+        self.write_instruction(EnableSelect::Both, data)?;
+        self.display_shift = operands;
+        Ok(())
+    }
+
+    fn function_set(
+        &mut self,
+        data_length: DataLength,
+        num_lines: NumberOfDisplayLines,
+        font: CharacterFont,
+    ) -> Result<(), DataBusError<E>> {
+        let function_set = FunctionSet {
+            data_length,
+            num_lines,
+            char_font: font,
+        };
+        let data = Opcodes::FunctionSet as u8 | function_set.as_byte();
+        self.write_instruction(EnableSelect::Both, data)?;
+        self.function_set = function_set;
+        // The cursors may be left at addresses that no longer exist
+        for controller in [EnableSelect::First, EnableSelect::Second] {
+            let address = self.address_counters[counter_index(controller)].ddram_address;
+            if !function_set.is_valid_ddram_address(address) {
+                self.set_position_on(controller, 0)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn set_cgram_address(&mut self, address: u8) -> Result<(), DataBusError<E>> {
+        if address >= Opcodes::SetCgramAddress as u8 {
+            return Err(DataBusError::AddressOutOfRange);
+        }
+        let data = Opcodes::SetCgramAddress as u8 | address;
+        self.write_instruction(EnableSelect::Both, data)?;
+        for address_counter in self.address_counters.iter_mut() {
+            address_counter.cgram_selected = true;
+        }
+        Ok(())
+    }
+
+    /// Sets the DDRAM address of the controller the cursor is on. Use `set_cursor` to move to the
+    /// rows of the other controller.
+    fn set_position(&mut self, address: u8) -> Result<(), DataBusError<E>> {
+        self.set_position_on(self.cursor_controller, address)
+    }
+
+    fn set_cursor(&mut self, row: u8, col: u8) -> Result<(), DataBusError<E>> {
+        let address = self
+            .geometry
+            .address(row, col)
+            .ok_or(DataBusError::AddressOutOfRange)?;
+        let controller = if row < self.geometry.rows() / 2 {
+            EnableSelect::First
+        } else {
+            EnableSelect::Second
+        };
+        self.set_position_on(controller, address)?;
+        self.set_cursor_controller(controller)
+    }
+
+    fn write_char(&mut self, data: char) -> Result<(), DataBusError<E>> {
+        self.write_byte(data as u8)
+    }
+
+    fn write_str(&mut self, str: &str) -> Result<(), DataBusError<E>> {
+        self.write_bytes(str.as_bytes())
+    }
+
+    fn write_byte(&mut self, byte: u8) -> Result<(), DataBusError<E>> {
+        let target = self.data_target();
+        self.bus.select(target);
+        self.bus
+            .write_byte(byte, TransactionType::Data, &mut self.delay)?;
+        self.delay.delay_us(self.bus.timing().execution_us);
+        self.address_counters[counter_index(self.cursor_controller)].step(
+            self.entry_mode.cursor_direction,
+            self.function_set.num_lines,
+        );
+        Ok(())
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), DataBusError<E>> {
+        let target = self.data_target();
+        self.bus.select(target);
+        self.bus
+            .write_bytes(bytes, TransactionType::Data, &mut self.delay)?;
+        self.delay.delay_us(self.bus.timing().execution_us);
+        for _ in bytes {
+            self.address_counters[counter_index(self.cursor_controller)].step(
+                self.entry_mode.cursor_direction,
+                self.function_set.num_lines,
+            );
+        }
+        Ok(())
+    }
+}
+
+impl<B, D, E> fmt::Write for WriteOnlyDualHD44780<B, D>
+where
+    B: DualEnableBus<Error = DataBusError<E>>,
+    D: Delay<B::Hal>,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        Driver::write_str(self, s).map_err(|e| {
+            self.fmt_error = Some(e);
+            fmt::Error
+        })
+    }
+}
+
+/// Async write only driver. Commands are the same as for [`WriteOnlyHD44780`], but the delays
+/// while the device executes each instruction and the interface transfers are awaited, so other
+/// tasks run in the meantime. Select feature = async to activate.
+///
+/// ```rust, ignore
+/// let mut lcd = AsyncWriteOnlyHD44780::new_pcf8574(i2c, 0x27, mapping, delay, config).await?;
+/// lcd.write_str("Hello").await?;
+/// ```
+#[cfg(feature = "async")]
+pub struct AsyncWriteOnlyHD44780<B, D>
+where
+    B: AsyncDataBus,
+    D: DelayNs,
+{
+    bus: B,
+    delay: D,
+    entry_mode: EntryMode,
+    display_control: DisplayControl,
+    display_shift: DisplayShift,
+    function_set: FunctionSet,
+    geometry: Geometry,
+    address_counter: AddressCounter,
+}
+
+#[cfg(feature = "async")]
+impl<B, D> AsyncWriteOnlyHD44780<B, D>
+where
+    B: AsyncDataBus,
+    D: DelayNs,
+{
+    /// Wrap an initialized bus with the configured state, before any instructions are sent.
+    fn from_bus(mut bus: B, delay: D, config: &Hd44780Config, data_length: DataLength) -> Self {
+        bus.set_timing(config.timing);
+        AsyncWriteOnlyHD44780 {
+            bus,
+            delay,
+            entry_mode: config.entry_mode,
+            display_control: config.display_control,
+            display_shift: Default::default(),
+            function_set: FunctionSet {
+                data_length,
+                ..config.function_set
+            },
+            geometry: config.geometry,
+            address_counter: Default::default(),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<EN, RS, D0, D1, D2, D3, D4, D5, D6, D7, D>
+    AsyncWriteOnlyHD44780<WriteOnlyBus8<EN, RS, D0, D1, D2, D3, D4, D5, D6, D7, Eh1>, D>
+where
+    EN: hal_1::digital::OutputPin,
+    RS: hal_1::digital::OutputPin<Error = EN::Error>,
+    D0: hal_1::digital::OutputPin<Error = EN::Error>,
+    D1: hal_1::digital::OutputPin<Error = EN::Error>,
+    D2: hal_1::digital::OutputPin<Error = EN::Error>,
+    D3: hal_1::digital::OutputPin<Error = EN::Error>,
+    D4: hal_1::digital::OutputPin<Error = EN::Error>,
+    D5: hal_1::digital::OutputPin<Error = EN::Error>,
+    D6: hal_1::digital::OutputPin<Error = EN::Error>,
+    D7: hal_1::digital::OutputPin<Error = EN::Error>,
+    D: DelayNs,
+{
+    /// Create an async instance of a write-only capable HD44780 with an 8-bit wide data bus.
+    pub async fn new_bus8(
+        en: EN,
+        rs: RS,
+        d0: D0,
+        d1: D1,
+        d2: D2,
+        d3: D3,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        delay: D,
+        config: Hd44780Config,
+    ) -> Result<
+        AsyncWriteOnlyHD44780<WriteOnlyBus8<EN, RS, D0, D1, D2, D3, D4, D5, D6, D7, Eh1>, D>,
+        DataBusError<EN::Error>,
+    > {
+        let bus = WriteOnlyBus8::from_pins(en, rs, d0, d1, d2, d3, d4, d5, d6, d7);
+        let mut lcd = AsyncWriteOnlyHD44780::from_bus(bus, delay, &config, DataLength::EightBits);
+
+        lcd.delay.delay_ms(config.timing.power_on_ms.into()).await;
+        lcd.init(config.clear_on_init).await?;
+
+        Ok(lcd)
+    }
+
+    /// Destroy the HD44780 instance and return the pins and delays provided.
+    pub fn release(self) -> ((EN, RS, D0, D1, D2, D3, D4, D5, D6, D7), D) {
+        (self.bus.release(), self.delay)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<EN, RS, D4, D5, D6, D7, D> AsyncWriteOnlyHD44780<WriteOnlyBus4<EN, RS, D4, D5, D6, D7, Eh1>, D>
+where
+    EN: hal_1::digital::OutputPin,
+    RS: hal_1::digital::OutputPin<Error = EN::Error>,
+    D4: hal_1::digital::OutputPin<Error = EN::Error>,
+    D5: hal_1::digital::OutputPin<Error = EN::Error>,
+    D6: hal_1::digital::OutputPin<Error = EN::Error>,
+    D7: hal_1::digital::OutputPin<Error = EN::Error>,
+    D: DelayNs,
+{
+    /// Create an async instance of a write-only capable HD44780 with a 4-bit wide data bus
+    /// (D4-D7).
+    pub async fn new_bus4(
+        en: EN,
+        rs: RS,
+        d4: D4,
+        d5: D5,
+        d6: D6,
+        d7: D7,
+        delay: D,
+        config: Hd44780Config,
+    ) -> Result<
+        AsyncWriteOnlyHD44780<WriteOnlyBus4<EN, RS, D4, D5, D6, D7, Eh1>, D>,
+        DataBusError<EN::Error>,
+    > {
+        let bus = WriteOnlyBus4::from_pins(en, rs, d4, d5, d6, d7);
+        let mut lcd = AsyncWriteOnlyHD44780::from_bus(bus, delay, &config, DataLength::FourBits);
+
+        lcd.delay.delay_ms(config.timing.power_on_ms.into()).await;
+        lcd.enter_four_bit_mode().await?;
+        lcd.init(config.clear_on_init).await?;
+
+        Ok(lcd)
+    }
+
+    /// Destroy the HD44780 instance and return the pins and delays provided.
+    pub fn release(self) -> ((EN, RS, D4, D5, D6, D7), D) {
+        (self.bus.release(), self.delay)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<I2C, D> AsyncWriteOnlyHD44780<Pcf8574Bus<I2C, Eh1>, D>
+where
+    I2C: AsyncI2c,
+    D: DelayNs,
+{
+    /// Create an async instance of a write-only capable HD44780 connected through a PCF8574 I2C
+    /// backpack at `address`, with the expander pins assigned according to `mapping`.
+    pub async fn new_pcf8574(
+        i2c: I2C,
+        address: u8,
+        mapping: Pcf8574Mapping,
+        delay: D,
+        config: Hd44780Config,
+    ) -> Result<AsyncWriteOnlyHD44780<Pcf8574Bus<I2C, Eh1>, D>, DataBusError<I2C::Error>> {
+        let bus = Pcf8574Bus::new(i2c, address, mapping);
+        let mut lcd = AsyncWriteOnlyHD44780::from_bus(bus, delay, &config, DataLength::FourBits);
+
+        lcd.delay.delay_ms(config.timing.power_on_ms.into()).await;
+        lcd.enter_four_bit_mode().await?;
+        lcd.init(config.clear_on_init).await?;
+
+        Ok(lcd)
+    }
+
+    /// Turn the backlight on or off.
+    pub async fn set_backlight(&mut self, on: bool) -> Result<(), DataBusError<I2C::Error>> {
+        self.bus.set_backlight_async(on).await
+    }
+
+    /// Destroy the HD44780 instance and return the I2C peripheral and delays provided.
+    pub fn release(self) -> (I2C, D) {
+        (self.bus.release(), self.delay)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<I2C, D> AsyncWriteOnlyHD44780<SerialI2cBus<I2C, Eh1>, D>
+where
+    I2C: AsyncI2c,
+    D: DelayNs,
+{
+    /// Create an async instance of a write-only capable controller with a native I2C interface
+    /// (e.g. ST7032i, AIP31068L) at `address`. `speed` must match the I2C bus clock, as for
+    /// [`WriteOnlyHD44780::new_serial_i2c`].
+    pub async fn new_serial_i2c(
+        i2c: I2C,
+        address: u8,
+        speed: I2cSpeed,
+        delay: D,
+        config: Hd44780Config,
+    ) -> Result<AsyncWriteOnlyHD44780<SerialI2cBus<I2C, Eh1>, D>, DataBusError<I2C::Error>> {
+        let bus = SerialI2cBus::new(i2c, address, speed);
+        let mut lcd = AsyncWriteOnlyHD44780::from_bus(bus, delay, &config, DataLength::EightBits);
+
+        lcd.delay.delay_ms(config.timing.power_on_ms.into()).await;
+        lcd.init(config.clear_on_init).await?;
+
+        Ok(lcd)
+    }
+
+    /// Destroy the HD44780 instance and return the I2C peripheral and delays provided.
+    pub fn release(self) -> (I2C, D) {
+        (self.bus.release(), self.delay)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<SPI, RS, D> AsyncWriteOnlyHD44780<SerialSpiBus<SPI, DeviceSelect<RS::Error>, RS, Eh1>, D>
+where
+    SPI: AsyncSpiDevice,
+    RS: hal_1::digital::OutputPin,
+    D: DelayNs,
+{
+    /// Create an async instance of a write-only capable controller with a native 4-wire SPI
+    /// interface (e.g. ST7032, ST7036) on an `SpiDevice`, which frames each byte with its own chip
+    /// select, and with the register selected by `rs`.
+    pub async fn new_serial_spi_device(
+        spi: SPI,
+        rs: RS,
+        delay: D,
+        config: Hd44780Config,
+    ) -> Result<
+        AsyncWriteOnlyHD44780<SerialSpiBus<SPI, DeviceSelect<RS::Error>, RS, Eh1>, D>,
+        DataBusError<SpiError<SPI::Error, RS::Error>>,
+    > {
+        let bus = SerialSpiBus::new_device(spi, rs);
+        let mut lcd = AsyncWriteOnlyHD44780::from_bus(bus, delay, &config, DataLength::EightBits);
+
+        lcd.delay.delay_ms(config.timing.power_on_ms.into()).await;
+        lcd.init(config.clear_on_init).await?;
+
+        Ok(lcd)
+    }
+
+    /// Destroy the HD44780 instance and return the SPI device, register select pin and delays
+    /// provided.
+    pub fn release(self) -> ((SPI, RS), D) {
+        let (spi, _, rs) = self.bus.release();
+        ((spi, rs), self.delay)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<SPI, D> AsyncWriteOnlyHD44780<Shift595Bus<SPI, DeviceSelect, Eh1>, D>
+where
+    SPI: AsyncSpiDevice,
+    D: DelayNs,
+{
+    /// Create an async instance of a write-only capable HD44780 connected through a 74HC595 shift
+    /// register on an `SpiDevice`, with the chip select of the device wired to the latch pin and
+    /// the register outputs assigned according to `mapping`.
+    pub async fn new_shift595_device(
+        spi: SPI,
+        mapping: Shift595Mapping,
+        delay: D,
+        config: Hd44780Config,
+    ) -> Result<
+        AsyncWriteOnlyHD44780<Shift595Bus<SPI, DeviceSelect, Eh1>, D>,
+        DataBusError<SPI::Error>,
+    > {
+        let bus = Shift595Bus::new_device(spi, mapping);
+        let mut lcd = AsyncWriteOnlyHD44780::from_bus(bus, delay, &config, DataLength::FourBits);
+
+        lcd.delay.delay_ms(config.timing.power_on_ms.into()).await;
+        lcd.enter_four_bit_mode().await?;
+        lcd.init(config.clear_on_init).await?;
+
+        Ok(lcd)
+    }
+
+    /// Turn the backlight on or off.
+    pub async fn set_backlight(&mut self, on: bool) -> Result<(), DataBusError<SPI::Error>> {
+        self.bus.set_backlight_async(on).await
+    }
+
+    /// Destroy the HD44780 instance and return the SPI device and delays provided.
+    pub fn release(self) -> (SPI, D) {
+        let (spi, _) = self.bus.release();
+        (spi, self.delay)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<B, D, E> AsyncWriteOnlyHD44780<B, D>
+where
+    B: AsyncFourBitBus<Error = DataBusError<E>>,
+    D: DelayNs,
+{
+    /// Initialization by instruction: the device may be in either 8-bit mode or half way through a
+    /// 4-bit transfer, so force 8-bit mode three times before switching to 4-bit.
+    async fn enter_four_bit_mode(&mut self) -> Result<(), DataBusError<E>> {
+        let timing = *self.bus.timing();
+        self.bus
+            .write_nibble(0x03, TransactionType::Instruction, &mut self.delay)
+            .await?;
+        self.delay.delay_us(timing.reset_wait_us.into()).await;
+        self.bus
+            .write_nibble(0x03, TransactionType::Instruction, &mut self.delay)
+            .await?;
+        self.delay.delay_us(100).await; // at least 100 µs, fixed by the datasheet
+        self.bus
+            .write_nibble(0x03, TransactionType::Instruction, &mut self.delay)
+            .await?;
+        self.delay.delay_us(timing.execution_us.into()).await;
+        self.bus
+            .write_nibble(0x02, TransactionType::Instruction, &mut self.delay)
+            .await?;
+        self.delay.delay_us(timing.execution_us.into()).await;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<B, D, E> AsyncWriteOnlyHD44780<B, D>
+where
+    B: AsyncDataBus<Error = DataBusError<E>>,
+    D: DelayNs,
+{
+    /// Size and row layout of the display.
+    pub fn geometry(&self) -> &Geometry {
+        &self.geometry
+    }
+
+    /// Replace the timing profile used by the bus and driver.
+    pub fn set_timing(&mut self, timing: Timing) {
+        self.bus.set_timing(timing);
+    }
+
+    /// Apply the stored configuration once the interface data length has been established.
+    async fn init(&mut self, clear: bool) -> Result<(), DataBusError<E>> {
+        let function_set = self.function_set;
+        let display_control = self.display_control;
+        let entry_mode = self.entry_mode;
+        self.function_set(
+            function_set.data_length,
+            function_set.num_lines,
+            function_set.char_font,
+        )
+        .await?;
+        self.set_display_control(
+            display_control.display,
+            display_control.cursor,
+            display_control.blink,
+        )
+        .await?;
+        if clear {
+            self.clear_display().await?;
+        }
+        self.set_entry_mode(entry_mode.cursor_direction, entry_mode.display_shift)
+            .await
+    }
+
+    /// Write an instruction and wait until it has been executed.
+    async fn write_instruction(&mut self, data: u8) -> Result<(), DataBusError<E>> {
+        self.bus
+            .write_byte(data, TransactionType::Instruction, &mut self.delay)
+            .await?;
+        let execution_us = self.bus.timing().execution_us;
+        self.delay.delay_us(execution_us.into()).await;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<B, D, E> AsyncDriver for AsyncWriteOnlyHD44780<B, D>
+where
+    B: AsyncDataBus<Error = DataBusError<E>>,
+    D: DelayNs,
+{
+    type Error = DataBusError<E>;
+
+    async fn clear_display(&mut self) -> Result<(), DataBusError<E>> {
+        let data = Opcodes::ClearDisplay as u8;
+        self.bus
+            .write_byte(data, TransactionType::Instruction, &mut self.delay)
+            .await?;
+        let clear_home_us = self.bus.timing().clear_home_us;
+        self.delay.delay_us(clear_home_us.into()).await;
+        // Clear display also sets the cursor direction to increment
+        self.entry_mode.cursor_direction = IncrementDecrement::Increment;
+        self.address_counter = Default::default();
+        Ok(())
+    }
+
+    async fn return_home(&mut self) -> Result<(), DataBusError<E>> {
+        let data = Opcodes::ReturnHome as u8;
+        self.bus
+            .write_byte(data, TransactionType::Instruction, &mut self.delay)
+            .await?;
+        let clear_home_us = self.bus.timing().clear_home_us;
+        self.delay.delay_us(clear_home_us.into()).await;
+        self.address_counter = Default::default();
+        Ok(())
+    }
+
+    async fn set_entry_mode(
+        &mut self,
+        direction: IncrementDecrement,
+        display_shift: AccompaniesDisplayShift,
+    ) -> Result<(), DataBusError<E>> {
+        let entry_mode = EntryMode {
+            cursor_direction: direction,
+            display_shift,
+        };
+        let data = Opcodes::EntryMode as u8 | entry_mode.as_byte();
+        self.write_instruction(data).await?;
+        self.entry_mode = entry_mode;
+        Ok(())
+    }
+
+    async fn set_display_control(
+        &mut self,
+        display_on: ShowDisplay,
+        cursor_displayed: ShowCursor,
+        cursor_blink: Blink,
+    ) -> Result<(), DataBusError<E>> {
+        let display_control = DisplayControl {
+            display: display_on,
+            cursor: cursor_displayed,
+            blink: cursor_blink,
+        };
+        let data = Opcodes::DisplayControl as u8 | display_control.as_byte();
+        self.write_instruction(data).await?;
+        self.display_control = display_control;
+        Ok(())
+    }
+
+    fn current_entry_mode(&self) -> EntryMode {
+        self.entry_mode
+    }
+
+    fn current_display_control(&self) -> DisplayControl {
+        self.display_control
+    }
+
+    fn current_function_set(&self) -> FunctionSet {
+        self.function_set
+    }
+
+    fn cursor_address(&self) -> u8 {
+        self.address_counter.ddram_address
+    }
+
+    async fn shift_cursor(
+        &mut self,
+        shift_direction: ShiftDirection,
+    ) -> Result<(), DataBusError<E>> {
+        let operands = DisplayShift {
+            shift_type: ShiftType::CursorMove,
+            shift_direction,
+        };
+        let data = Opcodes::DisplayShift as u8 | operands.as_byte();
+        self.write_instruction(data).await?;
+        self.display_shift = operands;
+        let direction = match shift_direction {
+            ShiftDirection::ShiftRight => IncrementDecrement::Increment,
+            ShiftDirection::ShiftLeft => IncrementDecrement::Decrement,
+        };
+        self.address_counter
+            .step(direction, self.function_set.num_lines);
+        Ok(())
+    }
+
+    async fn shift_display(
+        &mut self,
+        shift_direction: ShiftDirection,
+    ) -> Result<(), DataBusError<E>> {
+        let operands = DisplayShift {
+            shift_type: ShiftType::DisplayShift,
+            shift_direction,
+        };
+        let data = Opcodes::DisplayShift as u8 | operands.as_byte();
+        self.write_instruction(data).await?;
+        self.display_shift = operands;
+        Ok(())
+    }
+
+    async fn function_set(
+        &mut self,
+        data_length: DataLength,
+        num_lines: NumberOfDisplayLines,
+        font: CharacterFont,
+    ) -> Result<(), DataBusError<E>> {
+        let function_set = FunctionSet {
+            data_length,
+            num_lines,
+            char_font: font,
+        };
+        let data = Opcodes::FunctionSet as u8 | function_set.as_byte();
+        self.write_instruction(data).await?;
+        self.function_set = function_set;
+        // The cursor may be left at an address that no longer exists, e.g. on the second line
+        // after switching to one line mode
+        if !function_set.is_valid_ddram_address(self.address_counter.ddram_address) {
+            self.set_position(0).await?;
+        }
+        Ok(())
+    }
+
+    async fn set_cgram_address(&mut self, address: u8) -> Result<(), DataBusError<E>> {
+        if address >= Opcodes::SetCgramAddress as u8 {
+            return Err(DataBusError::AddressOutOfRange);
+        }
+        let data = Opcodes::SetCgramAddress as u8 | address;
+        self.write_instruction(data).await?;
+        self.address_counter.cgram_selected = true;
+        Ok(())
+    }
+
+    async fn set_position(&mut self, address: u8) -> Result<(), DataBusError<E>> {
+        if !self.function_set.is_valid_ddram_address(address) {
+            return Err(DataBusError::AddressOutOfRange);
+        }
+        let data = Opcodes::SetDdramAddress as u8 | address;
+        self.write_instruction(data).await?;
+        self.address_counter = AddressCounter {
+            ddram_address: address,
+            cgram_selected: false,
+        };
+        Ok(())
+    }
+
+    async fn set_cursor(&mut self, row: u8, col: u8) -> Result<(), DataBusError<E>> {
+        let address = self
+            .geometry
+            .address(row, col)
+            .ok_or(DataBusError::AddressOutOfRange)?;
+        self.set_position(address).await
+    }
+
+    async fn write_char(&mut self, data: char) -> Result<(), DataBusError<E>> {
+        self.write_byte(data as u8).await
+    }
+
+    async fn write_str(&mut self, str: &str) -> Result<(), DataBusError<E>> {
+        self.write_bytes(str.as_bytes()).await
+    }
+
+    async fn write_byte(&mut self, byte: u8) -> Result<(), DataBusError<E>> {
+        self.bus
+            .write_byte(byte, TransactionType::Data, &mut self.delay)
+            .await?;
+        let execution_us = self.bus.timing().execution_us;
+        self.delay.delay_us(execution_us.into()).await;
+        self.address_counter.step(
+            self.entry_mode.cursor_direction,
+            self.function_set.num_lines,
+        );
+        Ok(())
+    }
+
+    async fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), DataBusError<E>> {
+        self.bus
+            .write_bytes(bytes, TransactionType::Data, &mut self.delay)
+            .await?;
+        let execution_us = self.bus.timing().execution_us;
+        self.delay.delay_us(execution_us.into()).await;
+        for _ in bytes {
+            self.address_counter.step(
+                self.entry_mode.cursor_direction,
+                self.function_set.num_lines,
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "async")]
+    use crate::mock::{block_on, Hal1, I2c, Spi};
+    use crate::mock::{Delay, Pin, PinFault, Port};
+
+    extern crate std;
+    use std::vec::Vec;
+
+    type Bus4 = WriteOnlyBus4<Pin, Pin, Pin, Pin, Pin, Pin>;
+
+    /// Write-only driver on a 4-bit bus with E on bit 0, RS on bit 1 and D4-D7 on bits 2-5.
+    fn bus4_lcd(port: &Port, config: Hd44780Config) -> WriteOnlyHD44780<Bus4, Delay> {
+        WriteOnlyHD44780::new_bus4(
+            port.pin(0),
+            port.pin(1),
+            port.pin(2),
+            port.pin(3),
+            port.pin(4),
+            port.pin(5),
+            Delay::default(),
+            config,
+        )
+        .unwrap()
+    }
+
+    /// Bytes latched by a 4-bit bus from nibble `from` on, as (RS, byte).
+    fn bus4_bytes(port: &Port, from: usize) -> Vec<(u32, u32)> {
+        port.latched(0)[from..]
+            .chunks(2)
+            .map(|n| (n[0] >> 1 & 1, (n[0] >> 2 & 0x0F) << 4 | n[1] >> 2 & 0x0F))
+            .collect()
+    }
+
+    #[test]
+    fn bus4_initialization_enters_four_bit_mode() {
+        let port = Port::default();
+        let lcd = WriteOnlyHD44780::new_bus4(
+            port.pin(0),
+            port.pin(1),
+            port.pin(2),
+            port.pin(3),
+            port.pin(4),
+            port.pin(5),
+            Delay::default(),
+            Hd44780Config::new(),
+        )
+        .unwrap();
+        let latched = port.latched(0);
+
+        // Three 0x3 nibbles force 8-bit mode, then 0x2 switches to 4-bit mode before the function
+        // set (DL = 0, N = 1) is sent as two nibbles
+        let nibbles: [u32; 6] = core::array::from_fn(|i| latched[i] >> 2 & 0x0F);
+        assert_eq!(nibbles, [0x3, 0x3, 0x3, 0x2, 0x2, 0x8]);
+        assert!(latched.iter().all(|levels| levels & 0b10 == 0));
+        assert!(matches!(lcd.function_set.data_length, DataLength::FourBits));
+    }
+
+    #[test]
+    fn readable_driver_polls_busy_flag_instead_of_waiting() {
+        let port = Port::default();
+        let mut lcd = ReadableHd44780::new_bus8(
+            port.pin(0),
+            port.pin(1),
+            port.pin(2),
+            port.io_pin(3),
+            port.io_pin(4),
+            port.io_pin(5),
+            port.io_pin(6),
+            port.io_pin(7),
+            port.io_pin(8),
+            port.io_pin(9),
+            port.io_pin(10),
+            Delay::default(),
+            Hd44780Config::new(),
+        )
+        .unwrap();
+        lcd.delay.log.clear();
+
+        lcd.clear_display().unwrap();
+        // Only the enable pulse and the busy flag read, no poll interval
+        assert!(!lcd.delay.log.contains(&u32::from(BUSY_POLL_INTERVAL_US)));
+
+        // D7 held high: the busy flag never clears
+        port.drive(1 << 10);
+        lcd.set_busy_timeout_us(50);
+        assert!(matches!(
+            lcd.clear_display(),
+            Err(DataBusError::BlockTooLongError)
+        ));
+        let polls = lcd
+            .delay
+            .log
+            .iter()
+            .filter(|us| **us == u32::from(BUSY_POLL_INTERVAL_US))
+            .count();
+        assert_eq!(polls, 5);
+    }
+
+    #[test]
+    fn pin_errors_are_returned_to_the_caller() {
+        let port = Port::default();
+        let result = WriteOnlyHD44780::new_bus4(
+            port.pin(0),
+            port.pin(1),
+            port.pin(2),
+            port.pin(3),
+            port.pin(4),
+            port.faulty_pin(5),
+            Delay::default(),
+            Hd44780Config::new(),
+        );
+
+        assert!(matches!(
+            result,
+            Err(DataBusError::PinSetError(PinFault(5)))
+        ));
+    }
+
+    #[test]
+    fn bus4_initialization_applies_config() {
+        let port = Port::default();
+        let config = Hd44780Config::new()
+            .num_lines(NumberOfDisplayLines::OneLine)
+            .font(CharacterFont::FiveByTen)
+            .display_control(ShowDisplay::On, ShowCursor::On, Blink::Off)
+            .entry_mode(
+                IncrementDecrement::Decrement,
+                AccompaniesDisplayShift::NoShift,
+            )
+            .clear_on_init(false);
+        WriteOnlyHD44780::new_bus4(
+            port.pin(0),
+            port.pin(1),
+            port.pin(2),
+            port.pin(3),
+            port.pin(4),
+            port.pin(5),
+            Delay::default(),
+            config,
+        )
+        .unwrap();
+
+        // Bytes sent after the four initialization nibbles: function set, display control and entry
+        // mode, without a clear display
+        let nibbles: Vec<u32> = port.latched(0)[4..]
+            .iter()
+            .map(|levels| levels >> 2 & 0x0F)
+            .collect();
+        let bytes: Vec<u32> = nibbles.chunks(2).map(|n| n[0] << 4 | n[1]).collect();
+        assert_eq!(bytes, [0x24, 0x0E, 0x04]);
+    }
+
+    #[test]
+    fn cursor_is_placed_by_row_and_column() {
+        let port = Port::default();
+        let config = Hd44780Config::new().geometry(Geometry::lcd_20x4());
+        let mut lcd = WriteOnlyHD44780::new_bus4(
+            port.pin(0),
+            port.pin(1),
+            port.pin(2),
+            port.pin(3),
+            port.pin(4),
+            port.pin(5),
+            Delay::default(),
+            config,
+        )
+        .unwrap();
+        let sent = port.latched(0).len();
+
+        lcd.set_cursor(3, 2).unwrap();
+        let nibbles: Vec<u32> = port.latched(0)[sent..]
+            .iter()
+            .map(|levels| levels >> 2 & 0x0F)
+            .collect();
+        // Set DDRAM address 0x56: the fourth row continues the second line at 0x54
+        assert_eq!(nibbles, [0xD, 0x6]);
+
+        assert!(matches!(
+            lcd.set_cursor(4, 0),
+            Err(DataBusError::AddressOutOfRange)
+        ));
+        assert!(matches!(
+            lcd.set_cursor(0, 20),
+            Err(DataBusError::AddressOutOfRange)
+        ));
+    }
+
+    #[test]
+    fn single_setting_commands_keep_other_settings() {
+        let port = Port::default();
+        let mut lcd = bus4_lcd(&port, Hd44780Config::new());
+        let sent = port.latched(0).len();
+
+        lcd.show_cursor(true).unwrap();
+        lcd.blink(true).unwrap();
+        lcd.display_on(false).unwrap();
+        lcd.set_font(CharacterFont::FiveByTen).unwrap();
+        lcd.set_autoscroll(true).unwrap();
+
+        assert_eq!(
+            bus4_bytes(&port, sent),
+            [(0, 0x0E), (0, 0x0F), (0, 0x0B), (0, 0x2C), (0, 0x07)]
+        );
+        assert!(matches!(lcd.current_display_control().blink, Blink::On));
+        assert!(matches!(
+            lcd.current_function_set().char_font,
+            CharacterFont::FiveByTen
+        ));
+    }
+
+    #[test]
+    fn define_char_restores_cursor_position() {
+        let port = Port::default();
+        let mut lcd = bus4_lcd(&port, Hd44780Config::new());
+        lcd.set_position(0x45).unwrap();
+        let sent = port.latched(0).len();
+
+        lcd.define_char(1, [0x1F; 8]).unwrap();
+
+        let mut expected = Vec::new();
+        expected.push((0, 0x48));
+        expected.extend([(1, 0x1F); 8]);
+        expected.push((0, 0xC5));
+        assert_eq!(bus4_bytes(&port, sent), expected);
+        assert_eq!(lcd.cursor_address(), 0x45);
+
+        lcd.write_byte(1).unwrap();
+        assert_eq!(lcd.cursor_address(), 0x46);
+    }
+
+    #[test]
+    fn leaving_two_line_mode_moves_cursor_back_into_range() {
+        let port = Port::default();
+        let mut lcd = bus4_lcd(&port, Hd44780Config::new());
+        lcd.set_position(0x60).unwrap();
+        let sent = port.latched(0).len();
+
+        lcd.set_num_lines(NumberOfDisplayLines::OneLine).unwrap();
+        assert_eq!(bus4_bytes(&port, sent), [(0, 0x20), (0, 0x80)]);
+        assert_eq!(lcd.cursor_address(), 0x00);
+
+        // Enough writes to pass the end of the address space
+        for _ in 0..0xA0 {
+            lcd.write_byte(b'x').unwrap();
+        }
+        assert_eq!(lcd.cursor_address(), 0x00);
+    }
+
+    #[test]
+    fn fmt_write_keeps_the_driver_error() {
+        use core::fmt::Write;
+
+        let port = Port::default();
+        let mut lcd = bus4_lcd(&port, Hd44780Config::new());
+        write!(lcd, "{}", 42).unwrap();
+        assert!(lcd.take_fmt_error().is_none());
+
+        port.fail(0);
+        assert!(write!(lcd, "{}", 42).is_err());
+        assert!(matches!(
+            lcd.take_fmt_error(),
+            Some(DataBusError::PinSetError(PinFault(0)))
+        ));
+        assert!(lcd.take_fmt_error().is_none());
+    }
+
+    #[test]
+    fn writer_adapter_keeps_the_driver_error() {
+        use core::fmt::Write;
+
+        let port = Port::default();
+        let mut lcd = bus4_lcd(&port, Hd44780Config::new());
+        let sent = port.latched(0).len();
+        let mut writer = lcd.writer();
+        write!(writer, "{:>3}", 7).unwrap();
+        assert!(writer.take_error().is_none());
+        assert_eq!(
+            bus4_bytes(&port, sent),
+            [
+                (1, u32::from(b' ')),
+                (1, u32::from(b' ')),
+                (1, u32::from(b'7'))
+            ]
+        );
+
+        port.fail(0);
+        assert!(write!(writer, "{}", 7).is_err());
+        assert!(matches!(
+            writer.take_error(),
+            Some(DataBusError::PinSetError(PinFault(0)))
+        ));
+    }
+
+    type DualBus4 = WriteOnlyDualBus4<Pin, Pin, Pin, Pin, Pin, Pin, Pin>;
+
+    /// 40x4 dual controller driver with E1 on bit 0, E2 on bit 1, RS on bit 2 and D4-D7 on bits 3-6,
+    /// showing the cursor.
+    fn dual_lcd(port: &Port) -> WriteOnlyDualHD44780<DualBus4, Delay> {
+        let config = Hd44780Config::new()
+            .geometry(Geometry::lcd_40x4())
+            .display_control(ShowDisplay::On, ShowCursor::On, Blink::Off);
+        WriteOnlyDualHD44780::new_dual_bus4(
+            port.pin(0),
+            port.pin(1),
+            port.pin(2),
+            port.pin(3),
+            port.pin(4),
+            port.pin(5),
+            port.pin(6),
+            Delay::default(),
+            config,
+        )
+        .unwrap()
+    }
+
+    /// Bytes latched by the controller enabled by `enable` from nibble `from` on, as (RS, byte).
+    fn dual_bytes(port: &Port, enable: u8, from: usize) -> Vec<(u32, u32)> {
+        port.latched(enable)[from..]
+            .chunks(2)
+            .map(|n| (n[0] >> 2 & 1, (n[0] >> 3 & 0x0F) << 4 | n[1] >> 3 & 0x0F))
+            .collect()
+    }
+
+    #[test]
+    fn dual_data_is_written_to_the_controller_with_the_cursor() {
+        let port = Port::default();
+        let mut lcd = dual_lcd(&port);
+        let (first, second) = (port.latched(0).len(), port.latched(1).len());
+
+        lcd.set_cursor(2, 1).unwrap();
+        lcd.write_str("ab").unwrap();
+        lcd.clear_display().unwrap();
+
+        // Only the controller with the cursor shows it
+        assert_eq!(
+            dual_bytes(&port, 0, first),
+            [(0, 0x0C), (0, 0x01), (0, 0x0E)]
+        );
+        assert_eq!(
+            dual_bytes(&port, 1, second),
+            [
+                (0, 0x81),
+                (0, 0x0E),
+                (1, u32::from(b'a')),
+                (1, u32::from(b'b')),
+                (0, 0x01),
+                (0, 0x0C),
+            ]
+        );
+        assert!(matches!(lcd.cursor_controller(), EnableSelect::First));
+    }
+
+    #[test]
+    fn dual_define_char_returns_both_controllers_to_ddram() {
+        let port = Port::default();
+        let mut lcd = dual_lcd(&port);
+        lcd.set_cursor(3, 4).unwrap();
+        let (first, second) = (port.latched(0).len(), port.latched(1).len());
+
+        lcd.define_char(2, [0x0A; 8]).unwrap();
+
+        let mut expected = Vec::new();
+        expected.push((0, 0x50));
+        expected.extend([(1, 0x0A); 8]);
+        expected.push((0, 0x80));
+        assert_eq!(dual_bytes(&port, 0, first), expected);
+        expected.pop();
+        expected.push((0, 0xC4));
+        assert_eq!(dual_bytes(&port, 1, second), expected);
+
+        // Data goes to the controller with the cursor alone again
+        let first = port.latched(0).len();
+        lcd.write_byte(2).unwrap();
+        assert_eq!(port.latched(0).len(), first);
+        assert_eq!(lcd.cursor_address(), 0x45);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn async_bus4_driver_awaits_the_same_transfers_and_delays() {
+        let port = Port::default();
+        let mut lcd = bus4_lcd(&port, Hd44780Config::new());
+        lcd.set_cursor(1, 2).unwrap();
+        lcd.write_str("Hi").unwrap();
+        let port_async = Port::default();
+        let mut lcd_async = block_on(AsyncWriteOnlyHD44780::new_bus4(
+            Hal1(port_async.pin(0)),
+            Hal1(port_async.pin(1)),
+            Hal1(port_async.pin(2)),
+            Hal1(port_async.pin(3)),
+            Hal1(port_async.pin(4)),
+            Hal1(port_async.pin(5)),
+            Hal1(Delay::default()),
+            Hd44780Config::new(),
+        ))
+        .unwrap();
+        block_on(lcd_async.set_cursor(1, 2)).unwrap();
+        block_on(lcd_async.write_str("Hi")).unwrap();
+
+        assert_eq!(port_async.latched(0), port.latched(0));
+        assert_eq!(lcd_async.cursor_address(), lcd.cursor_address());
+        assert_eq!((lcd_async.release().1).0.log, lcd.release().1.log);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn async_pcf8574_driver_awaits_the_same_i2c_writes() {
+        let i2c = I2c::default();
+        let mut lcd = WriteOnlyHD44780::new_pcf8574(
+            i2c.clone(),
+            0x27,
+            Pcf8574Mapping::common(),
+            Delay::default(),
+            Hd44780Config::new(),
+        )
+        .unwrap();
+        lcd.write_str("Hi").unwrap();
+        lcd.set_backlight(false).unwrap();
+        let i2c_async = I2c::default();
+        let mut lcd_async = block_on(AsyncWriteOnlyHD44780::new_pcf8574(
+            Hal1(i2c_async.clone()),
+            0x27,
+            Pcf8574Mapping::common(),
+            Hal1(Delay::default()),
+            Hd44780Config::new(),
+        ))
+        .unwrap();
+        block_on(lcd_async.write_str("Hi")).unwrap();
+        block_on(lcd_async.set_backlight(false)).unwrap();
+
+        assert_eq!(i2c_async.writes(), i2c.writes());
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn async_serial_i2c_driver_sends_one_byte_per_transaction_in_fast_mode() {
+        let i2c = I2c::default();
+        let mut lcd = block_on(AsyncWriteOnlyHD44780::new_serial_i2c(
+            Hal1(i2c.clone()),
+            0x3E,
+            I2cSpeed::Fast,
+            Hal1(Delay::default()),
+            Hd44780Config::new(),
+        ))
+        .unwrap();
+        let from = i2c.writes().len();
+
+        block_on(lcd.write_str("Hi")).unwrap();
+
+        assert_eq!(
+            i2c.writes()[from..],
+            [(0x3E, std::vec![0x40, b'H']), (0x3E, std::vec![0x40, b'i'])]
+        );
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn async_serial_spi_device_frames_each_byte_with_its_chip_select() {
+        let port = Port::default();
+        let spi = Spi::on(&port);
+        WriteOnlyHD44780::new_serial_spi(
+            spi.clone(),
+            port.pin(0),
+            port.pin(1),
+            Delay::default(),
+            Hd44780Config::new(),
+        )
+        .unwrap();
+        let port_async = Port::default();
+        let spi_async = Spi::on(&port_async);
+        block_on(AsyncWriteOnlyHD44780::new_serial_spi_device(
+            Hal1((spi_async.clone(), port_async.pin(0))),
+            Hal1(port_async.pin(1)),
+            Hal1(Delay::default()),
+            Hd44780Config::new(),
+        ))
+        .unwrap();
+
+        assert_eq!(spi_async.transfers(), spi.transfers());
+        assert_eq!(port_async.levels(), port.levels());
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn async_shift595_device_is_latched_by_its_chip_select() {
+        let port = Port::default();
+        let spi = Spi::on(&port);
+        WriteOnlyHD44780::new_shift595(
+            spi.clone(),
+            port.pin(0),
+            Shift595Mapping::adafruit_backpack(),
+            Delay::default(),
+            Hd44780Config::new(),
+        )
+        .unwrap();
+        let port_async = Port::default();
+        let spi_async = Spi::on(&port_async);
+        let mut lcd_async = block_on(AsyncWriteOnlyHD44780::new_shift595_device(
+            Hal1((spi_async.clone(), port_async.pin(0))),
+            Shift595Mapping::adafruit_backpack(),
+            Hal1(Delay::default()),
+            Hd44780Config::new(),
+        ))
+        .unwrap();
+
+        // The device chip select rises after every byte, like the latch pin of the blocking bus
+        assert_eq!(spi_async.transfers(), spi.transfers());
+        assert_eq!(port_async.latched(0).len(), port.latched(0).len());
+        assert_eq!(port_async.levels(), port.levels());
+
+        block_on(lcd_async.set_backlight(false)).unwrap();
+        assert_eq!(spi_async.transfers().last().unwrap().1, [0x00]);
+    }
+}
@@ -0,0 +1,467 @@
+//! Test doubles recording what the buses drive onto the pins.
+
+extern crate std;
+
+use crate::data_bus::pins::IoPin;
+use embedded_hal as hal;
+#[cfg(feature = "hal-1")]
+use embedded_hal_1 as hal_1;
+use hal::blocking::delay::{DelayMs, DelayUs};
+use hal::blocking::{i2c, spi};
+use hal::digital::v2::OutputPin;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::vec::Vec;
+
+/// Output latch shared by a group of mock pins, one bit per pin. The levels are recorded after every
+/// change.
+#[derive(Clone, Default)]
+pub struct Port {
+    history: Rc<RefCell<Vec<u32>>>,
+    inputs: Rc<RefCell<u32>>,
+    device: Rc<RefCell<u32>>,
+    faults: Rc<RefCell<u32>>,
+}
+
+impl Port {
+    /// Pin driving bit `bit` of the port.
+    pub fn pin(&self, bit: u8) -> Pin {
+        Pin {
+            port: self.clone(),
+            bit,
+        }
+    }
+
+    /// Pin on bit `bit` of the port that fails every time it is set.
+    pub fn faulty_pin(&self, bit: u8) -> Pin {
+        self.fail(bit);
+        self.pin(bit)
+    }
+
+    /// Make the output pin on bit `bit` fail every time it is set from now on.
+    pub fn fail(&self, bit: u8) {
+        *self.faults.borrow_mut() |= 1 << bit;
+    }
+
+    /// Bidirectional pin on bit `bit` of the port, reading the levels set with `drive`.
+    pub fn io_pin(&self, bit: u8) -> BidirectionalPin {
+        BidirectionalPin {
+            port: self.clone(),
+            bit,
+        }
+    }
+
+    /// Levels driven by the device onto the pins configured as inputs.
+    pub fn drive(&self, levels: u32) {
+        *self.device.borrow_mut() = levels;
+    }
+
+    /// Pins currently configured as inputs, one bit per pin.
+    pub fn inputs(&self) -> u32 {
+        *self.inputs.borrow()
+    }
+
+    /// Current levels of all pins.
+    pub fn levels(&self) -> u32 {
+        self.history.borrow().last().copied().unwrap_or(0)
+    }
+
+    /// Levels of all pins at each falling edge of `bit`, as latched by a device clocked on that
+    /// edge.
+    pub fn latched(&self, bit: u8) -> Vec<u32> {
+        let mut latched = Vec::new();
+        let mut previous = 0;
+        for levels in self.history.borrow().iter() {
+            if previous & (1 << bit) != 0 && levels & (1 << bit) == 0 {
+                latched.push(previous);
+            }
+            previous = *levels;
+        }
+        latched
+    }
+
+    fn set(&self, bit: u8, high: bool) {
+        let levels = self.levels();
+        let levels = if high {
+            levels | 1 << bit
+        } else {
+            levels & !(1 << bit)
+        };
+        self.history.borrow_mut().push(levels);
+    }
+}
+
+/// Error of a faulty mock pin.
+#[derive(Debug, PartialEq)]
+pub struct PinFault(pub u8);
+
+/// Output pin of a mock port.
+pub struct Pin {
+    port: Port,
+    bit: u8,
+}
+
+impl Pin {
+    fn set(&mut self, high: bool) -> Result<(), PinFault> {
+        if *self.port.faults.borrow() & (1 << self.bit) != 0 {
+            return Err(PinFault(self.bit));
+        }
+        self.port.set(self.bit, high);
+        Ok(())
+    }
+}
+
+impl OutputPin for Pin {
+    type Error = PinFault;
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.set(false)
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.set(true)
+    }
+}
+
+/// Delay recording each pause, in microseconds.
+#[derive(Default)]
+pub struct Delay {
+    pub log: Vec<u32>,
+}
+
+impl DelayMs<u16> for Delay {
+    fn delay_ms(&mut self, ms: u16) {
+        self.log.push(u32::from(ms) * 1000);
+    }
+}
+
+impl DelayUs<u16> for Delay {
+    fn delay_us(&mut self, us: u16) {
+        self.log.push(us.into());
+    }
+}
+
+/// Bidirectional pin of a mock port. Reads return the levels driven by the device.
+pub struct BidirectionalPin {
+    port: Port,
+    bit: u8,
+}
+
+impl IoPin for BidirectionalPin {
+    type Error = PinFault;
+
+    fn set_as_output(&mut self) -> Result<(), Self::Error> {
+        *self.port.inputs.borrow_mut() &= !(1 << self.bit);
+        Ok(())
+    }
+
+    fn set_as_input(&mut self) -> Result<(), Self::Error> {
+        *self.port.inputs.borrow_mut() |= 1 << self.bit;
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.port.set(self.bit, true);
+        Ok(())
+    }
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.port.set(self.bit, false);
+        Ok(())
+    }
+
+    fn is_high(&self) -> Result<bool, Self::Error> {
+        Ok(*self.port.device.borrow() & (1 << self.bit) != 0)
+    }
+}
+
+/// Transfer made on a mock I2C bus.
+#[derive(Clone, Debug, PartialEq)]
+pub enum I2cOp {
+    /// Bytes written to an address.
+    Write(u8, Vec<u8>),
+    /// Number of bytes read from an address.
+    Read(u8, usize),
+}
+
+/// I2C peripheral recording every transfer. Reads return the bytes queued with `respond`, then
+/// zeros.
+#[derive(Clone, Default)]
+pub struct I2c {
+    ops: Rc<RefCell<Vec<I2cOp>>>,
+    responses: Rc<RefCell<VecDeque<u8>>>,
+}
+
+impl I2c {
+    /// Transfers made so far.
+    pub fn ops(&self) -> Vec<I2cOp> {
+        self.ops.borrow().clone()
+    }
+
+    /// Writes made so far, as (address, bytes).
+    pub fn writes(&self) -> Vec<(u8, Vec<u8>)> {
+        self.ops
+            .borrow()
+            .iter()
+            .filter_map(|op| match op {
+                I2cOp::Write(address, bytes) => Some((*address, bytes.clone())),
+                I2cOp::Read(..) => None,
+            })
+            .collect()
+    }
+
+    /// Queue bytes to be returned by the following reads.
+    pub fn respond(&self, bytes: &[u8]) {
+        self.responses.borrow_mut().extend(bytes);
+    }
+
+    /// Bytes written to a controller with a native serial interface, each paired with the control
+    /// byte of its transfer.
+    pub fn serial_bytes(&self) -> Vec<(u8, u8)> {
+        self.writes()
+            .iter()
+            .flat_map(|(_, bytes)| bytes[1..].iter().map(move |byte| (bytes[0], *byte)))
+            .collect()
+    }
+}
+
+impl i2c::Write for I2c {
+    type Error = ();
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.ops
+            .borrow_mut()
+            .push(I2cOp::Write(address, bytes.to_vec()));
+        Ok(())
+    }
+}
+
+impl i2c::Read for I2c {
+    type Error = ();
+
+    fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.ops
+            .borrow_mut()
+            .push(I2cOp::Read(address, buffer.len()));
+        for byte in buffer.iter_mut() {
+            *byte = self.responses.borrow_mut().pop_front().unwrap_or(0);
+        }
+        Ok(())
+    }
+}
+
+impl i2c::WriteRead for I2c {
+    type Error = ();
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        i2c::Write::write(self, address, bytes)?;
+        i2c::Read::read(self, address, buffer)
+    }
+}
+
+/// SPI peripheral recording every transfer along with the levels of `port` at the time, so the
+/// state of chip select or latch pins can be checked. Transfers return the bytes queued with
+/// `respond`, then zeros.
+#[derive(Clone, Default)]
+pub struct Spi {
+    port: Port,
+    transfers: Rc<RefCell<Vec<(u32, Vec<u8>)>>>,
+    responses: Rc<RefCell<VecDeque<u8>>>,
+}
+
+impl Spi {
+    /// SPI peripheral sharing the bus with the pins of `port`.
+    pub fn on(port: &Port) -> Spi {
+        Spi {
+            port: port.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Transfers made so far, as (port levels, bytes sent).
+    pub fn transfers(&self) -> Vec<(u32, Vec<u8>)> {
+        self.transfers.borrow().clone()
+    }
+
+    /// Queue bytes to be returned by the following transfers.
+    pub fn respond(&self, bytes: &[u8]) {
+        self.responses.borrow_mut().extend(bytes);
+    }
+}
+
+impl spi::Write<u8> for Spi {
+    type Error = ();
+
+    fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+        self.transfers
+            .borrow_mut()
+            .push((self.port.levels(), words.to_vec()));
+        Ok(())
+    }
+}
+
+impl spi::Transfer<u8> for Spi {
+    type Error = ();
+
+    fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], Self::Error> {
+        spi::Write::write(self, words)?;
+        for word in words.iter_mut() {
+            *word = self.responses.borrow_mut().pop_front().unwrap_or(0);
+        }
+        Ok(words)
+    }
+}
+
+/// embedded-hal 1.0 view of a mock, for the buses and drivers built on the 1.0 traits. An SPI
+/// device is formed by an SPI peripheral and the pin of its chip select.
+#[cfg(feature = "hal-1")]
+pub struct Hal1<T>(pub T);
+
+#[cfg(feature = "hal-1")]
+impl hal_1::digital::Error for PinFault {
+    fn kind(&self) -> hal_1::digital::ErrorKind {
+        hal_1::digital::ErrorKind::Other
+    }
+}
+
+#[cfg(feature = "hal-1")]
+impl hal_1::digital::ErrorType for Hal1<Pin> {
+    type Error = PinFault;
+}
+
+#[cfg(feature = "hal-1")]
+impl hal_1::digital::OutputPin for Hal1<Pin> {
+    fn set_low(&mut self) -> Result<(), PinFault> {
+        self.0.set(false)
+    }
+
+    fn set_high(&mut self) -> Result<(), PinFault> {
+        self.0.set(true)
+    }
+}
+
+#[cfg(feature = "hal-1")]
+impl hal_1::delay::DelayNs for Hal1<Delay> {
+    fn delay_ns(&mut self, ns: u32) {
+        self.0.log.push(ns / 1000);
+    }
+
+    fn delay_us(&mut self, us: u32) {
+        self.0.log.push(us);
+    }
+
+    fn delay_ms(&mut self, ms: u32) {
+        self.0.log.push(ms * 1000);
+    }
+}
+
+#[cfg(feature = "hal-1")]
+impl hal_1::i2c::ErrorType for Hal1<I2c> {
+    type Error = hal_1::i2c::ErrorKind;
+}
+
+#[cfg(feature = "hal-1")]
+impl hal_1::i2c::I2c for Hal1<I2c> {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [hal_1::i2c::Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        for operation in operations {
+            match operation {
+                hal_1::i2c::Operation::Write(bytes) => {
+                    i2c::Write::write(&mut self.0, address, bytes)
+                }
+                hal_1::i2c::Operation::Read(buffer) => {
+                    i2c::Read::read(&mut self.0, address, buffer)
+                }
+            }
+            .map_err(|_| hal_1::i2c::ErrorKind::Other)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "hal-1")]
+impl hal_1::spi::ErrorType for Hal1<(Spi, Pin)> {
+    type Error = hal_1::spi::ErrorKind;
+}
+
+#[cfg(feature = "hal-1")]
+impl hal_1::spi::SpiDevice for Hal1<(Spi, Pin)> {
+    fn transaction(
+        &mut self,
+        operations: &mut [hal_1::spi::Operation<'_, u8>],
+    ) -> Result<(), Self::Error> {
+        let (spi, cs) = &mut self.0;
+        cs.set(false)
+            .map_err(|_| hal_1::spi::ErrorKind::ChipSelectFault)?;
+        for operation in operations {
+            match operation {
+                hal_1::spi::Operation::Write(words) => spi::Write::write(spi, words),
+                hal_1::spi::Operation::TransferInPlace(words) => {
+                    spi::Transfer::transfer(spi, words).map(|_| ())
+                }
+                _ => unimplemented!(),
+            }
+            .map_err(|_| hal_1::spi::ErrorKind::Other)?;
+        }
+        cs.set(true)
+            .map_err(|_| hal_1::spi::ErrorKind::ChipSelectFault)
+    }
+}
+
+/// Run a future of the async buses or driver to completion. The mocks never wait, so the future is
+/// ready as soon as it has been polled through.
+#[cfg(feature = "async")]
+pub fn block_on<F: core::future::Future>(future: F) -> F::Output {
+    let mut future = core::pin::pin!(future);
+    let mut context = core::task::Context::from_waker(core::task::Waker::noop());
+    loop {
+        if let core::task::Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_async::delay::DelayNs for Hal1<Delay> {
+    async fn delay_ns(&mut self, ns: u32) {
+        hal_1::delay::DelayNs::delay_ns(self, ns)
+    }
+
+    async fn delay_us(&mut self, us: u32) {
+        hal_1::delay::DelayNs::delay_us(self, us)
+    }
+
+    async fn delay_ms(&mut self, ms: u32) {
+        hal_1::delay::DelayNs::delay_ms(self, ms)
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_async::i2c::I2c for Hal1<I2c> {
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [hal_1::i2c::Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        hal_1::i2c::I2c::transaction(self, address, operations)
+    }
+}
+
+#[cfg(feature = "async")]
+impl embedded_hal_async::spi::SpiDevice for Hal1<(Spi, Pin)> {
+    async fn transaction(
+        &mut self,
+        operations: &mut [hal_1::spi::Operation<'_, u8>],
+    ) -> Result<(), Self::Error> {
+        hal_1::spi::SpiDevice::transaction(self, operations)
+    }
+}
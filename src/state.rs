@@ -3,7 +3,8 @@
 
 use crate::instructions::*;
 
-#[derive(Default)]
+/// Cursor move direction and display shift settings.
+#[derive(Default, Copy, Clone)]
 pub struct EntryMode {
     pub cursor_direction: IncrementDecrement,
     pub display_shift: AccompaniesDisplayShift,
@@ -11,13 +12,14 @@ pub struct EntryMode {
 
 impl EntryMode {
     pub fn as_byte(&self) -> u8 {
-        let dir = (*(&self.cursor_direction) as u8) << 1;
-        let shift = (*(&self.display_shift) as u8) << 0;
+        let dir = (self.cursor_direction as u8) << 1;
+        let shift = self.display_shift as u8;
         dir | shift
     }
 }
 
-#[derive(Default)]
+/// Display, cursor and blink settings.
+#[derive(Default, Copy, Clone)]
 pub struct DisplayControl {
     pub display: ShowDisplay,
     pub cursor: ShowCursor,
@@ -26,14 +28,15 @@ pub struct DisplayControl {
 
 impl DisplayControl {
     pub fn as_byte(&self) -> u8 {
-        let d = (*(&self.display) as u8) << 2;
-        let c = (*(&self.cursor) as u8) << 1;
-        let b = (*(&self.blink) as u8) << 0;
+        let d = (self.display as u8) << 2;
+        let c = (self.cursor as u8) << 1;
+        let b = self.blink as u8;
         d | c | b
     }
 }
 
-#[derive(Default)]
+/// Last cursor or display shift performed.
+#[derive(Default, Copy, Clone)]
 pub struct DisplayShift {
     pub shift_type: ShiftType,
     pub shift_direction: ShiftDirection,
@@ -41,13 +44,14 @@ pub struct DisplayShift {
 
 impl DisplayShift {
     pub fn as_byte(&self) -> u8 {
-        let sc = (*(&self.shift_type) as u8) << 3;
-        let rl = (*(&self.shift_direction) as u8) << 2;
+        let sc = (self.shift_type as u8) << 3;
+        let rl = (self.shift_direction as u8) << 2;
         sc | rl
     }
 }
 
-#[derive(Default)]
+/// Interface data length, number of display lines and character font settings.
+#[derive(Default, Copy, Clone)]
 pub struct FunctionSet {
     pub data_length: DataLength,
     pub num_lines: NumberOfDisplayLines,
@@ -56,9 +60,204 @@ pub struct FunctionSet {
 
 impl FunctionSet {
     pub fn as_byte(&self) -> u8 {
-        let dl = (*(&self.data_length) as u8) << 4;
-        let nl = (*(&self.num_lines) as u8) << 3;
-        let cf = (*(&self.char_font) as u8) << 2;
+        let dl = (self.data_length as u8) << 4;
+        let nl = match self.num_lines {
+            NumberOfDisplayLines::OneLine => 0,
+            NumberOfDisplayLines::TwoLines | NumberOfDisplayLines::FourLines => 1 << 3,
+        };
+        let cf = (self.char_font as u8) << 2;
         dl | nl | cf
     }
+
+    /// Function set operands selecting instruction `table`, for ST7032-family controllers which
+    /// use the otherwise unused lowest bit (IS).
+    pub fn as_byte_with_table(&self, table: InstructionTable) -> u8 {
+        self.as_byte() | table as u8
+    }
+
+    /// Whether `address` is a DDRAM address that exists in the current display line mode.
+    pub fn is_valid_ddram_address(&self, address: u8) -> bool {
+        match self.num_lines {
+            NumberOfDisplayLines::OneLine => address <= 0x4F,
+            NumberOfDisplayLines::TwoLines => address <= 0x27 || (0x40..=0x67).contains(&address),
+            NumberOfDisplayLines::FourLines => address <= 0x73 && address & 0x1F <= 0x13,
+        }
+    }
+}
+
+/// Extended function set (RE = 1) of KS0073 and SSD1803A-family controllers: font width, black and
+/// white inverting cursor and four-line mode.
+#[derive(Default, Copy, Clone)]
+pub struct ExtendedFunctionSet {
+    pub font_width: FontWidth,
+    pub inverting_cursor: bool,
+    pub four_lines: bool,
+}
+
+impl ExtendedFunctionSet {
+    pub fn as_byte(&self) -> u8 {
+        let fw = (self.font_width as u8) << 2;
+        let bw = (self.inverting_cursor as u8) << 1;
+        let nw = self.four_lines as u8;
+        fw | bw | nw
+    }
+}
+
+/// Address counter, tracked in software so the cursor position can be restored without reading it
+/// back from the device.
+#[derive(Default, Copy, Clone)]
+pub struct AddressCounter {
+    pub ddram_address: u8,
+    pub cgram_selected: bool,
+}
+
+impl AddressCounter {
+    /// Move to the next DDRAM address after a data read or write or a cursor shift, wrapping in the
+    /// same way as the device. Addresses that do not exist in the current line mode wrap to the
+    /// nearest end of the valid range. Has no effect while CGRAM is selected.
+    pub fn step(&mut self, direction: IncrementDecrement, num_lines: NumberOfDisplayLines) {
+        if self.cgram_selected {
+            return;
+        }
+        let address = self.ddram_address;
+        self.ddram_address = match (num_lines, direction) {
+            (NumberOfDisplayLines::OneLine, IncrementDecrement::Increment) => match address {
+                0x00..=0x4E => address + 1,
+                _ => 0x00,
+            },
+            (NumberOfDisplayLines::OneLine, IncrementDecrement::Decrement) => match address {
+                0x01..=0x4F => address - 1,
+                _ => 0x4F,
+            },
+            (NumberOfDisplayLines::TwoLines, IncrementDecrement::Increment) => match address {
+                0x00..=0x26 | 0x40..=0x66 => address + 1,
+                0x27..=0x3F => 0x40,
+                _ => 0x00,
+            },
+            (NumberOfDisplayLines::TwoLines, IncrementDecrement::Decrement) => match address {
+                0x01..=0x27 | 0x41..=0x67 => address - 1,
+                0x28..=0x40 => 0x27,
+                _ => 0x67,
+            },
+            (NumberOfDisplayLines::FourLines, IncrementDecrement::Increment) => match address {
+                0x00..=0x12 | 0x20..=0x32 | 0x40..=0x52 | 0x60..=0x72 => address + 1,
+                0x13..=0x1F => 0x20,
+                0x33..=0x3F => 0x40,
+                0x53..=0x5F => 0x60,
+                _ => 0x00,
+            },
+            (NumberOfDisplayLines::FourLines, IncrementDecrement::Decrement) => match address {
+                0x01..=0x13 | 0x21..=0x33 | 0x41..=0x53 | 0x61..=0x73 => address - 1,
+                0x14..=0x20 => 0x13,
+                0x34..=0x40 => 0x33,
+                0x54..=0x60 => 0x53,
+                _ => 0x73,
+            },
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stepped(address: u8, direction: IncrementDecrement, lines: NumberOfDisplayLines) -> u8 {
+        let mut counter = AddressCounter {
+            ddram_address: address,
+            cgram_selected: false,
+        };
+        counter.step(direction, lines);
+        counter.ddram_address
+    }
+
+    fn function_set(num_lines: NumberOfDisplayLines) -> FunctionSet {
+        FunctionSet {
+            num_lines,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn valid_ddram_addresses_follow_line_mode() {
+        let one = function_set(NumberOfDisplayLines::OneLine);
+        assert!(one.is_valid_ddram_address(0x4F));
+        assert!(!one.is_valid_ddram_address(0x50));
+
+        let two = function_set(NumberOfDisplayLines::TwoLines);
+        assert!(two.is_valid_ddram_address(0x27));
+        assert!(!two.is_valid_ddram_address(0x28));
+        assert!(!two.is_valid_ddram_address(0x3F));
+        assert!(two.is_valid_ddram_address(0x40));
+        assert!(two.is_valid_ddram_address(0x67));
+        assert!(!two.is_valid_ddram_address(0x68));
+
+        let four = function_set(NumberOfDisplayLines::FourLines);
+        assert!(four.is_valid_ddram_address(0x13));
+        assert!(!four.is_valid_ddram_address(0x14));
+        assert!(four.is_valid_ddram_address(0x60));
+        assert!(four.is_valid_ddram_address(0x73));
+        assert!(!four.is_valid_ddram_address(0x74));
+    }
+
+    #[test]
+    fn one_line_wraps_at_end_of_ddram() {
+        let lines = NumberOfDisplayLines::OneLine;
+        assert_eq!(stepped(0x4E, IncrementDecrement::Increment, lines), 0x4F);
+        assert_eq!(stepped(0x4F, IncrementDecrement::Increment, lines), 0x00);
+        assert_eq!(stepped(0x27, IncrementDecrement::Increment, lines), 0x28);
+        assert_eq!(stepped(0x00, IncrementDecrement::Decrement, lines), 0x4F);
+        assert_eq!(stepped(0x10, IncrementDecrement::Decrement, lines), 0x0F);
+    }
+
+    #[test]
+    fn two_lines_wrap_between_lines() {
+        let lines = NumberOfDisplayLines::TwoLines;
+        assert_eq!(stepped(0x27, IncrementDecrement::Increment, lines), 0x40);
+        assert_eq!(stepped(0x67, IncrementDecrement::Increment, lines), 0x00);
+        assert_eq!(stepped(0x40, IncrementDecrement::Decrement, lines), 0x27);
+        assert_eq!(stepped(0x00, IncrementDecrement::Decrement, lines), 0x67);
+    }
+
+    #[test]
+    fn four_lines_wrap_between_lines() {
+        let lines = NumberOfDisplayLines::FourLines;
+        assert_eq!(stepped(0x13, IncrementDecrement::Increment, lines), 0x20);
+        assert_eq!(stepped(0x53, IncrementDecrement::Increment, lines), 0x60);
+        assert_eq!(stepped(0x73, IncrementDecrement::Increment, lines), 0x00);
+        assert_eq!(stepped(0x40, IncrementDecrement::Decrement, lines), 0x33);
+        assert_eq!(stepped(0x00, IncrementDecrement::Decrement, lines), 0x73);
+    }
+
+    #[test]
+    fn addresses_outside_line_mode_wrap_into_range() {
+        let one = NumberOfDisplayLines::OneLine;
+        assert_eq!(stepped(0x60, IncrementDecrement::Increment, one), 0x00);
+        assert_eq!(stepped(0xFF, IncrementDecrement::Increment, one), 0x00);
+        assert_eq!(stepped(0x60, IncrementDecrement::Decrement, one), 0x4F);
+
+        let two = NumberOfDisplayLines::TwoLines;
+        assert_eq!(stepped(0x30, IncrementDecrement::Increment, two), 0x40);
+        assert_eq!(stepped(0xFF, IncrementDecrement::Increment, two), 0x00);
+        assert_eq!(stepped(0x30, IncrementDecrement::Decrement, two), 0x27);
+        assert_eq!(stepped(0xFF, IncrementDecrement::Decrement, two), 0x67);
+
+        let four = NumberOfDisplayLines::FourLines;
+        assert_eq!(stepped(0x18, IncrementDecrement::Increment, four), 0x20);
+        assert_eq!(stepped(0xFF, IncrementDecrement::Increment, four), 0x00);
+        assert_eq!(stepped(0x38, IncrementDecrement::Decrement, four), 0x33);
+        assert_eq!(stepped(0xFF, IncrementDecrement::Decrement, four), 0x73);
+    }
+
+    #[test]
+    fn cgram_selected_does_not_step() {
+        let mut counter = AddressCounter {
+            ddram_address: 0x05,
+            cgram_selected: true,
+        };
+        counter.step(
+            IncrementDecrement::Increment,
+            NumberOfDisplayLines::TwoLines,
+        );
+        assert_eq!(counter.ddram_address, 0x05);
+    }
 }
@@ -0,0 +1,88 @@
+//! Timing characteristics of the device. All values are minimums, rounded up to the resolution of
+//! the delay used (1 µs, except for the power-on wait).
+//!
+//! Datasheet values are given for the nominal 270 kHz oscillator; devices running at a lower supply
+//! voltage or clones with a slower internal oscillator require proportionally longer waits.
+
+/// Timing profile consulted by the data buses (enable pulse, address setup) and the drivers
+/// (instruction execution times, power-on and reset waits).
+#[derive(Copy, Clone, Debug)]
+pub struct Timing {
+    /// Width of the enable pulse (PWEH), also used as data delay time when reading.
+    pub enable_pulse_us: u16,
+
+    /// Address setup time (tAS) between RS/RW changing and the start of the enable pulse.
+    pub address_setup_us: u16,
+
+    /// Execution time of clear display and return home instructions.
+    pub clear_home_us: u16,
+
+    /// Execution time of all other instructions and of data writes.
+    pub execution_us: u16,
+
+    /// Wait after the first function set during initialization by instruction.
+    pub reset_wait_us: u16,
+
+    /// Wait after power on before the first instruction can be sent.
+    pub power_on_ms: u16,
+}
+
+impl Timing {
+    /// Datasheet timing for a 5 V device (VCC = 4.5 to 5.5 V).
+    pub const fn five_volt() -> Self {
+        Timing {
+            enable_pulse_us: 1,
+            address_setup_us: 1,
+            clear_home_us: 1520,
+            execution_us: 37,
+            reset_wait_us: 4100,
+            power_on_ms: 15,
+        }
+    }
+
+    /// Datasheet timing for a 3.3 V device (VCC = 2.7 to 4.5 V). The oscillator is slower at lower
+    /// supply voltages so execution times are scaled accordingly.
+    pub const fn three_volt() -> Self {
+        Timing {
+            enable_pulse_us: 1,
+            address_setup_us: 1,
+            clear_home_us: 2160,
+            execution_us: 53,
+            reset_wait_us: 4500,
+            power_on_ms: 40,
+        }
+    }
+
+    /// Conservative timing for clones and compatible controllers that run considerably slower than
+    /// the datasheet figures (e.g. some KS0066 and SPLC780 parts).
+    pub const fn slow() -> Self {
+        Timing {
+            enable_pulse_us: 2,
+            address_setup_us: 1,
+            clear_home_us: 4000,
+            execution_us: 120,
+            reset_wait_us: 5000,
+            power_on_ms: 100,
+        }
+    }
+
+    /// Timing for HD44780-compatible VFD modules (PT6314, Noritake CU-series). The controller is
+    /// fast, but its reset is held until the high voltage converter has started, so the power-on
+    /// wait is much longer than for an LCD.
+    pub const fn vfd() -> Self {
+        Timing {
+            enable_pulse_us: 1,
+            address_setup_us: 1,
+            clear_home_us: 2000,
+            execution_us: 40,
+            reset_wait_us: 4100,
+            power_on_ms: 260,
+        }
+    }
+}
+
+impl Default for Timing {
+    fn default() -> Self {
+        Timing::five_volt()
+    }
+}